@@ -3,6 +3,7 @@ const allowedCommands: Set<string> = new Set([
     'media_info',
     'media_size',
     'media_download',
+    'media_stop_recording',
     'media_fatal',
     'media_diagnostic',
     'navigate',
@@ -22,6 +23,33 @@ const allowedCommands: Set<string> = new Set([
     'preferences_get',
     'preferences_reset',
     'group_cancel',
+    'group_pause',
+    'group_resume',
+    'media_pause',
+    'media_resume',
+    'queue_list',
+    'queue_scheduled',
+    'archive_list',
+    'archive_remove',
+    'archive_import',
+    'archive_export',
+    'history_query',
+    'history_open',
+    'history_reveal',
+    'history_redownload',
+    'subscription_list',
+    'subscription_add',
+    'subscription_remove',
+    'subscription_check',
+    'preset_list',
+    'preset_save',
+    'preset_remove',
+    'preset_import',
+    'preset_export',
+    'proxy_status',
+    'proxy_check',
+    'config_resolve_site',
+    'import_file',
     'logging_subscribe',
     'logging_unsubscribe',
     'stronghold_init',
@@ -29,6 +57,11 @@ const allowedCommands: Set<string> = new Set([
     'stronghold_keys',
     'stronghold_get',
     'stronghold_set',
+    'credentials_list',
+    'credentials_create',
+    'credentials_delete',
+    'remote_token',
+    'remote_rotate',
     'get_platform',
     'notify',
     'plugin:shell|open',
@@ -0,0 +1,4 @@
+fn main() {
+  let code = open_video_downloader_lib::run_headless(std::env::args().skip(1));
+  std::process::exit(code);
+}
@@ -13,7 +13,7 @@ use indexmap::IndexMap;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tauri::{AppHandle, Emitter, Error, Manager, Wry};
+use tauri::{AppHandle, Emitter, Error, Manager, Runtime, Wry};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
@@ -107,14 +107,14 @@ struct ToolComplete {
   tool: String,
 }
 
-pub struct BinariesManager {
-  app: AppHandle<Wry>,
+pub struct BinariesManager<R: Runtime = Wry> {
+  app: AppHandle<R>,
   bin_dir: PathBuf,
   client: Client,
 }
 
-impl BinariesManager {
-  pub fn new(app: &AppHandle<Wry>) -> Self {
+impl<R: Runtime> BinariesManager<R> {
+  pub fn new(app: &AppHandle<R>) -> Self {
     let paths_manager = app.state::<PathsManager>();
     let client = Client::builder()
       .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
@@ -1,3 +1,4 @@
+use crate::launch::flush_pending_launches;
 use crate::SharedConfig;
 use std::env::args;
 use tauri::{AppHandle, Manager, State};
@@ -6,6 +7,8 @@ use tauri::{AppHandle, Manager, State};
 pub fn app_ready(app: AppHandle, cfg_handle: State<'_, SharedConfig>) {
   let args: Vec<String> = args().collect();
   let is_autostart = args.contains(&"--auto-start".to_string());
+  flush_pending_launches(&app);
+
   let cfg = cfg_handle.load();
   if cfg.system.auto_start_minimised && is_autostart {
     return;
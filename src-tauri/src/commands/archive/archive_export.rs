@@ -0,0 +1,11 @@
+use crate::state::archive::DownloadArchive;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Writes the archive as a yt-dlp archive file and returns the number of entries.
+#[tauri::command]
+pub fn archive_export(path: String, archive: State<'_, DownloadArchive>) -> Result<usize, String> {
+  archive
+    .export(&PathBuf::from(path))
+    .map_err(|e| format!("Failed to export download archive: {e}"))
+}
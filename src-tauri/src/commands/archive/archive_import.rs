@@ -0,0 +1,11 @@
+use crate::state::archive::DownloadArchive;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Merges a yt-dlp archive file into the app's archive and returns the number of new entries.
+#[tauri::command]
+pub fn archive_import(path: String, archive: State<'_, DownloadArchive>) -> Result<usize, String> {
+  archive
+    .import(&PathBuf::from(path))
+    .map_err(|e| format!("Failed to import download archive: {e}"))
+}
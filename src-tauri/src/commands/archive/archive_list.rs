@@ -0,0 +1,18 @@
+use crate::state::archive::{ArchivePage, DownloadArchive};
+use tauri::State;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+#[tauri::command]
+pub fn archive_list(
+  query: Option<String>,
+  offset: Option<usize>,
+  limit: Option<usize>,
+  archive: State<'_, DownloadArchive>,
+) -> ArchivePage {
+  archive.search(
+    query.as_deref(),
+    offset.unwrap_or(0),
+    limit.unwrap_or(DEFAULT_PAGE_SIZE),
+  )
+}
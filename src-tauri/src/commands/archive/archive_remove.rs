@@ -0,0 +1,11 @@
+use crate::state::archive::DownloadArchive;
+use tauri::State;
+
+#[tauri::command]
+pub fn archive_remove(key: String, archive: State<'_, DownloadArchive>) -> Result<(), String> {
+  match archive.remove(&key) {
+    Ok(true) => Ok(()),
+    Ok(false) => Err(format!("{key} is not in the download archive")),
+    Err(e) => Err(format!("Failed to update download archive: {e}")),
+  }
+}
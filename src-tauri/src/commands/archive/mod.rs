@@ -0,0 +1,9 @@
+pub mod archive_export;
+pub mod archive_import;
+pub mod archive_list;
+pub mod archive_remove;
+
+pub use archive_export::*;
+pub use archive_import::*;
+pub use archive_list::*;
+pub use archive_remove::*;
@@ -0,0 +1,9 @@
+use crate::runners::site_rules::{resolve_site_settings, SiteSettings};
+use crate::SharedConfig;
+use tauri::State;
+
+/// Shows which site rule applies to the URL and the cookie and network settings it results in.
+#[tauri::command]
+pub fn config_resolve_site(cfg: State<'_, SharedConfig>, url: String) -> SiteSettings {
+  resolve_site_settings(&cfg.load(), &url)
+}
@@ -1,7 +1,9 @@
 pub mod config_get;
 pub mod config_reset;
+pub mod config_resolve_site;
 pub mod config_set;
 
 pub use config_get::*;
 pub use config_reset::*;
+pub use config_resolve_site::*;
 pub use config_set::*;
@@ -0,0 +1,26 @@
+use crate::stronghold::credential_profiles::{CredentialProfileSummary, CredentialProfileUpdate};
+use crate::stronghold::stronghold_state::StrongholdState;
+use tauri::State;
+
+/// Stores a new profile. Passing the id of an existing profile replaces it, secrets left out of
+/// the update keep their stored value.
+#[tauri::command]
+pub async fn credentials_create(
+  state: State<'_, StrongholdState>,
+  mut profile: CredentialProfileUpdate,
+) -> Result<CredentialProfileSummary, String> {
+  if profile.id.trim().is_empty() {
+    profile.id = uuid::Uuid::new_v4().to_string();
+  }
+
+  let mut profiles = state.load_profiles()?;
+  let index = profiles.iter().position(|p| p.id == profile.id);
+  let profile = profile.apply(index.map(|i| &profiles[i])).normalize()?;
+  match index {
+    Some(i) => profiles[i] = profile.clone(),
+    None => profiles.push(profile.clone()),
+  }
+  state.save_profiles(&profiles)?;
+
+  Ok(CredentialProfileSummary::from(&profile))
+}
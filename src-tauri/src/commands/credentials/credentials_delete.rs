@@ -0,0 +1,16 @@
+use crate::stronghold::stronghold_state::StrongholdState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn credentials_delete(
+  state: State<'_, StrongholdState>,
+  id: String,
+) -> Result<(), String> {
+  let mut profiles = state.load_profiles()?;
+  let before = profiles.len();
+  profiles.retain(|p| p.id != id);
+  if profiles.len() == before {
+    return Err(format!("Credential profile {id} does not exist"));
+  }
+  state.save_profiles(&profiles)
+}
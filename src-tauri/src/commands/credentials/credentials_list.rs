@@ -0,0 +1,16 @@
+use crate::stronghold::credential_profiles::CredentialProfileSummary;
+use crate::stronghold::stronghold_state::StrongholdState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn credentials_list(
+  state: State<'_, StrongholdState>,
+) -> Result<Vec<CredentialProfileSummary>, String> {
+  let profiles = state.load_profiles()?;
+  Ok(
+    profiles
+      .iter()
+      .map(CredentialProfileSummary::from)
+      .collect(),
+  )
+}
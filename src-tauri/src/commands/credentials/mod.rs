@@ -0,0 +1,7 @@
+pub mod credentials_create;
+pub mod credentials_delete;
+pub mod credentials_list;
+
+pub use credentials_create::*;
+pub use credentials_delete::*;
+pub use credentials_list::*;
@@ -3,6 +3,7 @@ use crate::scheduling::dispatcher::DispatchRequest;
 use crate::scheduling::download_pipeline::DownloadSender;
 use crate::scheduling::fetch_pipeline::FetchSender;
 use crate::scheduling::group_state::cancel_group;
+use crate::SharedQueue;
 use tauri::State;
 
 #[tauri::command]
@@ -11,8 +12,10 @@ pub fn group_cancel(
   log_state: State<'_, LogStoreState>,
   fetch_sender: State<'_, FetchSender>,
   download_sender: State<'_, DownloadSender>,
+  queue: State<'_, SharedQueue>,
 ) {
   cancel_group(&group_id);
+  queue.remove_group(&group_id);
 
   // Send cleanup requests for items that were already dispatched.
   let _ = fetch_sender.0.send(DispatchRequest::Cleanup {
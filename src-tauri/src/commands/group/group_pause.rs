@@ -0,0 +1,15 @@
+use crate::scheduling::group_state::pause_group;
+use crate::state::queue_models::QueueEntryState;
+use crate::SharedQueue;
+use tauri::State;
+
+#[tauri::command]
+pub fn group_pause(group_id: String, queue: State<'_, SharedQueue>) -> Result<(), String> {
+  // Running downloads observe the state change, stop their yt-dlp process and re-queue
+  // themselves; queued entries stay parked in the dispatcher until the group is resumed.
+  if !pause_group(&group_id) {
+    return Err(format!("Group {group_id} is not active"));
+  }
+  queue.set_group_state(&group_id, QueueEntryState::Paused);
+  Ok(())
+}
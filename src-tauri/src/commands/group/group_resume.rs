@@ -0,0 +1,28 @@
+use crate::scheduling::dispatcher::DispatchRequest;
+use crate::scheduling::download_pipeline::DownloadSender;
+use crate::scheduling::fetch_pipeline::FetchSender;
+use crate::scheduling::group_state::{resume_group, resume_group_items};
+use crate::state::queue_models::QueueEntryState;
+use crate::SharedQueue;
+use tauri::State;
+
+#[tauri::command]
+pub fn group_resume(
+  group_id: String,
+  fetch_sender: State<'_, FetchSender>,
+  download_sender: State<'_, DownloadSender>,
+  queue: State<'_, SharedQueue>,
+) -> Result<(), String> {
+  if !resume_group(&group_id) {
+    return Err(format!("Group {group_id} is not active"));
+  }
+  resume_group_items(&group_id);
+  queue.set_group_state(&group_id, QueueEntryState::Queued);
+
+  // Wake the dispatchers so parked entries are scheduled again.
+  let _ = fetch_sender.0.send(DispatchRequest::Resume {
+    group_id: group_id.clone(),
+  });
+  let _ = download_sender.0.send(DispatchRequest::Resume { group_id });
+  Ok(())
+}
@@ -1,3 +1,7 @@
 pub mod group_cancel;
+pub mod group_pause;
+pub mod group_resume;
 
 pub use group_cancel::*;
+pub use group_pause::*;
+pub use group_resume::*;
@@ -0,0 +1,33 @@
+use crate::state::history_models::HistoryEntry;
+use crate::SharedHistory;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+#[tauri::command]
+pub fn history_open(
+  app: AppHandle,
+  id: i64,
+  history: State<'_, SharedHistory>,
+) -> Result<(), String> {
+  let path = existing_file_path(&history, id)?;
+  app
+    .opener()
+    .open_path(path, None::<&str>)
+    .map_err(|e| format!("Failed to open file: {e}"))
+}
+
+/// Returns the file of a history entry, failing when it was moved or deleted since.
+pub(crate) fn existing_file_path(history: &SharedHistory, id: i64) -> Result<String, String> {
+  let entry: HistoryEntry = history
+    .get(id)
+    .map_err(|e| format!("Failed to read download history: {e}"))?
+    .ok_or_else(|| format!("History entry {id} not found"))?;
+  let path = entry
+    .file_path
+    .ok_or_else(|| format!("History entry {id} has no file"))?;
+  if !Path::new(&path).exists() {
+    return Err(format!("File no longer exists: {path}"));
+  }
+  Ok(path)
+}
@@ -0,0 +1,13 @@
+use crate::state::history_models::{HistoryPage, HistoryQuery};
+use crate::SharedHistory;
+use tauri::State;
+
+#[tauri::command]
+pub fn history_query(
+  query: HistoryQuery,
+  history: State<'_, SharedHistory>,
+) -> Result<HistoryPage, String> {
+  history
+    .query(&query)
+    .map_err(|e| format!("Failed to query download history: {e}"))
+}
@@ -0,0 +1,43 @@
+use crate::models::DownloadItem;
+use crate::scheduling::dispatcher::DispatchRequest;
+use crate::scheduling::download_pipeline::{DownloadRequest, DownloadSender};
+use crate::scheduling::group_state::ensure_group_running;
+use crate::SharedHistory;
+use tauri::State;
+
+/// Enqueues a history entry again with the same format, preset, sections and tag overrides and
+/// returns the new group id.
+#[tauri::command]
+pub fn history_redownload(
+  id: i64,
+  history: State<'_, SharedHistory>,
+  pipeline: State<'_, DownloadSender>,
+) -> Result<String, String> {
+  let entry = history
+    .get(id)
+    .map_err(|e| format!("Failed to read download history: {e}"))?
+    .ok_or_else(|| format!("History entry {id} not found"))?;
+
+  let group_id = uuid::Uuid::new_v4().to_string();
+  ensure_group_running(&group_id);
+
+  pipeline
+    .0
+    .send(DispatchRequest::Pipeline(DownloadRequest::Batch {
+      group_id: group_id.clone(),
+      items: vec![DownloadItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: entry.url,
+        format: entry.format,
+        template_context: entry.template_context,
+        live: None,
+        preset: entry.preset,
+        sections: entry.sections,
+        metadata: entry.metadata_options,
+      }],
+      schedule: None,
+    }))
+    .map_err(|e| e.to_string())?;
+
+  Ok(group_id)
+}
@@ -0,0 +1,17 @@
+use crate::commands::history::history_open::existing_file_path;
+use crate::SharedHistory;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+#[tauri::command]
+pub fn history_reveal(
+  app: AppHandle,
+  id: i64,
+  history: State<'_, SharedHistory>,
+) -> Result<(), String> {
+  let path = existing_file_path(&history, id)?;
+  app
+    .opener()
+    .reveal_item_in_dir(path)
+    .map_err(|e| format!("Failed to reveal file: {e}"))
+}
@@ -0,0 +1,9 @@
+pub mod history_open;
+pub mod history_query;
+pub mod history_redownload;
+pub mod history_reveal;
+
+pub use history_open::*;
+pub use history_query::*;
+pub use history_redownload::*;
+pub use history_reveal::*;
@@ -0,0 +1,26 @@
+use crate::scheduling::fetch_pipeline::submit_url;
+use crate::scheduling::watch_folders::read_url_file;
+use crate::SharedPresets;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Fetches every URL in a link list, shortcut or bookmarks file and returns their group ids.
+#[tauri::command]
+pub fn import_file(
+  app: AppHandle,
+  path: String,
+  preset: Option<String>,
+  presets: State<'_, SharedPresets>,
+) -> Result<Vec<String>, String> {
+  if let Some(name) = &preset {
+    if presets.find(name).is_none() {
+      return Err(format!("Preset {name} does not exist"));
+    }
+  }
+
+  let urls = read_url_file(Path::new(&path))?;
+  urls
+    .into_iter()
+    .map(|url| submit_url(&app, url, preset.as_deref()))
+    .collect()
+}
@@ -0,0 +1,3 @@
+pub mod import_file;
+
+pub use import_file::*;
@@ -2,14 +2,42 @@ use crate::models::DownloadItem;
 use crate::scheduling::dispatcher::DispatchRequest;
 use crate::scheduling::download_pipeline::{DownloadRequest, DownloadSender};
 use crate::scheduling::group_state::ensure_group_running;
+use crate::scheduling::schedule::DownloadSchedule;
+use crate::SharedPresets;
 use tauri::State;
 
 #[tauri::command]
 pub fn media_download(
   group_id: String,
   items: Vec<DownloadItem>,
+  schedule: Option<DownloadSchedule>,
+  preset: Option<String>,
   pipeline: State<'_, DownloadSender>,
-) -> String {
+  presets: State<'_, SharedPresets>,
+) -> Result<String, String> {
+  if let Some(schedule) = &schedule {
+    schedule.validate()?;
+  }
+
+  // The group's preset applies to every item that does not pick its own.
+  let mut items = items;
+  for item in &mut items {
+    if item.preset.is_none() {
+      item.preset.clone_from(&preset);
+    }
+    if let Some(sections) = &item.sections {
+      sections.validate()?;
+    }
+    if let Some(metadata) = &item.metadata {
+      metadata.validate()?;
+    }
+    if let Some(id) = &item.preset {
+      if presets.get(id).is_none() {
+        return Err(format!("Preset {id} does not exist"));
+      }
+    }
+  }
+
   ensure_group_running(&group_id);
 
   pipeline
@@ -17,8 +45,9 @@ pub fn media_download(
     .send(DispatchRequest::Pipeline(DownloadRequest::Batch {
       group_id: group_id.clone(),
       items,
+      schedule,
     }))
     .unwrap();
 
-  group_id
+  Ok(group_id)
 }
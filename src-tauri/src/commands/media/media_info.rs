@@ -19,6 +19,7 @@ pub fn media_info(
       group_id: group_id.clone(),
       url,
       id,
+      download_preset: None,
     }))
     .map_err(|e| e.to_string())?;
 
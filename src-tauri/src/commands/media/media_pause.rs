@@ -0,0 +1,19 @@
+use crate::scheduling::group_state::pause_item;
+use crate::state::queue_models::QueueEntryState;
+use crate::SharedQueue;
+use tauri::State;
+
+/// Pauses a single download while the rest of its group goes on.
+#[tauri::command]
+pub fn media_pause(id: String, queue: State<'_, SharedQueue>) -> Result<(), String> {
+  let Some(entry) = queue.get(&id) else {
+    return Err(format!("Download {id} is not queued"));
+  };
+  // A running download stops its yt-dlp process and is set aside by the dispatcher until it
+  // is resumed; its `.part` files are kept.
+  if !pause_item(&entry.group_id, &id) {
+    return Err(format!("Group {} is not active", entry.group_id));
+  }
+  queue.set_state(&id, QueueEntryState::Paused);
+  Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::scheduling::dispatcher::DispatchRequest;
+use crate::scheduling::download_pipeline::DownloadSender;
+use crate::scheduling::group_state::{is_group_paused, resume_item};
+use crate::state::queue_models::QueueEntryState;
+use crate::SharedQueue;
+use tauri::State;
+
+/// Resumes a download paused with `media_pause`.
+#[tauri::command]
+pub fn media_resume(
+  id: String,
+  download_sender: State<'_, DownloadSender>,
+  queue: State<'_, SharedQueue>,
+) -> Result<(), String> {
+  let Some(entry) = queue.get(&id) else {
+    return Err(format!("Download {id} is not queued"));
+  };
+  if !resume_item(&id) {
+    return Err(format!("Download {id} is not paused"));
+  }
+  // An item of a paused group stays paused until the whole group is resumed.
+  if !is_group_paused(&entry.group_id) {
+    queue.set_state(&id, QueueEntryState::Queued);
+  }
+
+  // Wake the dispatcher so the entry set aside is scheduled again.
+  let _ = download_sender.0.send(DispatchRequest::Resume {
+    group_id: entry.group_id,
+  });
+  Ok(())
+}
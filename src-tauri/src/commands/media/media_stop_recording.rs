@@ -0,0 +1,10 @@
+use crate::scheduling::recording::stop_recording;
+
+/// Stops a livestream recording and keeps what was recorded so far.
+#[tauri::command]
+pub fn media_stop_recording(id: String) -> Result<(), String> {
+  if !stop_recording(&id) {
+    return Err(format!("No recording is running for {id}"));
+  }
+  Ok(())
+}
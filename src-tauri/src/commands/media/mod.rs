@@ -1,7 +1,13 @@
 pub mod media_download;
 pub mod media_info;
+pub mod media_pause;
+pub mod media_resume;
 pub mod media_size;
+pub mod media_stop_recording;
 
 pub use media_download::*;
 pub use media_info::*;
+pub use media_pause::*;
+pub use media_resume::*;
 pub use media_size::*;
+pub use media_stop_recording::*;
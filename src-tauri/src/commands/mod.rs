@@ -1,25 +1,43 @@
 pub mod app_ready;
+pub mod archive;
 pub mod binaries;
 pub mod config;
+pub mod credentials;
 pub mod group;
+pub mod history;
+pub mod import;
 pub mod logging;
 pub mod media;
 pub mod notifications;
 pub mod platform;
 pub mod preferences;
+pub mod preset;
+pub mod proxy;
+pub mod queue;
+pub mod remote;
 pub mod shortcuts;
 pub mod stronghold;
+pub mod subscription;
 pub mod updater;
 
 pub use app_ready::*;
+pub use archive::*;
 pub use binaries::*;
 pub use config::*;
+pub use credentials::*;
 pub use group::*;
+pub use history::*;
+pub use import::*;
 pub use logging::*;
 pub use media::*;
 pub use notifications::*;
 pub use platform::*;
 pub use preferences::*;
+pub use preset::*;
+pub use proxy::*;
+pub use queue::*;
+pub use remote::*;
 pub use shortcuts::*;
 pub use stronghold::*;
+pub use subscription::*;
 pub use updater::*;
@@ -0,0 +1,11 @@
+pub mod preset_export;
+pub mod preset_import;
+pub mod preset_list;
+pub mod preset_remove;
+pub mod preset_save;
+
+pub use preset_export::*;
+pub use preset_import::*;
+pub use preset_list::*;
+pub use preset_remove::*;
+pub use preset_save::*;
@@ -0,0 +1,11 @@
+use crate::SharedPresets;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Writes every preset to a JSON file and returns the number of presets.
+#[tauri::command]
+pub fn preset_export(path: String, presets: State<'_, SharedPresets>) -> Result<usize, String> {
+  presets
+    .export(&PathBuf::from(path))
+    .map_err(|e| format!("Failed to export presets: {e}"))
+}
@@ -0,0 +1,12 @@
+use crate::SharedPresets;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Adds the presets of a JSON file written by `preset_export`, replacing presets with the same
+/// id. Returns the number of imported presets.
+#[tauri::command]
+pub fn preset_import(path: String, presets: State<'_, SharedPresets>) -> Result<usize, String> {
+  presets
+    .import(&PathBuf::from(path))
+    .map_err(|e| format!("Failed to import presets: {e}"))
+}
@@ -0,0 +1,8 @@
+use crate::state::presets_models::Preset;
+use crate::SharedPresets;
+use tauri::State;
+
+#[tauri::command]
+pub fn preset_list(presets: State<'_, SharedPresets>) -> Vec<Preset> {
+  presets.list()
+}
@@ -0,0 +1,11 @@
+use crate::SharedPresets;
+use tauri::State;
+
+#[tauri::command]
+pub fn preset_remove(id: String, presets: State<'_, SharedPresets>) -> Result<(), String> {
+  if presets.remove(&id) {
+    Ok(())
+  } else {
+    Err(format!("Preset {id} does not exist"))
+  }
+}
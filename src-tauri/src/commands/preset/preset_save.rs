@@ -0,0 +1,19 @@
+use crate::state::presets_models::Preset;
+use crate::SharedPresets;
+use tauri::State;
+
+/// Creates the preset, or updates the existing one with the same id. An empty id creates a new
+/// preset.
+#[tauri::command]
+pub fn preset_save(
+  mut preset: Preset,
+  presets: State<'_, SharedPresets>,
+) -> Result<Preset, String> {
+  if preset.id.trim().is_empty() {
+    preset.id = uuid::Uuid::new_v4().to_string();
+  }
+  let preset = preset.normalize()?;
+
+  presets.save(preset.clone());
+  Ok(preset)
+}
@@ -0,0 +1,5 @@
+pub mod proxy_check;
+pub mod proxy_status;
+
+pub use proxy_check::*;
+pub use proxy_status::*;
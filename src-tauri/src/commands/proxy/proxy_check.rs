@@ -0,0 +1,16 @@
+use crate::scheduling::proxy_pool::{ProxyPool, ProxyStatus};
+use crate::SharedConfig;
+use tauri::{AppHandle, Emitter, State};
+
+/// Runs the health checks of the proxy pool right away.
+#[tauri::command]
+pub async fn proxy_check(
+  app: AppHandle,
+  cfg: State<'_, SharedConfig>,
+  pool: State<'_, ProxyPool>,
+) -> Result<Vec<ProxyStatus>, String> {
+  let settings = cfg.load().network.proxy_pool.clone();
+  let statuses = pool.check_all(&settings).await;
+  let _ = app.emit("proxy_health", &statuses);
+  Ok(statuses)
+}
@@ -0,0 +1,8 @@
+use crate::scheduling::proxy_pool::{ProxyPool, ProxyStatus};
+use crate::SharedConfig;
+use tauri::State;
+
+#[tauri::command]
+pub fn proxy_status(cfg: State<'_, SharedConfig>, pool: State<'_, ProxyPool>) -> Vec<ProxyStatus> {
+  pool.statuses(&cfg.load().network.proxy_pool)
+}
@@ -0,0 +1,5 @@
+pub mod queue_list;
+pub mod queue_scheduled;
+
+pub use queue_list::*;
+pub use queue_scheduled::*;
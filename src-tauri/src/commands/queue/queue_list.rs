@@ -0,0 +1,8 @@
+use crate::state::queue_models::QueuedDownload;
+use crate::SharedQueue;
+use tauri::State;
+
+#[tauri::command]
+pub fn queue_list(queue: State<'_, SharedQueue>) -> Vec<QueuedDownload> {
+  queue.snapshot()
+}
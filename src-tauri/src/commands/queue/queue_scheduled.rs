@@ -0,0 +1,20 @@
+use crate::scheduling::scheduler::scheduled_groups;
+use crate::state::queue_models::ScheduledGroup;
+use crate::SharedQueue;
+use tauri::State;
+
+#[tauri::command]
+pub fn queue_scheduled(queue: State<'_, SharedQueue>) -> Vec<ScheduledGroup> {
+  let mut groups: Vec<ScheduledGroup> = scheduled_groups()
+    .into_iter()
+    .map(|(group_id, schedule, status)| ScheduledGroup {
+      pending_count: queue.group_len(&group_id),
+      group_id,
+      schedule,
+      open: status.open,
+      next_change_at: status.next_change_at,
+    })
+    .collect();
+  groups.sort_by_key(|group| group.next_change_at.unwrap_or(i64::MAX));
+  groups
+}
@@ -0,0 +1,5 @@
+pub mod remote_rotate;
+pub mod remote_token;
+
+pub use remote_rotate::*;
+pub use remote_token::*;
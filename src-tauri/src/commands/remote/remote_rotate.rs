@@ -0,0 +1,13 @@
+use crate::remote::RemoteApiServer;
+use crate::stronghold::stronghold_state::StrongholdState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn remote_rotate(
+  state: State<'_, StrongholdState>,
+  server: State<'_, RemoteApiServer>,
+) -> Result<String, String> {
+  let token = state.rotate_remote_api_token()?;
+  server.set_token(token.clone());
+  Ok(token)
+}
@@ -0,0 +1,8 @@
+use crate::stronghold::stronghold_state::StrongholdState;
+use tauri::State;
+
+/// Token clients of the local API authenticate with, created on first use.
+#[tauri::command]
+pub async fn remote_token(state: State<'_, StrongholdState>) -> Result<String, String> {
+  state.remote_api_token()
+}
@@ -0,0 +1,9 @@
+pub mod subscription_add;
+pub mod subscription_check;
+pub mod subscription_list;
+pub mod subscription_remove;
+
+pub use subscription_add::*;
+pub use subscription_check::*;
+pub use subscription_list::*;
+pub use subscription_remove::*;
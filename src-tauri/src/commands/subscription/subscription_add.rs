@@ -0,0 +1,37 @@
+use crate::models::download::FormatOptions;
+use crate::state::subscriptions_models::Subscription;
+use crate::SharedSubscriptions;
+use tauri::State;
+
+#[tauri::command]
+pub fn subscription_add(
+  url: String,
+  title: Option<String>,
+  format: FormatOptions,
+  download_existing: Option<bool>,
+  subscriptions: State<'_, SharedSubscriptions>,
+) -> Result<Subscription, String> {
+  let url = url.trim().to_string();
+  if url.is_empty() {
+    return Err("Subscription URL is empty".into());
+  }
+  if subscriptions.list().iter().any(|s| s.url == url) {
+    return Err(format!("Already subscribed to {url}"));
+  }
+
+  let subscription = Subscription {
+    id: uuid::Uuid::new_v4().to_string(),
+    url,
+    title,
+    format,
+    download_existing: download_existing.unwrap_or(false),
+    created_at: std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map(|d| d.as_millis())
+      .unwrap_or(0),
+    last_checked_at: None,
+    last_error: None,
+  };
+  subscriptions.add(subscription.clone());
+  Ok(subscription)
+}
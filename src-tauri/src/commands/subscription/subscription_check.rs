@@ -0,0 +1,11 @@
+use crate::scheduling::subscriptions::check_subscription;
+use crate::state::subscriptions_models::SubscriptionCheckResult;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn subscription_check(
+  app: AppHandle,
+  id: String,
+) -> Result<SubscriptionCheckResult, String> {
+  check_subscription(&app, &id).await
+}
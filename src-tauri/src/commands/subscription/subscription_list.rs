@@ -0,0 +1,8 @@
+use crate::state::subscriptions_models::Subscription;
+use crate::SharedSubscriptions;
+use tauri::State;
+
+#[tauri::command]
+pub fn subscription_list(subscriptions: State<'_, SharedSubscriptions>) -> Vec<Subscription> {
+  subscriptions.list()
+}
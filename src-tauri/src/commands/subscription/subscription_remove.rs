@@ -0,0 +1,13 @@
+use crate::SharedSubscriptions;
+use tauri::State;
+
+#[tauri::command]
+pub fn subscription_remove(
+  id: String,
+  subscriptions: State<'_, SharedSubscriptions>,
+) -> Result<(), String> {
+  if !subscriptions.remove(&id) {
+    return Err(format!("Subscription {id} not found"));
+  }
+  Ok(())
+}
@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: ovd-cli [OPTIONS] [URL]...
+
+Downloads the given URLs with the settings of the desktop app and exits.
+
+Options:
+  -f, --file <PATH>     Read URLs from a file, one per line, `-` for stdin
+  -p, --preset <NAME>   Download with a preset, by id or name
+  -a, --audio           Download audio only
+      --max-height <N>  Download video up to this height
+      --json            Print events as JSON lines instead of text
+  -q, --quiet           Only print errors and the summary
+      --skip-binaries   Do not install or update yt-dlp and ffmpeg first
+  -h, --help            Print this help
+  -V, --version         Print the version
+
+Exit codes:
+  0    every download finished
+  1    at least one download failed
+  2    invalid arguments
+  3    the downloader could not be set up
+  130  interrupted with Ctrl+C";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeadlessOptions {
+  pub urls: Vec<String>,
+  pub url_file: Option<PathBuf>,
+  pub preset: Option<String>,
+  pub audio_only: bool,
+  pub max_height: Option<u32>,
+  pub json: bool,
+  pub quiet: bool,
+  pub skip_binaries: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HeadlessCommand {
+  Help,
+  Version,
+  Run(HeadlessOptions),
+}
+
+/// Parses the arguments after the program name.
+pub fn parse_args<I, S>(args: I) -> Result<HeadlessCommand, String>
+where
+  I: IntoIterator<Item = S>,
+  S: Into<String>,
+{
+  let mut options = HeadlessOptions::default();
+  let mut args = args.into_iter().map(Into::into);
+  let mut only_urls = false;
+
+  while let Some(arg) = args.next() {
+    if only_urls || !arg.starts_with('-') {
+      options.urls.push(arg);
+      continue;
+    }
+
+    // Accept `--flag=value` as well as `--flag value`.
+    let (flag, inline_value) = match arg.split_once('=') {
+      Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+      _ => (arg.clone(), None),
+    };
+    let mut value = |name: &str| {
+      inline_value
+        .clone()
+        .or_else(|| args.next())
+        .ok_or_else(|| format!("{name} needs a value"))
+    };
+
+    match flag.as_str() {
+      "-h" | "--help" => return Ok(HeadlessCommand::Help),
+      "-V" | "--version" => return Ok(HeadlessCommand::Version),
+      "-f" | "--file" => options.url_file = Some(PathBuf::from(value(&flag)?)),
+      "-p" | "--preset" => options.preset = Some(value(&flag)?),
+      "-a" | "--audio" => options.audio_only = true,
+      "--max-height" => {
+        let height = value(&flag)?;
+        options.max_height = Some(
+          height
+            .parse()
+            .map_err(|_| format!("Invalid height: {height}"))?,
+        );
+      }
+      "--json" => options.json = true,
+      "-q" | "--quiet" => options.quiet = true,
+      "--skip-binaries" => options.skip_binaries = true,
+      "--" => only_urls = true,
+      _ => return Err(format!("Unknown option: {arg}")),
+    }
+  }
+
+  Ok(HeadlessCommand::Run(options))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_flags_values_and_urls() {
+    let command = parse_args([
+      "https://a.example/1",
+      "-p",
+      "Podcast",
+      "--max-height=720",
+      "--json",
+      "-f",
+      "urls.txt",
+      "--",
+      "-not-a-flag",
+    ])
+    .unwrap();
+
+    assert_eq!(
+      command,
+      HeadlessCommand::Run(HeadlessOptions {
+        urls: vec!["https://a.example/1".into(), "-not-a-flag".into()],
+        url_file: Some(PathBuf::from("urls.txt")),
+        preset: Some("Podcast".into()),
+        max_height: Some(720),
+        json: true,
+        ..HeadlessOptions::default()
+      })
+    );
+  }
+
+  #[test]
+  fn rejects_unknown_options_and_missing_values() {
+    assert!(parse_args(["--nope"]).is_err());
+    assert!(parse_args(["--preset"]).is_err());
+    assert!(parse_args(["--max-height", "tall"]).is_err());
+    assert_eq!(parse_args(["-a", "-h"]), Ok(HeadlessCommand::Help));
+  }
+}
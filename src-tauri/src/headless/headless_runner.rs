@@ -0,0 +1,345 @@
+use crate::binaries::binaries_manager::BinariesManager;
+use crate::headless::headless_args::{parse_args, HeadlessCommand, HeadlessOptions, USAGE};
+use crate::models::download::FormatOptions;
+use crate::models::TrackType;
+use crate::parsers::url_list::parse_url_list;
+use crate::runners::ytdlp_info::run_ytdlp_info_fetch;
+use crate::scheduling::dispatcher::DispatchRequest;
+use crate::scheduling::download_pipeline::{DownloadRequest, DownloadSender};
+use crate::scheduling::fetch_pipeline::media_download_items;
+use crate::scheduling::group_state::{cancel_group, ensure_group_running};
+use crate::{app_context, setup_core, SharedPresets};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::test::MockRuntime;
+use tauri::{AppHandle, Listener, Manager, Runtime};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_SETUP: i32 = 3;
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Events printed while downloads run.
+const PRINTED_EVENTS: [&str; 5] = [
+  "media_progress",
+  "media_destination",
+  "media_retry",
+  "media_complete",
+  "media_fatal",
+];
+
+/// How long cancelled downloads get to stop their processes after Ctrl+C.
+const INTERRUPT_GRACE: Duration = Duration::from_secs(10);
+
+/// Runs the downloader without a window and returns the process exit code.
+pub fn run_headless<I, S>(args: I) -> i32
+where
+  I: IntoIterator<Item = S>,
+  S: Into<String>,
+{
+  let options = match parse_args(args) {
+    Ok(HeadlessCommand::Run(options)) => options,
+    Ok(HeadlessCommand::Help) => {
+      println!("{USAGE}");
+      return EXIT_OK;
+    }
+    Ok(HeadlessCommand::Version) => {
+      println!("ovd-cli {}", env!("CARGO_PKG_VERSION"));
+      return EXIT_OK;
+    }
+    Err(e) => {
+      eprintln!("{e}\n\n{USAGE}");
+      return EXIT_USAGE;
+    }
+  };
+
+  let urls = match collect_urls(&options) {
+    Ok(urls) if !urls.is_empty() => urls,
+    Ok(_) => {
+      eprintln!("No URLs given\n\n{USAGE}");
+      return EXIT_USAGE;
+    }
+    Err(e) => {
+      eprintln!("{e}");
+      return EXIT_USAGE;
+    }
+  };
+
+  tracing_subscriber::fmt()
+    .with_writer(std::io::stderr)
+    .with_max_level(tracing::Level::WARN)
+    .init();
+
+  // A runtime without windows or an event loop, so no display is needed. The app is never run,
+  // the job runs on the async runtime while the app is alive.
+  let app = tauri::Builder::<MockRuntime>::new()
+    .plugin(tauri_plugin_store::Builder::new().build())
+    .plugin(tauri_plugin_keyring::init())
+    .build(app_context());
+  let app = match app {
+    Ok(app) => app,
+    Err(e) => {
+      eprintln!("Failed to start the downloader: {e}");
+      return EXIT_SETUP;
+    }
+  };
+
+  let handle = app.handle().clone();
+  if let Err(e) = setup_core(&handle) {
+    eprintln!("Failed to start the downloader: {e}");
+    return EXIT_SETUP;
+  }
+  let code = tauri::async_runtime::block_on(run_job(&handle, options, urls));
+  handle.cleanup_before_exit();
+  code
+}
+
+fn collect_urls(options: &HeadlessOptions) -> Result<Vec<String>, String> {
+  let mut urls = options.urls.clone();
+  if let Some(file) = &options.url_file {
+    let text = if file.as_os_str() == "-" {
+      let mut text = String::new();
+      std::io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read URLs from stdin: {e}"))?;
+      text
+    } else {
+      std::fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {e}", file.display()))?
+    };
+    urls.extend(parse_url_list(&text));
+  }
+  Ok(urls)
+}
+
+async fn run_job<R: Runtime>(
+  app: &AppHandle<R>,
+  options: HeadlessOptions,
+  urls: Vec<String>,
+) -> i32 {
+  if !options.skip_binaries {
+    if let Err(e) = app.state::<BinariesManager<R>>().ensure(None, false).await {
+      eprintln!("Failed to install yt-dlp and ffmpeg: {e}");
+      return EXIT_SETUP;
+    }
+  }
+
+  let preset = match &options.preset {
+    Some(name) => match app.state::<SharedPresets>().find(name) {
+      Some(preset) => Some(preset.id),
+      None => {
+        eprintln!("Preset {name} does not exist");
+        return EXIT_USAGE;
+      }
+    },
+    None => None,
+  };
+
+  let printer = Arc::new(Printer::new(&options));
+  for event in PRINTED_EVENTS {
+    let printer = printer.clone();
+    app.listen_any(event, move |e| printer.print(event, e.payload()));
+  }
+  let (done_tx, mut done_rx) = mpsc::unbounded_channel::<Value>();
+  app.listen_any("group_complete", move |e| {
+    if let Ok(payload) = serde_json::from_str(e.payload()) {
+      let _ = done_tx.send(payload);
+    }
+  });
+
+  let mut format = FormatOptions {
+    height: options.max_height,
+    ..FormatOptions::default()
+  };
+  if options.audio_only {
+    format.track_type = TrackType::Audio;
+  }
+
+  let mut groups = HashSet::new();
+  let mut failed = 0;
+  for url in urls {
+    let group_id = Uuid::new_v4().to_string();
+    let media = run_ytdlp_info_fetch(
+      app,
+      group_id.clone(),
+      group_id.clone(),
+      &url,
+      Some(format.clone()),
+    )
+    .await;
+    let items = match media {
+      Ok(Some(media)) => media_download_items(media, &url, &format, preset.as_ref()),
+      Ok(None) => Vec::new(),
+      Err(e) => {
+        eprintln!("Failed to fetch {url}: {e}");
+        Vec::new()
+      }
+    };
+    if items.is_empty() {
+      failed += 1;
+      continue;
+    }
+
+    for item in &items {
+      printer.label(&item.id, &item.url);
+    }
+    ensure_group_running(&group_id);
+    let _ = app
+      .state::<DownloadSender>()
+      .0
+      .send(DispatchRequest::Pipeline(DownloadRequest::Batch {
+        group_id: group_id.clone(),
+        items,
+        schedule: None,
+      }));
+    groups.insert(group_id);
+  }
+
+  let mut completed = 0;
+  let mut cancelled = 0;
+  let mut interrupted = false;
+  while !groups.is_empty() {
+    tokio::select! {
+      payload = done_rx.recv() => {
+        let Some(payload) = payload else { break };
+        if let Some(group_id) = payload["groupId"].as_str() {
+          groups.remove(group_id);
+        }
+        let count = |key: &str| payload[key].as_u64().unwrap_or(0) as usize;
+        completed += count("completed");
+        failed += count("failed");
+        cancelled += count("cancelled");
+      }
+      _ = tokio::signal::ctrl_c(), if !interrupted => {
+        interrupted = true;
+        eprintln!("Interrupted, cancelling downloads");
+        for group_id in &groups {
+          cancel_group(group_id);
+        }
+        let pending = groups.len();
+        // Give the downloads a moment to stop their yt-dlp processes.
+        let _ = tokio::time::timeout(INTERRUPT_GRACE, async {
+          for _ in 0..pending {
+            if done_rx.recv().await.is_none() {
+              break;
+            }
+          }
+        })
+        .await;
+        break;
+      }
+    }
+  }
+
+  printer.summary(completed, failed, cancelled);
+  if interrupted {
+    EXIT_INTERRUPTED
+  } else if failed > 0 {
+    EXIT_FAILED
+  } else {
+    EXIT_OK
+  }
+}
+
+/// Prints download events to stdout, either as text or as JSON lines.
+struct Printer {
+  json: bool,
+  quiet: bool,
+  /// URL of every item, to tell the downloads apart in text output.
+  labels: Mutex<HashMap<String, String>>,
+  /// Last printed progress per item in steps of ten percent, to keep logs readable.
+  last_step: Mutex<HashMap<String, u64>>,
+}
+
+impl Printer {
+  fn new(options: &HeadlessOptions) -> Self {
+    Self {
+      json: options.json,
+      quiet: options.quiet,
+      labels: Mutex::new(HashMap::new()),
+      last_step: Mutex::new(HashMap::new()),
+    }
+  }
+
+  fn label(&self, id: &str, url: &str) {
+    self
+      .labels
+      .lock()
+      .unwrap()
+      .insert(id.to_string(), url.to_string());
+  }
+
+  fn print(&self, event: &str, payload: &str) {
+    let Ok(payload) = serde_json::from_str::<Value>(payload) else {
+      return;
+    };
+    if self.json {
+      println!(
+        "{}",
+        serde_json::json!({ "event": event, "payload": payload })
+      );
+      return;
+    }
+
+    let id = payload["id"].as_str().unwrap_or_default();
+    let label = self
+      .labels
+      .lock()
+      .unwrap()
+      .get(id)
+      .cloned()
+      .unwrap_or_else(|| id.to_string());
+    match event {
+      "media_fatal" => {
+        let message = payload["message"].as_str().unwrap_or("Download failed");
+        eprintln!("[failed] {label}: {message}");
+      }
+      _ if self.quiet => {}
+      "media_progress" => {
+        let Some(percentage) = payload["percentage"].as_f64() else {
+          return;
+        };
+        let step = (percentage / 10.0).floor() as u64;
+        if self.last_step.lock().unwrap().insert(id.to_string(), step) == Some(step) {
+          return;
+        }
+        let speed = payload["speedBps"]
+          .as_f64()
+          .map(|bps| format!(" at {:.1} MiB/s", bps / 1_048_576.0))
+          .unwrap_or_default();
+        println!("[{percentage:5.1}%] {label}{speed}");
+      }
+      "media_destination" => {
+        if let Some(path) = payload["destination"]["path"].as_str() {
+          println!("[file] {path}");
+        }
+      }
+      "media_retry" => {
+        let attempt = payload["attempt"].as_u64().unwrap_or(0);
+        println!("[retry] {label}, attempt {attempt}");
+      }
+      "media_complete" => println!("[done] {label}"),
+      _ => {}
+    }
+  }
+
+  fn summary(&self, completed: usize, failed: usize, cancelled: usize) {
+    if self.json {
+      println!(
+        "{}",
+        serde_json::json!({
+          "event": "summary",
+          "payload": { "completed": completed, "failed": failed, "cancelled": cancelled },
+        })
+      );
+    } else {
+      println!("{completed} completed, {failed} failed, {cancelled} cancelled");
+    }
+  }
+}
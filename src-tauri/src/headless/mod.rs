@@ -0,0 +1,4 @@
+pub mod headless_args;
+pub mod headless_runner;
+
+pub use headless_runner::run_headless;
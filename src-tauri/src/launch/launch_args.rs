@@ -0,0 +1,179 @@
+use crate::parsers::url_list::is_web_url;
+
+/// Scheme of deep links like `ovd://add?url=...&preset=...`.
+pub const URL_SCHEME: &str = "ovd";
+
+/// A URL the app was asked to open, by a deep link or on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchRequest {
+  pub url: String,
+  /// Preset id or name to download with right away, `None` to only add the URL to the list.
+  pub preset: Option<String>,
+}
+
+/// Parses the arguments after the program name. Flags the app does not know, like
+/// `--auto-start`, and anything that is not a web URL are skipped.
+pub fn parse_launch_args<I, S>(args: I) -> Vec<LaunchRequest>
+where
+  I: IntoIterator<Item = S>,
+  S: Into<String>,
+{
+  let mut urls = Vec::new();
+  let mut links = Vec::new();
+  let mut preset = None;
+  let mut args = args.into_iter().map(Into::into);
+
+  while let Some(arg) = args.next() {
+    if let Some(value) = arg.strip_prefix("--preset=") {
+      preset = Some(value.to_string());
+    } else if arg == "--preset" {
+      preset = args.next();
+    } else if arg.starts_with('-') {
+      continue;
+    } else if is_web_url(&arg) {
+      urls.push(arg);
+    } else if let Some(requests) = parse_deep_link(&arg) {
+      links.extend(requests);
+    }
+  }
+
+  let preset = preset.filter(|p| !p.is_empty());
+  urls
+    .into_iter()
+    .map(|url| LaunchRequest {
+      url,
+      preset: preset.clone(),
+    })
+    .chain(links)
+    .collect()
+}
+
+/// Parses `ovd://add?url=...&preset=...`. `url` may be repeated to add several URLs at once.
+pub fn parse_deep_link(link: &str) -> Option<Vec<LaunchRequest>> {
+  let (scheme, rest) = link.split_once(':')?;
+  if !scheme.eq_ignore_ascii_case(URL_SCHEME) {
+    return None;
+  }
+  let rest = rest.trim_start_matches('/');
+  let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+  if !action.trim_end_matches('/').eq_ignore_ascii_case("add") {
+    tracing::warn!("Unknown deep link action in {link}");
+    return None;
+  }
+
+  let mut urls = Vec::new();
+  let mut preset = None;
+  for pair in query.split('&') {
+    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+    let Some(value) = decode_component(value) else {
+      continue;
+    };
+    match key {
+      "url" if is_web_url(&value) => urls.push(value),
+      "preset" if !value.is_empty() => preset = Some(value),
+      _ => {}
+    }
+  }
+
+  Some(
+    urls
+      .into_iter()
+      .map(|url| LaunchRequest {
+        url,
+        preset: preset.clone(),
+      })
+      .collect(),
+  )
+}
+
+/// Decodes a percent-encoded query value, `+` meaning a space.
+fn decode_component(value: &str) -> Option<String> {
+  let mut bytes = Vec::with_capacity(value.len());
+  let mut input = value.bytes();
+  while let Some(byte) = input.next() {
+    match byte {
+      b'+' => bytes.push(b' '),
+      b'%' => {
+        let hex = [input.next()?, input.next()?];
+        let hex = std::str::from_utf8(&hex).ok()?;
+        bytes.push(u8::from_str_radix(hex, 16).ok()?);
+      }
+      _ => bytes.push(byte),
+    }
+  }
+  String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request(url: &str, preset: Option<&str>) -> LaunchRequest {
+    LaunchRequest {
+      url: url.into(),
+      preset: preset.map(Into::into),
+    }
+  }
+
+  #[test]
+  fn parses_command_line_urls_with_a_preset() {
+    let requests = parse_launch_args([
+      "--auto-start",
+      "https://a.example/watch?v=1",
+      "--preset",
+      "Podcast",
+      "notes.txt",
+      "https://a.example/watch?v=2",
+    ]);
+    assert_eq!(
+      requests,
+      vec![
+        request("https://a.example/watch?v=1", Some("Podcast")),
+        request("https://a.example/watch?v=2", Some("Podcast")),
+      ]
+    );
+    assert_eq!(
+      parse_launch_args(["--preset=", "http://a.example/1"]),
+      vec![request("http://a.example/1", None)]
+    );
+    assert!(parse_launch_args(["--auto-start"]).is_empty());
+  }
+
+  #[test]
+  fn parses_deep_links() {
+    let requests = parse_launch_args([
+      "ovd://add?url=https%3A%2F%2Fa.example%2Fwatch%3Fv%3D1%26t%3D5&preset=Phone+720p",
+    ]);
+    assert_eq!(
+      requests,
+      vec![request(
+        "https://a.example/watch?v=1&t=5",
+        Some("Phone 720p")
+      )]
+    );
+
+    let requests =
+      parse_deep_link("OVD://add/?url=https://a.example/1&url=https://a.example/2").unwrap();
+    assert_eq!(
+      requests,
+      vec![
+        request("https://a.example/1", None),
+        request("https://a.example/2", None),
+      ]
+    );
+  }
+
+  #[test]
+  fn ignores_unsafe_or_unknown_links() {
+    assert_eq!(parse_deep_link("https://a.example"), None);
+    assert_eq!(parse_deep_link("ovd://remove?url=https://a.example"), None);
+    assert_eq!(
+      parse_deep_link("ovd://add?url=file%3A%2F%2F%2Fetc%2Fpasswd&url=ytsearch%3Acats"),
+      Some(vec![])
+    );
+    assert_eq!(
+      parse_deep_link("ovd://add?url=https%3A%2F%2Fa.example%2"),
+      Some(vec![])
+    );
+  }
+}
@@ -0,0 +1,108 @@
+use crate::launch::launch_args::{parse_launch_args, LaunchRequest};
+use crate::scheduling::fetch_pipeline::submit_url;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Launch requests that arrived before the frontend could list what they fetch.
+#[derive(Default)]
+pub struct PendingLaunches {
+  inner: Mutex<PendingInner>,
+}
+
+#[derive(Default)]
+struct PendingInner {
+  frontend_ready: bool,
+  requests: Vec<LaunchRequest>,
+}
+
+/// Fetches the URLs in the arguments of this or another launch of the app.
+pub fn handle_launch_args<R: Runtime, I, S>(app: &AppHandle<R>, args: I)
+where
+  I: IntoIterator<Item = S>,
+  S: Into<String>,
+{
+  let requests = parse_launch_args(args);
+  if requests.is_empty() {
+    return;
+  }
+  let Some(pending) = app.try_state::<PendingLaunches>() else {
+    return;
+  };
+
+  let mut inner = pending.inner.lock().unwrap();
+  if inner.frontend_ready {
+    drop(inner);
+    submit_requests(app, requests);
+  } else {
+    inner.requests.extend(requests);
+  }
+}
+
+/// Starts the requests held back until the frontend listens for their results.
+pub fn flush_pending_launches<R: Runtime>(app: &AppHandle<R>) {
+  let Some(pending) = app.try_state::<PendingLaunches>() else {
+    return;
+  };
+  let requests = {
+    let mut inner = pending.inner.lock().unwrap();
+    inner.frontend_ready = true;
+    std::mem::take(&mut inner.requests)
+  };
+  submit_requests(app, requests);
+}
+
+fn submit_requests<R: Runtime>(app: &AppHandle<R>, requests: Vec<LaunchRequest>) {
+  for request in requests {
+    if let Err(e) = submit_url(app, request.url, request.preset.as_deref()) {
+      tracing::error!("Failed to start launch request: {e}");
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::scheduling::dispatcher::DispatchRequest;
+  use crate::scheduling::fetch_pipeline::{FetchRequest, FetchSender};
+  use serde_json::{json, Value};
+  use std::sync::Arc;
+  use tauri::test::mock_app;
+  use tauri::Listener;
+  use tokio::sync::mpsc::unbounded_channel;
+
+  #[test]
+  fn launch_without_preset_is_listed_in_the_ui() {
+    let app = mock_app();
+    let (tx, mut rx) = unbounded_channel();
+    app.manage(FetchSender(tx));
+    app.manage(PendingLaunches::default());
+    let announced = Arc::new(Mutex::new(Vec::new()));
+    let sink = announced.clone();
+    app.listen_any("media_fetch", move |event| {
+      sink.lock().unwrap().push(event.payload().to_string());
+    });
+
+    handle_launch_args(app.handle(), ["https://example.com/video"]);
+    assert!(rx.try_recv().is_err(), "launches wait for the frontend");
+
+    flush_pending_launches(app.handle());
+    let Ok(DispatchRequest::Pipeline(FetchRequest::Initial {
+      group_id,
+      id,
+      url,
+      download_preset,
+    })) = rx.try_recv()
+    else {
+      panic!("the launch did not start a fetch");
+    };
+    assert_eq!(download_preset, None);
+
+    let announced = announced.lock().unwrap();
+    assert_eq!(announced.len(), 1);
+    let payload: Value = serde_json::from_str(&announced[0]).unwrap();
+    assert_eq!(
+      payload,
+      json!({ "groupId": group_id, "id": id, "url": url })
+    );
+  }
+}
@@ -0,0 +1,4 @@
+pub mod launch_args;
+pub mod launch_handoff;
+
+pub use launch_handoff::{flush_pending_launches, handle_launch_args, PendingLaunches};
@@ -1,11 +1,15 @@
 mod binaries;
 mod commands;
+#[cfg(feature = "headless")]
+mod headless;
 mod i18n;
+mod launch;
 mod logging;
 mod menu;
 mod models;
 mod parsers;
 mod paths;
+mod remote;
 mod runners;
 mod scheduling;
 mod state;
@@ -17,27 +21,48 @@ use crate::binaries::binaries_manager::BinariesManager;
 use crate::binaries::binaries_state::BinariesState;
 use crate::commands::*;
 use crate::i18n::I18nManager;
+use crate::launch::{handle_launch_args, PendingLaunches};
 use crate::logging::LogStoreState;
 use crate::menu::setup_menu;
 use crate::paths::PathsManager;
+use crate::remote::RemoteApiServer;
+use crate::runners::ytdlp_auth::{remove_stale_auth_files, AUTH_DIR_NAME};
+use crate::scheduling::bandwidth::{start_rate_limit_schedule, BandwidthManager};
 use crate::scheduling::concurrency::DynamicSemaphore;
-use crate::scheduling::download_pipeline::{setup_download_dispatcher, DownloadSender};
+use crate::scheduling::download_pipeline::{
+  restore_download_queue, setup_download_dispatcher, DownloadSender,
+};
 use crate::scheduling::fetch_pipeline::{setup_fetch_dispatcher, FetchSender};
+use crate::scheduling::proxy_pool::{start_proxy_health_checks, ProxyPool};
+use crate::scheduling::subscriptions::start_subscription_watcher;
+use crate::scheduling::watch_folders::start_folder_watcher;
+use crate::state::archive::DownloadArchive;
 use crate::state::config::ConfigHandle;
+use crate::state::history::{HistoryHandle, HISTORY_FILE};
 use crate::state::preferences::PreferencesHandle;
+use crate::state::presets::PresetsHandle;
+use crate::state::queue::QueueHandle;
+use crate::state::subscriptions::SubscriptionsHandle;
 use crate::tray::{create_tray, TrayState};
 use crate::window::{restore_main_window, setup_close_behaviour, track_main_window};
 use sentry::ClientInitGuard;
 use std::sync::{Arc, Mutex as StdMutex};
 use stronghold::stronghold_state;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
 use tokio::sync::Mutex;
 use tracing_subscriber::filter::{LevelFilter, Targets};
 use tracing_subscriber::{fmt, prelude::*};
 
+#[cfg(feature = "headless")]
+pub use crate::headless::run_headless;
+
 type SharedConfig = Arc<ConfigHandle>;
 type SharedPreferences = Arc<PreferencesHandle>;
+type SharedQueue = Arc<QueueHandle>;
+type SharedSubscriptions = Arc<SubscriptionsHandle>;
+type SharedHistory = Arc<HistoryHandle>;
+type SharedPresets = Arc<PresetsHandle>;
 
 #[derive(Clone)]
 pub struct DownloadLimiter(pub Arc<DynamicSemaphore>);
@@ -65,8 +90,10 @@ pub fn run() {
       MacosLauncher::LaunchAgent,
       Some(vec!["--auto-start"]),
     ))
-    .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-      reopen_window(app)
+    .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+      reopen_window(app);
+      // URLs and deep links opened while the app runs end up in the first instance.
+      handle_launch_args(app, args.into_iter().skip(1));
     }))
     .setup(|app| {
       let handle = app.handle();
@@ -84,18 +111,30 @@ pub fn run() {
 
       init_tracing();
 
-      // setup runtime mode detection / path management
-      let path_handle = PathsManager::new(handle);
-      handle.manage(path_handle.clone());
+      setup_core(handle)?;
+
+      // setup persistent download queue
+      let queue_handle = QueueHandle::init(handle)?;
+      handle.manage::<SharedQueue>(Arc::new(queue_handle));
+      restore_download_queue(handle, &handle.state::<DownloadSender>());
+
+      // setup channel and playlist subscriptions
+      let subscriptions_handle = SubscriptionsHandle::init(handle)?;
+      handle.manage::<SharedSubscriptions>(Arc::new(subscriptions_handle));
+      start_subscription_watcher(handle);
 
-      // setup config management
-      let config_handle = ConfigHandle::init(handle)?;
-      let shared_config = Arc::new(config_handle);
-      handle.manage::<SharedConfig>(shared_config);
+      // import link files dropped into watched folders
+      start_folder_watcher(handle);
 
-      let preferences_handle = PreferencesHandle::init(handle)?;
-      let shared_preferences = Arc::new(preferences_handle);
-      handle.manage::<SharedPreferences>(shared_preferences);
+      // setup local API for browser extensions and scripts
+      handle.manage(RemoteApiServer::new(handle));
+      handle
+        .state::<RemoteApiServer>()
+        .apply(&handle.state::<SharedConfig>().load().remote_api);
+
+      // fetch URLs the app was opened with once the frontend is ready
+      handle.manage(PendingLaunches::default());
+      handle_launch_args(handle, std::env::args().skip(1));
 
       // setup i18n management
       handle.manage(I18nManager::new(handle));
@@ -121,39 +160,6 @@ pub fn run() {
       // manage update store
       handle.manage(Mutex::new(UpdateStore::default()));
 
-      // manage log store
-      handle.manage(LogStoreState::new());
-
-      // setup dispatchers
-      let cfg_snapshot = handle.state::<SharedConfig>().load();
-      let max_concurrency = cfg_snapshot.performance.max_concurrency;
-      let download_limiter = Arc::new(DynamicSemaphore::new(max_concurrency));
-      let fetch_limiter = Arc::new(DynamicSemaphore::new(max_concurrency));
-      handle.manage(DownloadLimiter(download_limiter.clone()));
-      handle.manage(FetchLimiter(fetch_limiter.clone()));
-
-      let fetch_dispatcher = setup_fetch_dispatcher(handle, fetch_limiter);
-      handle.manage(FetchSender(fetch_dispatcher.sender()));
-      let download_dispatcher = setup_download_dispatcher(handle, download_limiter);
-      handle.manage(DownloadSender(download_dispatcher.sender()));
-
-      // setup binaries
-      handle.manage(BinariesState::default());
-      handle.manage(BinariesManager::new(handle));
-
-      // set up stronghold
-      let app_path = path_handle.app_dir();
-      let stronghold_path = app_path.join("vault.hold");
-
-      handle.manage(stronghold_state::StrongholdState::new(stronghold_path));
-
-      // async init stronghold
-      #[cfg(not(all(target_os = "macos", debug_assertions)))]
-      {
-        let state_ref = handle.state::<stronghold_state::StrongholdState>();
-        stronghold_state::init_on_startup(handle, &state_ref);
-      }
-
       // configure app menu
       setup_menu(handle);
 
@@ -167,11 +173,39 @@ pub fn run() {
       media_size,
       media_info,
       media_download,
+      media_pause,
+      media_resume,
+      media_stop_recording,
       group_cancel,
+      group_pause,
+      group_resume,
+      queue_list,
+      queue_scheduled,
+      archive_list,
+      archive_import,
+      archive_export,
+      archive_remove,
+      history_query,
+      history_open,
+      history_reveal,
+      history_redownload,
+      import_file,
+      subscription_add,
+      subscription_list,
+      subscription_remove,
+      subscription_check,
+      preset_list,
+      preset_save,
+      preset_remove,
+      preset_import,
+      preset_export,
       logging_subscribe,
       logging_unsubscribe,
       config_get,
       config_reset,
+      config_resolve_site,
+      proxy_status,
+      proxy_check,
       config_set,
       preferences_get,
       preferences_reset,
@@ -191,15 +225,24 @@ pub fn run() {
       stronghold_keys,
       stronghold_get,
       stronghold_set,
+      credentials_list,
+      credentials_create,
+      credentials_delete,
+      remote_token,
+      remote_rotate,
       get_platform,
       notify,
     ])
-    .build(tauri::generate_context!())
+    .build(app_context())
     .expect("error while running tauri application");
 
   app.run(|_app_handle, _event| {
     #[cfg(target_os = "macos")]
     {
+      // macOS hands deep links to the running app instead of passing arguments.
+      if let tauri::RunEvent::Opened { urls } = &_event {
+        handle_launch_args(_app_handle, urls.iter().map(ToString::to_string));
+      }
       if let tauri::RunEvent::Reopen {
         has_visible_windows,
         ..
@@ -213,6 +256,82 @@ pub fn run() {
   });
 }
 
+fn app_context<R: Runtime>() -> tauri::Context<R> {
+  tauri::generate_context!()
+}
+
+/// Sets up the state and pipelines downloads need, shared by the app and the headless CLI.
+/// Everything that needs a window, tray or the queue of the app is left to the caller.
+fn setup_core<R: Runtime>(handle: &AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
+  // setup runtime mode detection / path management
+  let path_handle = PathsManager::new(handle);
+  handle.manage(path_handle.clone());
+  remove_stale_auth_files(&path_handle.app_dir().join(AUTH_DIR_NAME));
+
+  // setup config management
+  let config_handle = ConfigHandle::init(handle)?;
+  let shared_config = Arc::new(config_handle);
+  handle.manage::<SharedConfig>(shared_config);
+
+  let preferences_handle = PreferencesHandle::init(handle)?;
+  let shared_preferences = Arc::new(preferences_handle);
+  handle.manage::<SharedPreferences>(shared_preferences);
+
+  // setup download archive
+  handle.manage(DownloadArchive::new(path_handle.app_dir()));
+
+  // setup download history
+  match HistoryHandle::open(&path_handle.app_dir().join(HISTORY_FILE)) {
+    Ok(history) => {
+      handle.manage::<SharedHistory>(Arc::new(history));
+    }
+    Err(e) => tracing::error!("Failed to open download history: {e}"),
+  }
+
+  // setup download presets
+  let presets_handle = PresetsHandle::init(handle)?;
+  handle.manage::<SharedPresets>(Arc::new(presets_handle));
+
+  // manage log store
+  handle.manage(LogStoreState::new());
+
+  // setup dispatchers
+  handle.manage(BandwidthManager::default());
+  handle.manage(ProxyPool::default());
+  let cfg_snapshot = handle.state::<SharedConfig>().load();
+  let max_concurrency = cfg_snapshot.performance.max_concurrency;
+  let download_limiter = Arc::new(DynamicSemaphore::new(max_concurrency));
+  let fetch_limiter = Arc::new(DynamicSemaphore::new(max_concurrency));
+  handle.manage(DownloadLimiter(download_limiter.clone()));
+  handle.manage(FetchLimiter(fetch_limiter.clone()));
+
+  let fetch_dispatcher = setup_fetch_dispatcher(handle, fetch_limiter);
+  handle.manage(FetchSender(fetch_dispatcher.sender()));
+  let download_dispatcher = setup_download_dispatcher(handle, download_limiter);
+  handle.manage(DownloadSender(download_dispatcher.sender()));
+  start_proxy_health_checks(handle);
+  start_rate_limit_schedule(handle);
+
+  // setup binaries
+  handle.manage(BinariesState::default());
+  handle.manage(BinariesManager::new(handle));
+
+  // set up stronghold
+  let app_path = path_handle.app_dir();
+  let stronghold_path = app_path.join("vault.hold");
+
+  handle.manage(stronghold_state::StrongholdState::new(stronghold_path));
+
+  // async init stronghold
+  #[cfg(not(all(target_os = "macos", debug_assertions)))]
+  {
+    let state_ref = handle.state::<stronghold_state::StrongholdState>();
+    stronghold_state::init_on_startup(handle, &state_ref);
+  }
+
+  Ok(())
+}
+
 pub fn init_tracing() {
   let fmt_layer = fmt::layer().with_filter(
     Targets::new()
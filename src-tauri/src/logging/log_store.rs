@@ -1,7 +1,7 @@
 use crate::models::payloads::AppendLogPayload;
 use indexmap::IndexMap;
 use std::collections::{HashSet, VecDeque};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Runtime};
 
 #[derive(Debug, Default)]
 pub struct GroupLog {
@@ -35,7 +35,7 @@ impl LogStore {
     self.subscribed.remove(group_id);
   }
 
-  pub fn append_lines<'a, I>(&mut self, app: &AppHandle, group: &str, lines: I)
+  pub fn append_lines<'a, R: Runtime, I>(&mut self, app: &AppHandle<R>, group: &str, lines: I)
   where
     I: IntoIterator<Item = &'a str>,
   {
@@ -44,7 +44,7 @@ impl LogStore {
     }
   }
 
-  pub fn append_line(&mut self, app: &AppHandle, group_id: &str, line: &str) {
+  pub fn append_line<R: Runtime>(&mut self, app: &AppHandle<R>, group_id: &str, line: &str) {
     let line_len = line.len();
 
     let entry = self.groups.entry(group_id.to_string()).or_default();
@@ -9,11 +9,122 @@ pub enum TrackType {
   Video,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum VideoContainer {
   Mp4,
   Mkv,
+  Webm,
+  Mov,
+}
+
+impl VideoContainer {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      VideoContainer::Mp4 => "mp4",
+      VideoContainer::Mkv => "mkv",
+      VideoContainer::Webm => "webm",
+      VideoContainer::Mov => "mov",
+    }
+  }
+
+  /// ffmpeg audio codec used when re-encoding into this container.
+  pub fn reencode_audio_codec(&self) -> &'static str {
+    match self {
+      VideoContainer::Mp4 | VideoContainer::Mov => "aac",
+      VideoContainer::Webm => "libopus",
+      VideoContainer::Mkv => "copy",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VideoCodec {
+  H264,
+  H265,
+  Vp9,
+  Av1,
+}
+
+impl VideoCodec {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      VideoCodec::H264 => "H.264",
+      VideoCodec::H265 => "H.265",
+      VideoCodec::Vp9 => "VP9",
+      VideoCodec::Av1 => "AV1",
+    }
+  }
+
+  pub fn encoder(&self) -> &'static str {
+    match self {
+      VideoCodec::H264 => "libx264",
+      VideoCodec::H265 => "libx265",
+      VideoCodec::Vp9 => "libvpx-vp9",
+      VideoCodec::Av1 => "libsvtav1",
+    }
+  }
+
+  pub fn fits_into(&self, container: VideoContainer) -> bool {
+    match container {
+      VideoContainer::Mkv => true,
+      VideoContainer::Mp4 => true,
+      VideoContainer::Webm => matches!(self, VideoCodec::Vp9 | VideoCodec::Av1),
+      VideoContainer::Mov => matches!(self, VideoCodec::H264 | VideoCodec::H265),
+    }
+  }
+
+  pub fn max_crf(&self) -> u8 {
+    match self {
+      VideoCodec::H264 | VideoCodec::H265 => 51,
+      VideoCodec::Vp9 | VideoCodec::Av1 => 63,
+    }
+  }
+
+  /// Encoder presets accepted for this codec, from fastest to slowest.
+  pub fn presets(&self) -> &'static [&'static str] {
+    match self {
+      VideoCodec::H264 | VideoCodec::H265 => &[
+        "ultrafast",
+        "superfast",
+        "veryfast",
+        "faster",
+        "fast",
+        "medium",
+        "slow",
+        "slower",
+        "veryslow",
+      ],
+      VideoCodec::Vp9 => &["realtime", "good", "best"],
+      VideoCodec::Av1 => &[
+        "13", "12", "11", "10", "9", "8", "7", "6", "5", "4", "3", "2", "1", "0",
+      ],
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum VideoQuality {
+  /// Constant quality, lower is better.
+  Crf { crf: u8 },
+  /// Average bitrate in KBit/s.
+  Bitrate { kbps: u32 },
+}
+
+/// What `TranscodePolicy::AllowReencode` encodes the video into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoReencodeTarget {
+  pub codec: VideoCodec,
+  pub quality: VideoQuality,
+  /// Encoder speed preset, see `VideoCodec::presets`. `None` uses the encoder's default.
+  #[serde(default)]
+  pub preset: Option<String>,
+  /// Scales larger videos down to this height, keeping the aspect ratio.
+  #[serde(default)]
+  pub max_height: Option<u32>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -40,6 +151,24 @@ impl AudioFormat {
   }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThumbnailFormat {
+  Jpg,
+  Png,
+  Webp,
+}
+
+impl ThumbnailFormat {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ThumbnailFormat::Jpg => "jpg",
+      ThumbnailFormat::Png => "png",
+      ThumbnailFormat::Webp => "webp",
+    }
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TranscodePolicy {
@@ -48,13 +177,150 @@ pub enum TranscodePolicy {
   AllowReencode,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadItem {
   pub id: String,
   pub url: String,
   pub format: FormatOptions,
   pub template_context: TemplateContext,
+  /// Records the item as a livestream when set.
+  #[serde(default)]
+  pub live: Option<LiveRecordingOptions>,
+  /// Id of the preset to download with instead of the global settings.
+  #[serde(default)]
+  pub preset: Option<String>,
+  /// Downloads only these parts of the video when set.
+  #[serde(default)]
+  pub sections: Option<SectionOptions>,
+  /// Tags to write into the file instead of the ones yt-dlp found.
+  #[serde(default)]
+  pub metadata: Option<MetadataOptions>,
+}
+
+/// Tags of a media file. Fetched videos fill it with what yt-dlp found, downloads use it to
+/// override those values; `None` keeps yt-dlp's value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MediaMetadata {
+  pub title: Option<String>,
+  pub artist: Option<String>,
+  pub album: Option<String>,
+  pub track_number: Option<u32>,
+  pub year: Option<u32>,
+  pub genre: Option<String>,
+  pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MetadataOptions {
+  pub fields: MediaMetadata,
+  /// Applied in order, before `fields`.
+  pub rewrites: Vec<MetadataRewrite>,
+  /// Fills album and track number from the playlist for playlist items.
+  pub playlist_as_album: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MetadataRewrite {
+  /// Matches `pattern` against `from`, a field name or output template, and stores the named
+  /// groups, e.g. `(?P<artist>.+?) - (?P<title>.+)` on `title`.
+  Parse { from: String, pattern: String },
+  /// Replaces every match of `regex` in the given fields.
+  Replace {
+    fields: Vec<String>,
+    regex: String,
+    replacement: String,
+  },
+}
+
+impl MetadataOptions {
+  pub fn validate(&self) -> Result<(), String> {
+    for rewrite in &self.rewrites {
+      match rewrite {
+        MetadataRewrite::Parse { from, pattern } => {
+          if from.trim().is_empty() || pattern.trim().is_empty() {
+            return Err("Metadata parsing needs a source and a pattern".into());
+          }
+        }
+        MetadataRewrite::Replace { fields, regex, .. } => {
+          if fields.iter().all(|f| f.trim().is_empty()) || regex.is_empty() {
+            return Err("Metadata replacement needs at least one field and a regex".into());
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SectionOptions {
+  pub ranges: Vec<TimeRange>,
+  /// Regular expressions matched against the chapter titles.
+  pub chapters: Vec<String>,
+  /// Re-encodes around the cuts so sections start exactly at the requested time.
+  pub force_keyframes_at_cuts: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRange {
+  /// Start in seconds.
+  pub start: f64,
+  /// End in seconds, `None` for the end of the video.
+  pub end: Option<f64>,
+}
+
+impl SectionOptions {
+  pub fn validate(&self) -> Result<(), String> {
+    if self.ranges.is_empty() && self.chapters.iter().all(|c| c.trim().is_empty()) {
+      return Err("Select at least one time range or chapter".into());
+    }
+    for range in &self.ranges {
+      if !range.start.is_finite() || range.start < 0.0 {
+        return Err(format!("Invalid section start {}", range.start));
+      }
+      if let Some(end) = range.end {
+        if !end.is_finite() || end <= range.start {
+          return Err(format!(
+            "Section end {end} must come after its start {}",
+            range.start
+          ));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Whether yt-dlp may write more than one file, which then need distinct names.
+  pub fn is_multi_part(&self) -> bool {
+    self.ranges.len() > 1 || !self.chapters.is_empty()
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LiveRecordingOptions {
+  /// Record from the start of the stream instead of from the moment the download starts.
+  pub from_start: bool,
+  /// Wait for scheduled streams, retrying every this many seconds. `None` fails right away.
+  pub wait_for_video_secs: Option<u64>,
+  /// Stops the recording cleanly after this many seconds.
+  pub max_duration_secs: Option<u64>,
+}
+
+impl Default for LiveRecordingOptions {
+  fn default() -> Self {
+    Self {
+      from_start: true,
+      wait_for_video_secs: Some(60),
+      max_duration_secs: None,
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,4 +334,23 @@ pub struct FormatOptions {
   pub video_codec: Option<String>,
   /// Optional preferred audio codec (e.g. aac, opus).
   pub audio_codec: Option<String>,
+  /// Exact yt-dlp format ids to download, e.g. a video id and an audio id to merge. Overrides
+  /// the preferences above when not empty.
+  #[serde(default)]
+  pub format_ids: Vec<String>,
+}
+
+impl Default for FormatOptions {
+  /// The best video with audio, without any limits.
+  fn default() -> Self {
+    Self {
+      track_type: TrackType::Both,
+      abr: None,
+      height: None,
+      fps: None,
+      video_codec: None,
+      audio_codec: None,
+      format_ids: vec![],
+    }
+  }
 }
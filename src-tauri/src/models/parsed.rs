@@ -1,9 +1,10 @@
+use crate::models::download::MediaMetadata;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ParsedMedia {
-  Single(ParsedSingleVideo),
+  Single(Box<ParsedSingleVideo>),
   Playlist(ParsedPlaylist),
   Livestream(ParsedLivestream),
 }
@@ -16,6 +17,38 @@ pub struct MediaFormat {
   pub height: Option<u64>,
   pub fps: Option<u64>,
   pub codecs: Vec<String>,
+  pub width: Option<u64>,
+  pub note: Option<String>,
+  pub ext: Option<String>,
+  pub container: Option<String>,
+  pub vcodec: Option<String>,
+  pub acodec: Option<String>,
+  /// Exact size when known, otherwise yt-dlp's estimate.
+  pub filesize: Option<u64>,
+  /// Total, video and audio bitrates in KBit/s.
+  pub tbr: Option<f64>,
+  pub vbr: Option<f64>,
+  /// e.g. `SDR`, `HDR10` or `HLG`.
+  pub dynamic_range: Option<String>,
+  pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaChapter {
+  pub title: Option<String>,
+  /// Start and end in seconds.
+  pub start: f64,
+  pub end: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaThumbnail {
+  pub id: Option<String>,
+  pub url: String,
+  pub width: Option<u32>,
+  pub height: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +58,8 @@ pub struct ParsedSingleVideo {
   pub url: Option<String>,
   pub title: Option<String>,
   pub thumbnail: Option<String>,
+  /// Every thumbnail size yt-dlp offers, smallest first.
+  pub thumbnails: Vec<MediaThumbnail>,
   pub description: Option<String>,
   pub uploader_id: Option<String>,
   pub uploader: Option<String>,
@@ -38,7 +73,17 @@ pub struct ParsedSingleVideo {
   pub video_codecs: Vec<String>,
   pub audio_codecs: Vec<String>,
   pub formats: Vec<MediaFormat>,
+  /// Every format yt-dlp offers, for picking exact format ids.
+  pub all_formats: Vec<MediaFormat>,
+  /// Chapters in playback order, for downloading only some of them.
+  pub chapters: Vec<MediaChapter>,
+  /// Tags yt-dlp would write, as the starting point for editing them.
+  pub metadata: MediaMetadata,
   pub filesize: Option<u64>,
+  /// The extractor's id of the video, as opposed to `id` which identifies the queue item.
+  pub video_id: Option<String>,
+  /// Whether the video is already in the download archive.
+  pub archived: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,13 +105,26 @@ pub struct ParsedPlaylist {
 pub struct PlaylistEntry {
   pub video_url: String,
   pub index: usize,
+  pub id: Option<String>,
+  pub extractor: Option<String>,
+  pub title: Option<String>,
+  /// Whether the entry is already in the download archive.
+  pub archived: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ParsedLivestream {
   pub id: String,
   pub url: Option<String>,
   pub title: Option<String>,
   pub uploader: Option<String>,
+  pub uploader_id: Option<String>,
+  pub thumbnail: Option<String>,
+  pub extractor: Option<String>,
+  pub video_id: Option<String>,
+  /// yt-dlp's `live_status`, e.g. `is_live` or `is_upcoming`.
+  pub live_status: Option<String>,
+  /// Unix time in seconds at which an upcoming stream is scheduled to start.
+  pub release_timestamp: Option<i64>,
 }
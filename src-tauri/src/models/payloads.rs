@@ -118,6 +118,39 @@ impl MediaFatalPayload {
   }
 }
 
+/// Sent before fetching a URL that was not added in the UI, so the frontend can list its group.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaFetchPayload {
+  pub group_id: String,
+  pub id: String,
+  pub url: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaRetryPayload {
+  pub group_id: String,
+  pub id: String,
+  /// The attempt that is about to start, starting at 2 for the first retry.
+  pub attempt: u32,
+  pub max_attempts: u32,
+  pub delay_ms: u64,
+  pub codes: Vec<String>,
+}
+
+/// Sent once every item of a download group finished, was skipped, failed or was cancelled.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupCompletePayload {
+  pub group_id: String,
+  pub completed: usize,
+  /// Items that were already in the download archive.
+  pub skipped: usize,
+  pub failed: usize,
+  pub cancelled: usize,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NavigatePayload {
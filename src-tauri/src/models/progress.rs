@@ -7,6 +7,10 @@ pub struct MediaDestination {
   pub group_id: String,
   pub destination: MediaDestinationPath,
   pub is_merged: bool,
+  /// One-based chapter number when the file is one of the chapters split off the download.
+  pub chapter: Option<u32>,
+  /// Thumbnail written next to the download.
+  pub is_thumbnail: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -25,6 +29,12 @@ pub struct MediaProgress {
   pub percentage: Option<f64>,
   pub speed_bps: Option<f64>,
   pub eta_secs: Option<u64>,
+  pub downloaded_bytes: Option<u64>,
+  /// Seconds since the download started; the measure of progress for livestreams.
+  pub elapsed_secs: Option<f64>,
+  /// Zero-based section being downloaded when only parts of the video are requested.
+  pub section_index: Option<u32>,
+  pub section_count: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -66,4 +76,7 @@ pub enum ProgressStage {
   Downloading,
   Merging,
   Finalizing,
+  Paused,
+  /// A livestream recording is being stopped and its file finalised.
+  Stopping,
 }
@@ -17,25 +17,48 @@ pub struct YtdlpInfo {
   pub thumbnail: Option<String>,
   pub thumbnails: Option<Vec<YtdlpThumbnail>>,
   pub formats: Option<Vec<YtdlpFormat>>,
+  pub chapters: Option<Vec<YtdlpChapter>>,
   #[serde(rename = "type_")]
   pub type_: Option<String>,
   pub is_live: Option<bool>,
+  pub live_status: Option<String>,
+  pub release_timestamp: Option<i64>,
   pub entries: Option<Vec<YtdlpEntry>>,
   pub webpage_url: Option<String>,
   pub filesize: Option<i64>,
   pub filesize_approx: Option<i64>,
   pub playlist_count: Option<i64>,
+  pub artist: Option<String>,
+  pub artists: Option<Vec<String>>,
+  pub album: Option<String>,
+  pub track: Option<String>,
+  pub track_number: Option<i64>,
+  pub release_year: Option<i64>,
+  pub upload_date: Option<String>,
+  pub genre: Option<String>,
+  pub genres: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct YtdlpThumbnail {
+  pub id: Option<String>,
   pub url: Option<String>,
   pub width: Option<i32>,
   pub height: Option<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct YtdlpChapter {
+  pub start_time: Option<f64>,
+  pub end_time: Option<f64>,
+  pub title: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct YtdlpEntry {
+  pub id: Option<String>,
+  pub ie_key: Option<String>,
+  pub title: Option<String>,
   pub url: Option<String>,
   pub webpage_url: Option<String>,
 }
@@ -43,10 +66,19 @@ pub struct YtdlpEntry {
 #[derive(Debug, Deserialize)]
 pub struct YtdlpFormat {
   pub format_id: Option<String>,
+  pub format_note: Option<String>,
+  pub width: Option<i64>,
   pub height: Option<i64>,
   pub fps: Option<f64>,
   pub abr: Option<f64>,
+  pub tbr: Option<f64>,
+  pub vbr: Option<f64>,
   pub ext: Option<String>,
+  pub container: Option<String>,
   pub vcodec: Option<String>,
   pub acodec: Option<String>,
+  pub filesize: Option<i64>,
+  pub filesize_approx: Option<i64>,
+  pub dynamic_range: Option<String>,
+  pub language: Option<String>,
 }
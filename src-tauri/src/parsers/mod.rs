@@ -1,3 +1,4 @@
+pub mod url_list;
 pub mod ytdlp_error;
 pub mod ytdlp_info;
 pub mod ytdlp_livestream;
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+/// URLs in a plain text list. Blank lines and lines starting with `#` or `;` are skipped.
+pub fn parse_url_list(text: &str) -> Vec<String> {
+  text
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+    .map(String::from)
+    .collect()
+}
+
+/// Web URLs in a file dropped by a user, read by its extension: `.url` internet shortcuts,
+/// XML `.webloc` files, exported browser bookmarks in `.html`, and plain lists otherwise.
+pub fn parse_url_file(path: &Path, text: &str) -> Vec<String> {
+  let extension = path
+    .extension()
+    .and_then(|e| e.to_str())
+    .map(str::to_ascii_lowercase);
+  let urls = match extension.as_deref() {
+    Some("url") => parse_internet_shortcut(text),
+    Some("webloc") => parse_webloc(text),
+    Some("html" | "htm") => parse_bookmarks(text),
+    _ => parse_url_list(text),
+  };
+  urls.into_iter().filter(|url| is_web_url(url)).collect()
+}
+
+/// Whether the value is an `http` or `https` URL, the only ones handed to yt-dlp from outside
+/// the app so they cannot point it at local files or search prefixes.
+pub fn is_web_url(value: &str) -> bool {
+  let lower = value.to_ascii_lowercase();
+  (lower.starts_with("https://") || lower.starts_with("http://"))
+    && !value.contains(char::is_whitespace)
+}
+
+/// `URL=` entry of a Windows `[InternetShortcut]` file.
+fn parse_internet_shortcut(text: &str) -> Vec<String> {
+  text
+    .lines()
+    .map(str::trim)
+    .find_map(|line| {
+      let (key, value) = line.split_once('=')?;
+      key
+        .eq_ignore_ascii_case("URL")
+        .then(|| value.trim().to_string())
+    })
+    .into_iter()
+    .collect()
+}
+
+/// The string after `<key>URL</key>` in an XML property list. Binary `.webloc` files are not
+/// supported.
+fn parse_webloc(text: &str) -> Vec<String> {
+  let Some(key) = text.find("<key>URL</key>") else {
+    return vec![];
+  };
+  let rest = &text[key..];
+  let value = rest
+    .find("<string>")
+    .map(|start| &rest[start + "<string>".len()..])
+    .and_then(|value| value.find("</string>").map(|end| &value[..end]));
+  value
+    .map(|value| decode_entities(value.trim()))
+    .into_iter()
+    .collect()
+}
+
+/// Every `href` of a Netscape bookmark file, as exported by browsers.
+fn parse_bookmarks(text: &str) -> Vec<String> {
+  // Lowercasing ASCII keeps byte offsets, so they apply to the original text.
+  let lower = text.to_ascii_lowercase();
+  let mut urls = Vec::new();
+  let mut offset = 0;
+  while let Some(found) = lower[offset..].find("href=\"") {
+    let start = offset + found + "href=\"".len();
+    let Some(len) = text[start..].find('"') else {
+      break;
+    };
+    urls.push(decode_entities(&text[start..start + len]));
+    offset = start + len;
+  }
+  urls
+}
+
+fn decode_entities(value: &str) -> String {
+  value
+    .replace("&quot;", "\"")
+    .replace("&#39;", "'")
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn url_list_skips_comments_and_blank_lines() {
+    let text = "# queue\nhttps://a.example/1\n\n  ; later\n  https://a.example/2  \n";
+    assert_eq!(
+      parse_url_list(text),
+      vec!["https://a.example/1", "https://a.example/2"]
+    );
+  }
+
+  #[test]
+  fn parses_files_by_extension() {
+    let shortcut = "[InternetShortcut]\r\nIconIndex=0\r\nURL=https://a.example/watch?v=1\r\n";
+    assert_eq!(
+      parse_url_file(Path::new("Clip.URL"), shortcut),
+      vec!["https://a.example/watch?v=1"]
+    );
+
+    let webloc = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict>
+  <key>URL</key>
+  <string>https://a.example/watch?v=2&amp;t=5</string>
+</dict></plist>"#;
+    assert_eq!(
+      parse_url_file(Path::new("clip.webloc"), webloc),
+      vec!["https://a.example/watch?v=2&t=5"]
+    );
+
+    let bookmarks = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+  <DT><A HREF="https://a.example/3" ADD_DATE="1" ICON="data:image/png;base64,AA">Three</A>
+  <DT><A HREF="javascript:void(0)">Bookmarklet</A>
+  <DT><a href="http://a.example/4?x=1&amp;y=2">Four</a>
+</DL>"#;
+    assert_eq!(
+      parse_url_file(Path::new("bookmarks.html"), bookmarks),
+      vec!["https://a.example/3", "http://a.example/4?x=1&y=2"]
+    );
+
+    let list = "https://a.example/5\nfile:///etc/passwd\nytsearch:cats\n";
+    assert_eq!(
+      parse_url_file(Path::new("links.txt"), list),
+      vec!["https://a.example/5"]
+    );
+  }
+}
@@ -20,7 +20,9 @@ pub fn parse_ytdlp_info(json: &str, id: String) -> Result<ParsedMedia, String> {
 }
 
 pub fn detect_info_type(info: &YtdlpInfo) -> InfoType {
-  if info.is_live.unwrap_or(false) {
+  if info.is_live.unwrap_or(false)
+    || matches!(info.live_status.as_deref(), Some("is_live" | "is_upcoming"))
+  {
     InfoType::Livestream
   } else if info.type_.as_deref() == Some("playlist")
     || info.entries.as_ref().is_some_and(|e| !e.is_empty())
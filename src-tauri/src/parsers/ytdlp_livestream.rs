@@ -7,5 +7,12 @@ pub fn parse_livestream(info: YtdlpInfo, id: String) -> ParsedMedia {
     url: info.webpage_url,
     title: info.title,
     uploader: info.uploader,
+    uploader_id: info.uploader_id,
+    thumbnail: info.thumbnail,
+    extractor: info.extractor_key,
+    video_id: info.id,
+    // Always set, so the frontend can tell livestreams from other items.
+    live_status: info.live_status.or_else(|| Some("is_live".into())),
+    release_timestamp: info.release_timestamp,
   })
 }
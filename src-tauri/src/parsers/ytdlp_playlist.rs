@@ -16,6 +16,10 @@ pub fn parse_playlist(info: YtdlpInfo, id: String) -> ParsedMedia {
         entries.push(PlaylistEntry {
           video_url: url,
           index: idx,
+          id: entry.id.clone(),
+          extractor: entry.ie_key.clone(),
+          title: entry.title.clone(),
+          archived: false,
         });
       }
     }
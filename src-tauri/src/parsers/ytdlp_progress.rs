@@ -10,6 +10,12 @@ pub struct YtdlpProgressParser {
   group_id: String,
   current_category: ProgressCategory,
   current_stage: ProgressStage,
+  /// Whether thumbnail files stay after embedding and should be reported.
+  thumbnail_files: bool,
+  /// Number of time ranges yt-dlp announced, `None` when the whole video is downloaded.
+  section_count: Option<u32>,
+  /// Media files started so far; each section is downloaded into its own file.
+  sections_started: u32,
 }
 
 impl YtdlpProgressParser {
@@ -19,12 +25,34 @@ impl YtdlpProgressParser {
       group_id: group_id.to_string(),
       current_category: ProgressCategory::Other,
       current_stage: ProgressStage::Initializing,
+      thumbnail_files: false,
+      section_count: None,
+      sections_started: 0,
     }
   }
 
+  pub fn with_thumbnail_files(mut self, thumbnail_files: bool) -> Self {
+    self.thumbnail_files = thumbnail_files;
+    self
+  }
+
   pub fn parse_line(&mut self, line: &str) -> Vec<ProgressEvent> {
     let mut evts = Vec::new();
 
+    if self.try_section_count(line) {
+      return evts;
+    }
+
+    if let Some(evt) = self.try_thumbnail_destination(line) {
+      evts.push(evt);
+      return evts;
+    }
+
+    if let Some(evt) = self.try_chapter_destination(line) {
+      evts.push(evt);
+      return evts;
+    }
+
     if let Some(evt) = self.try_destination(line) {
       evts.push(evt);
     }
@@ -56,6 +84,76 @@ impl YtdlpProgressParser {
     evts
   }
 
+  /// Picks up `[info] <id>: Downloading 3 time ranges: ...`.
+  fn try_section_count(&mut self, line: &str) -> bool {
+    let Some(rest) = line.strip_prefix("[info] ") else {
+      return false;
+    };
+    let Some((_, rest)) = rest.split_once(": Downloading ") else {
+      return false;
+    };
+    let Some((count, _)) = rest.split_once(" time ranges") else {
+      return false;
+    };
+    match count.trim().parse() {
+      Ok(count) => {
+        self.section_count = Some(count);
+        true
+      }
+      Err(_) => false,
+    }
+  }
+
+  /// Picks up `[info] Writing video thumbnail 41 to: <path>` and the converted file of
+  /// `[ThumbnailsConvertor] Converting thumbnail "<path>" to jpg`.
+  fn try_thumbnail_destination(&self, line: &str) -> Option<ProgressEvent> {
+    if !self.thumbnail_files {
+      return None;
+    }
+
+    let path = if let Some(rest) = line.strip_prefix("[info] Writing video thumbnail") {
+      rest.split_once(" to: ")?.1.trim().to_string()
+    } else {
+      let rest = line.strip_prefix("[ThumbnailsConvertor] Converting thumbnail \"")?;
+      let (source, format) = rest.rsplit_once("\" to ")?;
+      Path::new(source)
+        .with_extension(format.trim())
+        .to_string_lossy()
+        .into_owned()
+    };
+
+    Some(ProgressEvent::Destination(MediaDestination {
+      id: self.id.clone(),
+      group_id: self.group_id.clone(),
+      destination: MediaDestinationPath {
+        confidence: 100,
+        path,
+      },
+      is_merged: false,
+      chapter: None,
+      is_thumbnail: true,
+    }))
+  }
+
+  /// Picks up `[SplitChapters] Chapter 001; Destination: <path>`, one line per chapter file.
+  fn try_chapter_destination(&self, line: &str) -> Option<ProgressEvent> {
+    let rest = line.strip_prefix("[SplitChapters] Chapter ")?;
+    let (number, path) = rest.split_once("; Destination:")?;
+    let number = number.trim().parse().ok()?;
+
+    Some(ProgressEvent::Destination(MediaDestination {
+      id: self.id.clone(),
+      group_id: self.group_id.clone(),
+      destination: MediaDestinationPath {
+        confidence: 100,
+        path: path.trim().to_string(),
+      },
+      is_merged: false,
+      chapter: Some(number),
+      is_thumbnail: false,
+    }))
+  }
+
   fn try_merger_destination(&self, line: &str) -> Option<ProgressEvent> {
     const PREFIX: &str = "[Merger] Merging formats into";
     if let Some(rest) = line.strip_prefix(PREFIX) {
@@ -65,6 +163,8 @@ impl YtdlpProgressParser {
         id: self.id.clone(),
         group_id: self.group_id.clone(),
         is_merged: true,
+        chapter: None,
+        is_thumbnail: false,
         destination: MediaDestinationPath {
           confidence: 80,
           path: destination,
@@ -84,6 +184,8 @@ impl YtdlpProgressParser {
           path,
         },
         is_merged: false,
+        chapter: None,
+        is_thumbnail: false,
       }));
     }
 
@@ -96,6 +198,8 @@ impl YtdlpProgressParser {
           path,
         },
         is_merged: false,
+        chapter: None,
+        is_thumbnail: false,
       }));
     }
 
@@ -124,6 +228,8 @@ impl YtdlpProgressParser {
         path: full_path,
       },
       is_merged: false,
+      chapter: None,
+      is_thumbnail: false,
     }))
   }
 
@@ -173,6 +279,12 @@ impl YtdlpProgressParser {
           "json" => ProgressCategory::Metadata,
           _ => ProgressCategory::Other,
         };
+        if matches!(
+          self.current_category,
+          ProgressCategory::Video | ProgressCategory::Audio
+        ) {
+          self.sections_started += 1;
+        }
       }
       if self.current_stage != ProgressStage::Downloading {
         self.current_stage = ProgressStage::Downloading;
@@ -231,9 +343,9 @@ impl YtdlpProgressParser {
     let line = line.trim_end();
     let raw = line.strip_prefix("RAW|")?;
 
-    // Expect 9 fields from a progress line, fewer is okay.
+    // Expect 10 fields from a progress line, fewer is okay.
     let mut parts: Vec<&str> = raw.split('|').collect();
-    while parts.len() < 9 {
+    while parts.len() < 10 {
       parts.push("");
     }
 
@@ -246,6 +358,7 @@ impl YtdlpProgressParser {
     let estimate = parse_opt_u64(get_or_empty(&parts, 6)); // total_bytes_estimate
     let frag_i = parse_opt_u64(get_or_empty(&parts, 7)); // fragment_index
     let frag_n = parse_opt_u64(get_or_empty(&parts, 8)); // fragment_count
+    let elapsed_secs = parse_opt_f64(get_or_empty(&parts, 9)); // progress.elapsed (seconds)
 
     let percentage = pct_num
       .or(pct_str)
@@ -269,10 +382,29 @@ impl YtdlpProgressParser {
       .map(clamp01pct);
 
     // If no data is retrieved, drop the line.
-    if percentage.is_none() && speed_bps.is_none() && eta_secs.is_none() {
+    if percentage.is_none()
+      && speed_bps.is_none()
+      && eta_secs.is_none()
+      && dl.is_none()
+      && elapsed_secs.is_none()
+    {
       return None;
     }
 
+    // Sections are downloaded one after another, so spread them over the overall percentage.
+    let section_index = self.section_count.map(|count| {
+      self
+        .sections_started
+        .saturating_sub(1)
+        .min(count.saturating_sub(1))
+    });
+    let percentage = match (percentage, section_index, self.section_count) {
+      (Some(pct), Some(index), Some(count)) if count > 0 => {
+        Some((index as f64 * 100.0 + pct) / count as f64)
+      }
+      _ => percentage,
+    };
+
     Some(ProgressEvent::Progress(MediaProgress {
       id: self.id.clone(),
       group_id: self.group_id.clone(),
@@ -280,6 +412,10 @@ impl YtdlpProgressParser {
       percentage,
       speed_bps,
       eta_secs,
+      downloaded_bytes: dl,
+      elapsed_secs,
+      section_index,
+      section_count: self.section_count,
     }))
   }
 
@@ -312,3 +448,97 @@ impl YtdlpProgressParser {
     None
   }
 }
+
+/// Whether the line reports that yt-dlp skipped the video because it is in the download archive.
+pub fn is_archive_skip(line: &str) -> bool {
+  line.starts_with("[download] ")
+    && line
+      .trim_end()
+      .ends_with("has already been recorded in the archive")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn archive_skips_are_recognized() {
+    assert!(is_archive_skip(
+      "[download] Some video has already been recorded in the archive\n"
+    ));
+    assert!(!is_archive_skip("[download] Destination: /tmp/archive.mp4"));
+    assert!(!is_archive_skip(
+      "[info] has already been recorded in the archive"
+    ));
+  }
+
+  fn percentage(evts: &[ProgressEvent]) -> Option<(f64, Option<u32>)> {
+    evts.iter().find_map(|evt| match evt {
+      ProgressEvent::Progress(p) => Some((p.percentage?, p.section_index)),
+      _ => None,
+    })
+  }
+
+  #[test]
+  fn sections_are_spread_over_the_overall_percentage() {
+    let mut parser = YtdlpProgressParser::new("1", "g");
+    assert!(parser
+      .parse_line("[info] abc: Downloading 2 time ranges: 10.0-20.0,30.0-40.0")
+      .is_empty());
+
+    parser.parse_line("[download] Destination: /tmp/clip (10).mp4");
+    let evts = parser.parse_line("RAW|50.0|50.0%|||||||");
+    assert_eq!(percentage(&evts), Some((25.0, Some(0))));
+
+    parser.parse_line("[download] Destination: /tmp/clip (30).mp4");
+    let evts = parser.parse_line("RAW|50.0|50.0%|||||||");
+    assert_eq!(percentage(&evts), Some((75.0, Some(1))));
+  }
+
+  #[test]
+  fn split_chapters_are_reported_as_separate_destinations() {
+    let mut parser = YtdlpProgressParser::new("1", "g");
+    assert!(parser
+      .parse_line("[SplitChapters] Splitting video by chapters; 2 chapters found")
+      .is_empty());
+
+    let evts = parser.parse_line("[SplitChapters] Chapter 002; Destination: /tmp/Talk/002 Q&A.mp4");
+    match evts.as_slice() {
+      [ProgressEvent::Destination(destination)] => {
+        assert_eq!(destination.chapter, Some(2));
+        assert_eq!(destination.destination.path, "/tmp/Talk/002 Q&A.mp4");
+      }
+      other => panic!("unexpected events: {other:?}"),
+    }
+  }
+
+  #[test]
+  fn thumbnail_files_are_reported_when_kept() {
+    let line = "[info] Writing video thumbnail 41 to: /tmp/video.webp";
+    assert!(YtdlpProgressParser::new("1", "g")
+      .parse_line(line)
+      .is_empty());
+
+    let mut parser = YtdlpProgressParser::new("1", "g").with_thumbnail_files(true);
+    let paths: Vec<String> = [
+      line,
+      "[ThumbnailsConvertor] Converting thumbnail \"/tmp/video.webp\" to jpg",
+    ]
+    .iter()
+    .flat_map(|line| parser.parse_line(line))
+    .filter_map(|evt| match evt {
+      ProgressEvent::Destination(d) if d.is_thumbnail => Some(d.destination.path),
+      _ => None,
+    })
+    .collect();
+    assert_eq!(paths, vec!["/tmp/video.webp", "/tmp/video.jpg"]);
+  }
+
+  #[test]
+  fn whole_video_keeps_its_percentage() {
+    let mut parser = YtdlpProgressParser::new("1", "g");
+    parser.parse_line("[download] Destination: /tmp/video.mp4");
+    let evts = parser.parse_line("RAW|40.0|40.0%|||||||");
+    assert_eq!(percentage(&evts), Some((40.0, None)));
+  }
+}
@@ -1,3 +1,5 @@
+use crate::models::download::MediaMetadata;
+use crate::models::parsed::{MediaChapter, MediaThumbnail};
 use crate::models::ytdlp::YtdlpFormat;
 use crate::models::{MediaFormat, ParsedMedia, ParsedSingleVideo, YtdlpInfo};
 use std::collections::HashSet;
@@ -9,6 +11,89 @@ pub fn parse_single(info: YtdlpInfo, id: String) -> ParsedMedia {
     (HashSet::new(), HashSet::new(), Vec::new())
   };
 
+  let all_formats: Vec<MediaFormat> = info
+    .formats
+    .as_deref()
+    .unwrap_or_default()
+    .iter()
+    .filter(|fmt| fmt.ext.as_deref() != Some("mhtml"))
+    .filter_map(|fmt| {
+      let id = fmt.format_id.clone()?;
+      let fps = fmt.fps.filter(|f| *f > 0.0).map(|f| f.round() as u64);
+      let abr = fmt.abr.filter(|a| *a > 0.0).map(|a| a.round() as u64);
+      let codecs = [&fmt.vcodec, &fmt.acodec]
+        .into_iter()
+        .flatten()
+        .filter(|codec| codec.as_str() != "none")
+        .map(|codec| codec.to_lowercase())
+        .collect();
+      Some(detailed_format(
+        fmt,
+        id,
+        i64_to_u64(fmt.height),
+        fps,
+        abr,
+        codecs,
+      ))
+    })
+    .collect();
+
+  let chapters: Vec<MediaChapter> = info
+    .chapters
+    .as_deref()
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|chapter| {
+      Some(MediaChapter {
+        title: chapter.title.clone(),
+        start: chapter.start_time?,
+        end: chapter.end_time?,
+      })
+    })
+    .collect();
+
+  let mut thumbnails: Vec<MediaThumbnail> = info
+    .thumbnails
+    .as_deref()
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|thumb| {
+      Some(MediaThumbnail {
+        id: thumb.id.clone(),
+        url: thumb.url.clone()?,
+        width: thumb.width.and_then(|w| u32::try_from(w).ok()),
+        height: thumb.height.and_then(|h| u32::try_from(h).ok()),
+      })
+    })
+    .collect();
+  thumbnails.sort_by_key(|thumb| {
+    u64::from(thumb.width.unwrap_or(0)) * u64::from(thumb.height.unwrap_or(0))
+  });
+
+  let metadata = MediaMetadata {
+    title: info.track.clone().or_else(|| info.title.clone()),
+    artist: info
+      .artists
+      .as_ref()
+      .filter(|artists| !artists.is_empty())
+      .map(|artists| artists.join(", "))
+      .or_else(|| info.artist.clone())
+      .or_else(|| info.uploader.clone()),
+    album: info.album.clone(),
+    track_number: info.track_number.and_then(|n| u32::try_from(n).ok()),
+    year: info
+      .release_year
+      .and_then(|y| u32::try_from(y).ok())
+      .or_else(|| info.upload_date.as_ref()?.get(..4)?.parse().ok()),
+    genre: info
+      .genres
+      .as_ref()
+      .filter(|genres| !genres.is_empty())
+      .map(|genres| genres.join(", "))
+      .or_else(|| info.genre.clone()),
+    description: info.description.clone(),
+  };
+
   let detected = media_formats.len();
   let provided = info.formats.as_ref().map_or(0, Vec::len);
   let loss_pct = if provided == 0 {
@@ -24,11 +109,14 @@ pub fn parse_single(info: YtdlpInfo, id: String) -> ParsedMedia {
     loss_pct,
   );
 
-  ParsedMedia::Single(ParsedSingleVideo {
+  ParsedMedia::Single(Box::new(ParsedSingleVideo {
     id,
+    video_id: info.id,
+    archived: false,
     url: info.webpage_url,
     title: info.title,
     thumbnail: info.thumbnail,
+    thumbnails,
     uploader: info.uploader,
     uploader_id: info.uploader_id,
     duration: info.duration,
@@ -43,7 +131,10 @@ pub fn parse_single(info: YtdlpInfo, id: String) -> ParsedMedia {
     video_codecs: video_codecs.into_iter().collect(),
     audio_codecs: audio_codecs.into_iter().collect(),
     formats: media_formats,
-  })
+    all_formats,
+    chapters,
+    metadata,
+  }))
 }
 
 fn process_formats(
@@ -109,13 +200,14 @@ fn process_formats(
 
     let codecs = collect_codecs_for_group(formats, height, fps, abr);
 
-    media_formats.push(MediaFormat {
-      id: format_id.clone(),
-      abr,
-      height: i64_to_u64(height),
+    media_formats.push(detailed_format(
+      fmt,
+      format_id.clone(),
+      i64_to_u64(height),
       fps,
-      codecs: codecs.into_iter().collect(),
-    });
+      abr,
+      codecs.into_iter().collect(),
+    ));
 
     detected.insert(key);
   }
@@ -123,6 +215,34 @@ fn process_formats(
   (video_codecs, audio_codecs, media_formats)
 }
 
+fn detailed_format(
+  fmt: &YtdlpFormat,
+  id: String,
+  height: Option<u64>,
+  fps: Option<u64>,
+  abr: Option<u64>,
+  codecs: Vec<String>,
+) -> MediaFormat {
+  MediaFormat {
+    id,
+    abr,
+    height,
+    fps,
+    codecs,
+    width: i64_to_u64(fmt.width),
+    note: fmt.format_note.clone(),
+    ext: fmt.ext.clone(),
+    container: fmt.container.clone(),
+    vcodec: fmt.vcodec.clone().filter(|codec| codec != "none"),
+    acodec: fmt.acodec.clone().filter(|codec| codec != "none"),
+    filesize: i64_to_u64(fmt.filesize.or(fmt.filesize_approx)),
+    tbr: fmt.tbr,
+    vbr: fmt.vbr,
+    dynamic_range: fmt.dynamic_range.clone(),
+    language: fmt.language.clone(),
+  }
+}
+
 #[allow(clippy::cast_possible_truncation)]
 fn collect_codecs_for_group(
   formats: &[YtdlpFormat],
@@ -1,5 +1,5 @@
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime};
 
 const PORTABLE_DIR: &str = "ovd-portable";
 const BIN_DIR: &str = "bin";
@@ -16,7 +16,7 @@ pub struct PathsManager {
 }
 
 impl PathsManager {
-  pub fn new(app: &AppHandle) -> Self {
+  pub fn new<R: Runtime>(app: &AppHandle<R>) -> Self {
     let (app_dir, is_portable_app, is_snap_app) = Self::resolve_app_dir(app);
     let (bin_dir, is_microsoft_store_app) = Self::resolve_bin_dir(app_dir.clone());
 
@@ -90,7 +90,7 @@ impl PathsManager {
     )
   }
 
-  fn resolve_app_dir(app: &AppHandle) -> (PathBuf, bool, bool) {
+  fn resolve_app_dir<R: Runtime>(app: &AppHandle<R>) -> (PathBuf, bool, bool) {
     let app_data_dir = app
       .path()
       .app_data_dir()
@@ -0,0 +1,4 @@
+pub mod remote_rpc;
+pub mod remote_server;
+
+pub use remote_server::RemoteApiServer;
@@ -0,0 +1,209 @@
+use crate::commands::{group_cancel, media_download, media_info, queue_list, queue_scheduled};
+use crate::models::DownloadItem;
+use crate::scheduling::fetch_pipeline::announce_fetch;
+use crate::scheduling::schedule::DownloadSchedule;
+use crate::state::queue_models::QueueEntryState;
+use crate::SharedQueue;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// The command ran and returned an error.
+pub const COMMAND_FAILED: i64 = -32000;
+
+#[derive(Debug, PartialEq)]
+pub struct RpcCall {
+  /// `None` for notifications, which get no response.
+  pub id: Option<Value>,
+  pub method: String,
+  pub params: Value,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RpcError {
+  pub code: i64,
+  pub message: String,
+}
+
+impl RpcError {
+  fn new(code: i64, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MediaInfoParams {
+  url: String,
+  id: Option<String>,
+  group_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MediaDownloadParams {
+  group_id: Option<String>,
+  items: Vec<DownloadItem>,
+  schedule: Option<DownloadSchedule>,
+  preset: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupParams {
+  group_id: String,
+}
+
+/// Parses a JSON-RPC 2.0 request. Batches are not supported.
+pub fn parse_call(body: &[u8]) -> Result<RpcCall, RpcError> {
+  let value: Value =
+    serde_json::from_slice(body).map_err(|e| RpcError::new(PARSE_ERROR, e.to_string()))?;
+  let Value::Object(mut request) = value else {
+    return Err(RpcError::new(INVALID_REQUEST, "expected a request object"));
+  };
+  if request.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+    return Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+  }
+  let Some(Value::String(method)) = request.remove("method") else {
+    return Err(RpcError::new(INVALID_REQUEST, "method is missing"));
+  };
+  let params = match request.remove("params") {
+    None | Some(Value::Null) => Value::Object(Default::default()),
+    Some(params @ Value::Object(_)) => params,
+    Some(_) => return Err(RpcError::new(INVALID_PARAMS, "params must be an object")),
+  };
+
+  Ok(RpcCall {
+    id: request.remove("id"),
+    method,
+    params,
+  })
+}
+
+/// Runs the command behind the call, like the frontend would invoke it.
+pub fn dispatch(app: &AppHandle, call: RpcCall) -> Result<Value, RpcError> {
+  let failed = |e: String| RpcError::new(COMMAND_FAILED, e);
+
+  match call.method.as_str() {
+    "media_info" => {
+      let params: MediaInfoParams = params(call.params)?;
+      let id = params.id.unwrap_or_else(|| Uuid::new_v4().to_string());
+      let group_id = params
+        .group_id
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+      announce_fetch(app, &group_id, &id, &params.url);
+      let group_id = media_info(params.url, id.clone(), group_id, app.state()).map_err(failed)?;
+      Ok(json!({ "groupId": group_id, "id": id }))
+    }
+    "media_download" => {
+      let params: MediaDownloadParams = params(call.params)?;
+      let group_id = params
+        .group_id
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+      let group_id = media_download(
+        group_id,
+        params.items,
+        params.schedule,
+        params.preset,
+        app.state(),
+        app.state(),
+      )
+      .map_err(failed)?;
+      Ok(json!({ "groupId": group_id }))
+    }
+    "group_cancel" => {
+      let params: GroupParams = params(call.params)?;
+      group_cancel(
+        params.group_id,
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+      );
+      Ok(Value::Null)
+    }
+    "queue_list" => to_value(queue_list(app.state())),
+    "queue_scheduled" => to_value(queue_scheduled(app.state())),
+    "status" => {
+      let queue = app.state::<SharedQueue>().snapshot();
+      let count = |state: QueueEntryState| queue.iter().filter(|e| e.state == state).count();
+      Ok(json!({
+        "version": app.package_info().version.to_string(),
+        "queued": count(QueueEntryState::Queued),
+        "running": count(QueueEntryState::Running),
+        "paused": count(QueueEntryState::Paused),
+      }))
+    }
+    method => Err(RpcError::new(
+      METHOD_NOT_FOUND,
+      format!("unknown method {method}"),
+    )),
+  }
+}
+
+pub fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+  match result {
+    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    Err(error) => json!({
+      "jsonrpc": "2.0",
+      "id": id,
+      "error": { "code": error.code, "message": error.message },
+    }),
+  }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+  serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value, RpcError> {
+  serde_json::to_value(value).map_err(|e| RpcError::new(COMMAND_FAILED, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_calls_and_defaults_params() {
+    let call = parse_call(br#"{"jsonrpc":"2.0","id":7,"method":"queue_list"}"#).unwrap();
+    assert_eq!(
+      call,
+      RpcCall {
+        id: Some(json!(7)),
+        method: "queue_list".into(),
+        params: json!({}),
+      }
+    );
+
+    let call =
+      parse_call(br#"{"jsonrpc":"2.0","method":"group_cancel","params":{"groupId":"g"}}"#).unwrap();
+    assert_eq!(call.id, None);
+    assert_eq!(call.params["groupId"], "g");
+  }
+
+  #[test]
+  fn rejects_malformed_requests() {
+    let code = |body: &[u8]| parse_call(body).unwrap_err().code;
+    assert_eq!(code(b"{"), PARSE_ERROR);
+    assert_eq!(
+      code(br#"[{"jsonrpc":"2.0","method":"status"}]"#),
+      INVALID_REQUEST
+    );
+    assert_eq!(code(br#"{"method":"status"}"#), INVALID_REQUEST);
+    assert_eq!(code(br#"{"jsonrpc":"2.0","id":1}"#), INVALID_REQUEST);
+    assert_eq!(
+      code(br#"{"jsonrpc":"2.0","method":"status","params":[1]}"#),
+      INVALID_PARAMS
+    );
+  }
+}
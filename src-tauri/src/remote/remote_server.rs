@@ -0,0 +1,336 @@
+use crate::remote::remote_rpc::{dispatch, parse_call, response};
+use crate::state::config_models::RemoteApiSettings;
+use crate::stronghold::stronghold_state::StrongholdState;
+use futures::stream;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full, Limited, StreamBody};
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::header;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, watch};
+
+/// Events streamed to clients of `/events`.
+const STREAMED_EVENTS: [&str; 5] = [
+  "media_add",
+  "media_progress",
+  "media_complete",
+  "media_fatal",
+  "group_complete",
+];
+
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const EVENT_BUFFER: usize = 256;
+/// Comment sent on idle event streams so proxies and clients keep them open.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+type Body = UnsyncBoxBody<Bytes, Infallible>;
+
+#[derive(Clone, Debug)]
+struct StreamedEvent {
+  name: &'static str,
+  payload: String,
+}
+
+struct RunningServer {
+  port: u16,
+  shutdown: watch::Sender<bool>,
+}
+
+/// Local HTTP API for browser extensions and scripts, started and stopped with its settings.
+pub struct RemoteApiServer {
+  app: AppHandle,
+  events: broadcast::Sender<StreamedEvent>,
+  /// The vault's token, loaded when the vault is unlocked so requests never wait on it.
+  token: Mutex<Option<String>>,
+  running: Mutex<Option<RunningServer>>,
+}
+
+impl RemoteApiServer {
+  pub fn new(app: &AppHandle) -> Self {
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    for name in STREAMED_EVENTS {
+      let events = events.clone();
+      app.listen_any(name, move |e| {
+        // Nobody listening is fine, the event is just dropped.
+        let _ = events.send(StreamedEvent {
+          name,
+          payload: e.payload().to_string(),
+        });
+      });
+    }
+    let server = Self {
+      app: app.clone(),
+      events,
+      token: Mutex::new(None),
+      running: Mutex::new(None),
+    };
+    // The vault is usually unlocked on startup, before the server exists.
+    if let Some(vault) = app.try_state::<StrongholdState>() {
+      server.load_token(&vault);
+    }
+    server
+  }
+
+  /// Starts, stops or moves the server to match the settings.
+  pub fn apply(&self, settings: &RemoteApiSettings) {
+    let mut running = self.running.lock().unwrap();
+    if settings.enabled && running.as_ref().is_some_and(|r| r.port == settings.port) {
+      return;
+    }
+    if let Some(server) = running.take() {
+      let _ = server.shutdown.send(true);
+      tracing::info!(port = server.port, "Stopped local API");
+    }
+    if !settings.enabled {
+      return;
+    }
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port));
+    let listener = match std::net::TcpListener::bind(addr)
+      .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    {
+      Ok(listener) => listener,
+      Err(e) => {
+        tracing::error!("Failed to start local API on {addr}: {e}");
+        return;
+      }
+    };
+
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    tauri::async_runtime::spawn(serve(self.app.clone(), listener, shutdown_rx));
+    tracing::info!(port = settings.port, "Started local API");
+    *running = Some(RunningServer {
+      port: settings.port,
+      shutdown,
+    });
+  }
+
+  /// Makes the server accept `token` from now on, after it was rotated in the vault.
+  pub fn set_token(&self, token: String) {
+    *self.token.lock().unwrap() = Some(token);
+  }
+
+  /// Takes the token from the vault, creating it on first use. Does nothing while the vault is
+  /// locked.
+  pub fn load_token(&self, vault: &StrongholdState) {
+    if vault.inner.lock().unwrap().is_none() {
+      return;
+    }
+    match vault.remote_api_token() {
+      Ok(token) => self.set_token(token),
+      Err(e) => tracing::warn!("Failed to load the local API token: {e}"),
+    }
+  }
+
+  fn token(&self) -> Option<String> {
+    self.token.lock().unwrap().clone()
+  }
+}
+
+async fn serve(
+  app: AppHandle,
+  listener: std::net::TcpListener,
+  mut shutdown: watch::Receiver<bool>,
+) {
+  let listener = match TcpListener::from_std(listener) {
+    Ok(listener) => listener,
+    Err(e) => {
+      tracing::error!("Failed to start local API: {e}");
+      return;
+    }
+  };
+
+  loop {
+    let stream = tokio::select! {
+      accepted = listener.accept() => match accepted {
+        Ok((stream, _)) => stream,
+        Err(e) => {
+          tracing::warn!("Local API failed to accept a connection: {e}");
+          continue;
+        }
+      },
+      _ = shutdown.changed() => return,
+    };
+
+    let app = app.clone();
+    let mut shutdown = shutdown.clone();
+    tauri::async_runtime::spawn(async move {
+      let service = service_fn(move |req| {
+        let app = app.clone();
+        async move { Ok::<_, Infallible>(handle(&app, req).await) }
+      });
+      let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+      // Dropping the connection on shutdown also ends open event streams.
+      tokio::select! {
+        result = connection => {
+          if let Err(e) = result {
+            tracing::debug!("Local API connection closed: {e}");
+          }
+        }
+        _ = shutdown.changed() => {}
+      }
+    });
+  }
+}
+
+async fn handle(app: &AppHandle, req: Request<Incoming>) -> Response<Body> {
+  let server = app.state::<RemoteApiServer>();
+  let Some(token) = server.token() else {
+    return error(StatusCode::SERVICE_UNAVAILABLE, "vault locked");
+  };
+  if !request_token(&req).is_some_and(|given| same_token(given, &token)) {
+    return error(StatusCode::UNAUTHORIZED, "missing or invalid token");
+  }
+
+  match (req.method(), req.uri().path()) {
+    (&Method::POST, "/rpc") => rpc(app, req).await,
+    (&Method::GET, "/events") => events(&server),
+    _ => error(StatusCode::NOT_FOUND, "not found"),
+  }
+}
+
+async fn rpc(app: &AppHandle, req: Request<Incoming>) -> Response<Body> {
+  let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+    .collect()
+    .await
+  {
+    Ok(body) => body.to_bytes(),
+    Err(e) => return error(StatusCode::PAYLOAD_TOO_LARGE, &e.to_string()),
+  };
+
+  let reply = match parse_call(&body) {
+    Ok(call) => match call.id.clone() {
+      Some(id) => response(id, dispatch(app, call)),
+      None => {
+        let _ = dispatch(app, call);
+        return respond(Response::builder().status(StatusCode::NO_CONTENT), empty());
+      }
+    },
+    Err(e) => response(Value::Null, Err(e)),
+  };
+  json_response(StatusCode::OK, &reply)
+}
+
+/// Streams app events as server-sent events, named like the Tauri events.
+fn events(server: &RemoteApiServer) -> Response<Body> {
+  let rx = server.events.subscribe();
+  let stream = stream::unfold(rx, |mut rx| async move {
+    let chunk = match tokio::time::timeout(KEEP_ALIVE, rx.recv()).await {
+      Ok(Ok(event)) => format!("event: {}\ndata: {}\n\n", event.name, event.payload),
+      // A slow client missed some events, tell it and carry on with the newest ones.
+      Ok(Err(broadcast::error::RecvError::Lagged(missed))) => {
+        format!("event: lagged\ndata: {{\"missed\":{missed}}}\n\n")
+      }
+      Ok(Err(broadcast::error::RecvError::Closed)) => return None,
+      Err(_) => ": keep-alive\n\n".to_string(),
+    };
+    Some((Ok(Frame::data(Bytes::from(chunk))), rx))
+  });
+
+  respond(
+    Response::builder()
+      .header(header::CONTENT_TYPE, "text/event-stream")
+      .header(header::CACHE_CONTROL, "no-cache"),
+    StreamBody::new(stream).boxed_unsync(),
+  )
+}
+
+/// Token from the `Authorization: Bearer` header. `GET /events` also takes it as the `token`
+/// query parameter for clients like `EventSource` that cannot set headers; other requests do
+/// not, so the token does not end up in URLs more than needed.
+fn request_token<B>(req: &Request<B>) -> Option<&str> {
+  let bearer = req
+    .headers()
+    .get(header::AUTHORIZATION)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "));
+  if bearer.is_some() || req.method() != Method::GET || req.uri().path() != "/events" {
+    return bearer.map(str::trim);
+  }
+  req
+    .uri()
+    .query()?
+    .split('&')
+    .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Compares without returning early, so response times do not leak how much of a token matched.
+fn same_token(given: &str, expected: &str) -> bool {
+  given.len() == expected.len()
+    && given
+      .bytes()
+      .zip(expected.bytes())
+      .fold(0, |diff, (a, b)| diff | (a ^ b))
+      == 0
+}
+
+fn empty() -> Body {
+  Full::new(Bytes::new()).boxed_unsync()
+}
+
+fn json_response(status: StatusCode, value: &Value) -> Response<Body> {
+  respond(
+    Response::builder()
+      .status(status)
+      .header(header::CONTENT_TYPE, "application/json"),
+    Full::new(Bytes::from(value.to_string())).boxed_unsync(),
+  )
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+  json_response(status, &json!({ "error": message }))
+}
+
+/// Sends no CORS headers, so web pages cannot read the API. Browser extensions with host
+/// permissions for `127.0.0.1` are not bound by CORS.
+fn respond(builder: hyper::http::response::Builder, body: Body) -> Response<Body> {
+  builder.body(body).expect("static headers are valid")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_token_from_header_or_query() {
+    let req = Request::builder()
+      .uri("/rpc")
+      .header(header::AUTHORIZATION, "Bearer abc")
+      .body(())
+      .unwrap();
+    assert_eq!(request_token(&req), Some("abc"));
+
+    let req = Request::builder()
+      .uri("/events?since=1&token=def")
+      .body(())
+      .unwrap();
+    assert_eq!(request_token(&req), Some("def"));
+
+    let req = Request::builder().uri("/events").body(()).unwrap();
+    assert_eq!(request_token(&req), None);
+
+    let req = Request::builder()
+      .method(Method::POST)
+      .uri("/rpc?token=def")
+      .body(())
+      .unwrap();
+    assert_eq!(request_token(&req), None);
+  }
+
+  #[test]
+  fn compares_tokens() {
+    assert!(same_token("abc", "abc"));
+    assert!(!same_token("abd", "abc"));
+    assert!(!same_token("ab", "abc"));
+  }
+}
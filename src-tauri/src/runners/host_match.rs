@@ -0,0 +1,55 @@
+use tauri::Url;
+
+/// Pattern matching every host.
+pub const ANY_HOST: &str = "*";
+
+/// Lowercased host of the URL without a trailing dot, `None` when it does not parse.
+pub fn url_host(url: &str) -> Option<String> {
+  let host = Url::parse(url.trim()).ok()?.host_str()?.to_lowercase();
+  Some(host.trim_end_matches('.').to_string())
+}
+
+/// Strips the optional `*.` prefix and surrounding dots, `example.org` and `*.example.org`
+/// mean the same.
+pub fn normalize_host_pattern(pattern: &str) -> &str {
+  pattern
+    .trim()
+    .trim_start_matches("*.")
+    .trim_start_matches('.')
+    .trim_end_matches('.')
+}
+
+/// How specific `pattern` is for `host`, `None` when it does not match. A domain also matches
+/// its subdomains, longer patterns score higher and `*` scores lowest.
+pub fn host_pattern_score(pattern: &str, host: Option<&str>) -> Option<usize> {
+  if pattern.trim() == ANY_HOST {
+    return Some(0);
+  }
+  let pattern = normalize_host_pattern(pattern).to_lowercase();
+  if pattern.is_empty() {
+    return None;
+  }
+  let host = host?;
+  let matches = host == pattern
+    || host
+      .strip_suffix(pattern.as_str())
+      .is_some_and(|rest| rest.ends_with('.'));
+  matches.then_some(pattern.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn pattern_matches_domain_and_subdomains_only() {
+    let score = |pattern, url| host_pattern_score(pattern, url_host(url).as_deref());
+
+    assert_eq!(score("vimeo.com", "https://vimeo.com/123"), Some(9));
+    assert_eq!(score("*.vimeo.com", "https://player.VIMEO.com/1"), Some(9));
+    assert_eq!(score("vimeo.com", "https://notvimeo.com/123"), None);
+    assert_eq!(score("vimeo.com", "not a url"), None);
+    assert_eq!(score(ANY_HOST, "not a url"), Some(0));
+    assert_eq!(score(" . ", "https://vimeo.com"), None);
+  }
+}
@@ -1,5 +1,8 @@
+pub mod host_match;
+pub mod site_rules;
 pub mod template_context;
 pub mod ytdlp_args;
+pub mod ytdlp_auth;
 pub mod ytdlp_download;
 pub mod ytdlp_info;
 pub mod ytdlp_process;
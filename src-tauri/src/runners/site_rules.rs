@@ -0,0 +1,197 @@
+use crate::runners::host_match::{host_pattern_score, url_host};
+use crate::state::config_models::{Config, CookieOverride, ProxyOverride, SiteRule};
+use serde::Serialize;
+
+/// Cookie and network settings that apply to one URL after the site rules were resolved.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteSettings {
+  /// Index into `Config::site_rules` of the rule that matched, `None` for the global settings.
+  pub rule_index: Option<usize>,
+  pub rule_name: Option<String>,
+  pub cookie_browser: Option<String>,
+  pub cookie_file: Option<String>,
+  /// `Some("")` forces a direct connection.
+  pub proxy: Option<String>,
+  pub impersonate: String,
+  pub headers: Vec<String>,
+}
+
+/// The first enabled rule with a host pattern matching the URL.
+pub fn match_site_rule<'a>(rules: &'a [SiteRule], url: &str) -> Option<(usize, &'a SiteRule)> {
+  let host = url_host(url);
+  rules.iter().enumerate().find(|(_, rule)| {
+    rule.enabled
+      && rule
+        .hosts
+        .iter()
+        .any(|pattern| host_pattern_score(pattern, host.as_deref()).is_some())
+  })
+}
+
+/// Applies the matching site rule, if any, on top of the global auth and network settings.
+pub fn resolve_site_settings(cfg: &Config, url: &str) -> SiteSettings {
+  let proxy_enabled = cfg.network.enable_proxy.is_some_and(|enabled| enabled);
+  let mut settings = SiteSettings {
+    rule_index: None,
+    rule_name: None,
+    cookie_browser: Some(cfg.auth.cookie_browser.clone()).filter(|b| b != "none"),
+    cookie_file: cfg.auth.cookie_file.clone(),
+    proxy: cfg.network.proxy.clone().filter(|_| proxy_enabled),
+    impersonate: cfg.network.impersonate.clone(),
+    headers: vec![],
+  };
+
+  let Some((index, rule)) = match_site_rule(&cfg.site_rules, url) else {
+    return settings;
+  };
+  settings.rule_index = Some(index);
+  settings.rule_name = Some(rule.name.clone());
+
+  match &rule.cookies {
+    Some(CookieOverride::None) => {
+      settings.cookie_browser = None;
+      settings.cookie_file = None;
+    }
+    Some(CookieOverride::Browser { browser }) => {
+      settings.cookie_browser = Some(browser.clone()).filter(|b| b != "none");
+      settings.cookie_file = None;
+    }
+    Some(CookieOverride::File { path }) => {
+      settings.cookie_browser = None;
+      settings.cookie_file = Some(path.clone());
+    }
+    None => {}
+  }
+  match &rule.proxy {
+    Some(ProxyOverride::Direct) => settings.proxy = Some(String::new()),
+    Some(ProxyOverride::Proxy { url }) => settings.proxy = Some(url.clone()),
+    None => {}
+  }
+  if let Some(impersonate) = &rule.impersonate {
+    settings.impersonate = impersonate.clone();
+  }
+  settings.headers = rule
+    .headers
+    .iter()
+    .map(|h| h.trim().to_string())
+    .filter(|h| h.contains(':'))
+    .collect();
+
+  settings
+}
+
+pub fn build_cookie_args(settings: &SiteSettings) -> Vec<String> {
+  let mut args = Vec::new();
+  if let Some(browser) = &settings.cookie_browser {
+    args.extend(["--cookies-from-browser".into(), browser.clone()]);
+  }
+  if let Some(file) = &settings.cookie_file {
+    args.extend(["--cookies".into(), file.clone()]);
+  }
+  args
+}
+
+pub fn build_network_args(settings: &SiteSettings) -> Vec<String> {
+  let mut args = Vec::new();
+  if let Some(proxy) = &settings.proxy {
+    args.extend(["--proxy".into(), proxy.clone()]);
+  }
+
+  match settings.impersonate.as_str() {
+    "none" => {}
+    "any" => args.extend(["--impersonate".into(), String::new()]),
+    other => args.extend(["--impersonate".into(), other.to_string()]),
+  }
+
+  for header in &settings.headers {
+    args.extend(["--add-header".into(), header.clone()]);
+  }
+  args
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn config() -> Config {
+    let mut cfg = Config::default();
+    cfg.auth.cookie_browser = "chrome".into();
+    cfg.network.enable_proxy = Some(true);
+    cfg.network.proxy = Some("http://global:8080".into());
+    cfg.site_rules = vec![
+      SiteRule {
+        name: "Disabled".into(),
+        enabled: false,
+        hosts: vec!["nicovideo.jp".into()],
+        ..SiteRule::default()
+      },
+      SiteRule {
+        name: "Niconico".into(),
+        hosts: vec!["nicovideo.jp".into()],
+        cookies: Some(CookieOverride::Browser {
+          browser: "firefox".into(),
+        }),
+        proxy: Some(ProxyOverride::Proxy {
+          url: "socks5://jp:1080".into(),
+        }),
+        headers: vec!["Accept-Language: ja".into()],
+        ..SiteRule::default()
+      },
+      SiteRule {
+        name: "YouTube".into(),
+        hosts: vec!["youtube.com".into(), "youtu.be".into()],
+        cookies: Some(CookieOverride::None),
+        proxy: Some(ProxyOverride::Direct),
+        impersonate: Some("chrome".into()),
+        ..SiteRule::default()
+      },
+    ];
+    cfg
+  }
+
+  #[test]
+  fn unmatched_url_uses_global_settings() {
+    let settings = resolve_site_settings(&config(), "https://vimeo.com/1");
+    assert_eq!(settings.rule_index, None);
+    assert_eq!(
+      build_cookie_args(&settings),
+      vec!["--cookies-from-browser", "chrome"]
+    );
+    assert_eq!(
+      build_network_args(&settings),
+      vec!["--proxy", "http://global:8080"]
+    );
+  }
+
+  #[test]
+  fn first_enabled_rule_overrides_cookies_and_network() {
+    let settings = resolve_site_settings(&config(), "https://www.nicovideo.jp/watch/sm9");
+    assert_eq!(settings.rule_index, Some(1));
+    assert_eq!(settings.rule_name.as_deref(), Some("Niconico"));
+    assert_eq!(
+      build_cookie_args(&settings),
+      vec!["--cookies-from-browser", "firefox"]
+    );
+    assert_eq!(
+      build_network_args(&settings),
+      vec![
+        "--proxy",
+        "socks5://jp:1080",
+        "--add-header",
+        "Accept-Language: ja"
+      ]
+    );
+  }
+
+  #[test]
+  fn rule_can_disable_cookies_and_proxy() {
+    let settings = resolve_site_settings(&config(), "https://youtu.be/abc");
+    assert_eq!(settings.rule_index, Some(2));
+    assert!(build_cookie_args(&settings).is_empty());
+    assert_eq!(
+      build_network_args(&settings),
+      vec!["--proxy", "", "--impersonate", "chrome"]
+    );
+  }
+}
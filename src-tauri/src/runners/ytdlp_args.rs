@@ -1,7 +1,11 @@
-use crate::models::download::{AudioFormat, FormatOptions, TranscodePolicy, VideoContainer};
+use crate::models::download::{
+  AudioFormat, FormatOptions, LiveRecordingOptions, MetadataOptions, MetadataRewrite,
+  SectionOptions, ThumbnailFormat, TranscodePolicy, VideoCodec, VideoContainer, VideoQuality,
+  VideoReencodeTarget,
+};
 use crate::models::TrackType;
 use crate::runners::template_context::TemplateContext;
-use crate::state::config_models::OutputSettings;
+use crate::state::config_models::{OutputSettings, ThumbnailSettings};
 use crate::state::preferences_models::PathPreferences;
 use std::path::PathBuf;
 
@@ -11,6 +15,15 @@ pub fn build_format_args(
 ) -> Vec<String> {
   let mut args = Vec::new();
 
+  if let Some(selector) = exact_format_selector(&format_options.format_ids) {
+    if matches!(format_options.track_type, TrackType::Audio) {
+      args.push("-x".into());
+    }
+    args.push("-f".into());
+    args.push(selector);
+    return args;
+  }
+
   match format_options.track_type {
     TrackType::Audio => {
       args.push("-x".into());
@@ -85,11 +98,13 @@ pub fn build_format_args(
         sort_fields.push(format!("vcodec:{codec}"));
       }
 
-      if matches!(output_settings.video.container, VideoContainer::Mp4) {
-        sort_fields.push("vext:mp4".into());
-        sort_fields.push("vext:m4a".into());
-      } else {
-        sort_fields.push("vext".into());
+      match output_settings.video.container {
+        VideoContainer::Mp4 | VideoContainer::Mov => {
+          sort_fields.push("vext:mp4".into());
+          sort_fields.push("vext:m4a".into());
+        }
+        VideoContainer::Webm => sort_fields.push("vext:webm".into()),
+        VideoContainer::Mkv => sort_fields.push("vext".into()),
       }
 
       let sort_arg = sort_fields.join(",");
@@ -102,6 +117,20 @@ pub fn build_format_args(
   args
 }
 
+/// Joins exact format ids into a selector that downloads and merges all of them.
+fn exact_format_selector(format_ids: &[String]) -> Option<String> {
+  let ids: Vec<&str> = format_ids
+    .iter()
+    .map(|id| id.trim())
+    .filter(|id| !id.is_empty())
+    .collect();
+  if ids.is_empty() {
+    None
+  } else {
+    Some(ids.join("+"))
+  }
+}
+
 pub fn build_output_args(
   format_options: &FormatOptions,
   output_settings: &OutputSettings,
@@ -164,11 +193,7 @@ pub fn build_output_args(
       }
       TranscodePolicy::RemuxOnly => {
         args.push("--remux-video".into());
-        let container = match output_settings.video.container {
-          VideoContainer::Mp4 => "mp4",
-          VideoContainer::Mkv => "mkv",
-        };
-        args.push(container.into());
+        args.push(output_settings.video.container.as_str().into());
 
         if output_settings.add_thumbnail {
           args.push("--embed-thumbnail".into());
@@ -176,11 +201,11 @@ pub fn build_output_args(
       }
       TranscodePolicy::AllowReencode => {
         args.push("--recode-video".into());
-        let container = match output_settings.video.container {
-          VideoContainer::Mp4 => "mp4",
-          VideoContainer::Mkv => "mkv",
-        };
-        args.push(container.into());
+        args.push(output_settings.video.container.as_str().into());
+
+        if let Some(target) = &output_settings.video.reencode {
+          args.extend(build_reencode_args(target, output_settings.video.container));
+        }
 
         if output_settings.add_thumbnail {
           args.push("--embed-thumbnail".into());
@@ -197,9 +222,202 @@ pub fn build_output_args(
     args.push("--restrict-filenames".into());
   }
 
+  if output_settings.split_chapters {
+    args.push("--split-chapters".into());
+  }
+
+  args.extend(build_thumbnail_args(&output_settings.thumbnails));
+
+  args
+}
+
+/// Sidecar, conversion and cropping options for thumbnails. Cropping and scaling happen while
+/// converting, so they default to converting into JPEG.
+///
+/// yt-dlp skips converting thumbnails that already have the target format, so when filters are
+/// set the thumbnails are converted into an intermediate format first, like
+/// `build_reencode_args` does for videos. Both conversions get the filters, which do nothing
+/// the second time.
+///
+/// yt-dlp always picks the best thumbnail the site offers and has no option to pick another
+/// one, so there is no resolution choice besides `write_all`; `max_height` scales it instead.
+pub fn build_thumbnail_args(settings: &ThumbnailSettings) -> Vec<String> {
+  let mut args = Vec::new();
+  if settings.write_all {
+    args.push("--write-all-thumbnails".into());
+  } else if settings.sidecar {
+    args.push("--write-thumbnail".into());
+  }
+
+  let mut filters = Vec::new();
+  if settings.square_crop {
+    filters.push("crop='min(iw,ih)':'min(iw,ih)'".to_string());
+  }
+  if let Some(height) = settings.max_height {
+    filters.push(format!("scale=-2:'min({height},ih)'"));
+  }
+
+  let format = settings
+    .format
+    .or_else(|| (!filters.is_empty()).then_some(ThumbnailFormat::Jpg));
+  if let Some(format) = format {
+    if !filters.is_empty() {
+      let intermediate = match format {
+        ThumbnailFormat::Png => ThumbnailFormat::Webp,
+        _ => ThumbnailFormat::Png,
+      };
+      args.extend([
+        "--use-postprocessor".into(),
+        format!(
+          "FFmpegThumbnailsConvertor:format={};when=before_dl",
+          intermediate.as_str()
+        ),
+      ]);
+    }
+    args.extend(["--convert-thumbnails".into(), format.as_str().into()]);
+  }
+  if !filters.is_empty() {
+    args.extend([
+      "--postprocessor-args".into(),
+      format!("ThumbnailsConvertor+ffmpeg_o:-vf \"{}\"", filters.join(",")),
+    ]);
+  }
+  args
+}
+
+/// Rewrites and tag overrides for one item.
+///
+/// Rewrites go through yt-dlp's metadata parser, so they also change the fields file name
+/// templates see. Fixed values are handed to ffmpeg when the tags are embedded, as literal text
+/// cannot be passed to `--parse-metadata` reliably. `embeds_metadata` tells whether the output
+/// args already add `--add-metadata`.
+pub fn build_metadata_args(
+  options: &MetadataOptions,
+  context: &TemplateContext,
+  embeds_metadata: bool,
+) -> Vec<String> {
+  let mut args = Vec::new();
+  if !embeds_metadata {
+    args.push("--add-metadata".to_string());
+  }
+
+  for rewrite in &options.rewrites {
+    match rewrite {
+      MetadataRewrite::Parse { from, pattern } => {
+        // The first unescaped colon separates the source from the pattern.
+        let from = from.trim().replace(':', "\\:");
+        args.extend(["--parse-metadata".into(), format!("{from}:{pattern}")]);
+      }
+      MetadataRewrite::Replace {
+        fields,
+        regex,
+        replacement,
+      } => {
+        let fields: Vec<&str> = fields
+          .iter()
+          .map(|f| f.trim())
+          .filter(|f| !f.is_empty())
+          .collect();
+        args.extend([
+          "--replace-in-metadata".into(),
+          fields.join(","),
+          regex.clone(),
+          replacement.clone(),
+        ]);
+      }
+    }
+  }
+
+  let mut fields = options.fields.clone();
+  if options.playlist_as_album {
+    let value = |keys: &[&str]| {
+      keys
+        .iter()
+        .find_map(|key| context.values.get(*key).cloned())
+    };
+    fields.album = fields
+      .album
+      .or_else(|| value(&["playlist_title", "playlist"]));
+    fields.track_number = fields
+      .track_number
+      .or_else(|| value(&["playlist_autonumber", "playlist_index"]).and_then(|n| n.parse().ok()));
+  }
+
+  let tags = [
+    ("title", fields.title),
+    ("artist", fields.artist),
+    ("album", fields.album),
+    ("track", fields.track_number.map(|n| n.to_string())),
+    ("date", fields.year.map(|y| y.to_string())),
+    ("genre", fields.genre),
+    ("description", fields.description),
+  ];
+  let ffmpeg: Vec<String> = tags
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+    .map(|(key, value)| format!("-metadata {}", quote_ffmpeg_arg(&format!("{key}={value}"))))
+    .collect();
+  if !ffmpeg.is_empty() {
+    args.extend([
+      "--postprocessor-args".into(),
+      format!("Metadata+ffmpeg_o:{}", ffmpeg.join(" ")),
+    ]);
+  }
+
   args
 }
 
+/// Quotes a value for yt-dlp's shell-like splitting of postprocessor arguments.
+fn quote_ffmpeg_arg(value: &str) -> String {
+  format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// ffmpeg arguments for the video convertor that encode into `target`.
+///
+/// yt-dlp skips recoding files that already have the target extension, so downloads are
+/// merged into a different container first to make sure the codec target is always applied.
+pub fn build_reencode_args(target: &VideoReencodeTarget, container: VideoContainer) -> Vec<String> {
+  let mut ffmpeg: Vec<String> = vec!["-c:v".into(), target.codec.encoder().into()];
+
+  match target.quality {
+    VideoQuality::Crf { crf } => {
+      ffmpeg.extend(["-crf".into(), crf.to_string()]);
+      if target.codec == VideoCodec::Vp9 {
+        // libvpx only uses constant quality mode without a target bitrate.
+        ffmpeg.extend(["-b:v".into(), "0".into()]);
+      }
+    }
+    VideoQuality::Bitrate { kbps } => ffmpeg.extend(["-b:v".into(), format!("{kbps}k")]),
+  }
+
+  if let Some(preset) = &target.preset {
+    let flag = match target.codec {
+      VideoCodec::Vp9 => "-deadline",
+      _ => "-preset",
+    };
+    ffmpeg.extend([flag.into(), preset.clone()]);
+  }
+
+  if let Some(height) = target.max_height {
+    // Double quotes survive yt-dlp's argument splitting, the single quotes are for ffmpeg.
+    ffmpeg.extend(["-vf".into(), format!("\"scale=-2:'min({height},ih)'\"")]);
+  }
+
+  ffmpeg.extend(["-c:a".into(), container.reencode_audio_codec().into()]);
+
+  let intermediate = match container {
+    VideoContainer::Mkv => "webm/mp4",
+    _ => "mkv",
+  };
+
+  vec![
+    "--merge-output-format".into(),
+    intermediate.into(),
+    "--postprocessor-args".into(),
+    format!("VideoConvertor+ffmpeg_o:{}", ffmpeg.join(" ")),
+  ]
+}
+
 pub fn build_location_args(
   track_type: &TrackType,
   template_context: &TemplateContext,
@@ -240,17 +458,72 @@ pub fn build_location_args(
     TrackType::Audio => output_settings.audio_file_name_template.clone(),
   };
 
-  let output_path = base_dir.join(prefix_dir).join(filename);
-  let output_str = output_path.to_string_lossy();
-  let rendered_output_str = template_context.render_template(output_str.as_ref());
+  let output_dir = base_dir.join(prefix_dir);
+  let render = |filename: &str| {
+    let output_path = output_dir.join(filename);
+    template_context.render_template(output_path.to_string_lossy().as_ref())
+  };
+
+  let mut args = vec!["-o".into(), render(&filename)];
+  if output_settings.split_chapters {
+    let chapter_output = render(&output_settings.chapter_file_name_template);
+    args.extend(["-o".into(), format!("chapter:{chapter_output}")]);
+  }
+  args
+}
+
+pub fn build_section_args(sections: &SectionOptions) -> Vec<String> {
+  let mut args = Vec::new();
+  for range in &sections.ranges {
+    let end = range
+      .end
+      .map_or_else(|| "inf".to_string(), |end| end.to_string());
+    args.extend([
+      "--download-sections".into(),
+      format!("*{}-{end}", range.start),
+    ]);
+  }
+  for chapter in sections.chapters.iter().map(|c| c.trim()) {
+    if !chapter.is_empty() {
+      args.extend(["--download-sections".into(), chapter.to_string()]);
+    }
+  }
+  if sections.force_keyframes_at_cuts {
+    args.push("--force-keyframes-at-cuts".into());
+  }
+  args
+}
 
-  vec!["-o".into(), rendered_output_str]
+/// Adds the section to a file name template so sections of the same video get their own files.
+pub fn section_file_name_template(template: &str) -> String {
+  const SUFFIX: &str = " (%(section_title,section_start)s)";
+  match template.strip_suffix(".%(ext)s") {
+    Some(stem) => format!("{stem}{SUFFIX}.%(ext)s"),
+    None => format!("{template}{SUFFIX}"),
+  }
+}
+
+pub fn build_live_args(options: &LiveRecordingOptions) -> Vec<String> {
+  let mut args = Vec::new();
+  if options.from_start {
+    args.push("--live-from-start".into());
+  }
+  match options.wait_for_video_secs {
+    Some(secs) => {
+      args.push("--wait-for-video".into());
+      args.push(secs.max(1).to_string());
+    }
+    None => args.push("--no-wait-for-video".into()),
+  }
+  args
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::models::download::{AudioFormat, FormatOptions, TranscodePolicy, VideoContainer};
+  use crate::models::download::{
+    AudioFormat, FormatOptions, MediaMetadata, TimeRange, TranscodePolicy, VideoContainer,
+  };
   use crate::models::TrackType;
   use crate::state::config_models::OutputSettings;
 
@@ -262,6 +535,7 @@ mod tests {
       fps: None,
       video_codec: None,
       audio_codec: None,
+      format_ids: vec![],
     }
   }
 
@@ -273,6 +547,7 @@ mod tests {
       fps,
       video_codec: None,
       audio_codec: None,
+      format_ids: vec![],
     }
   }
 
@@ -284,6 +559,7 @@ mod tests {
       fps,
       video_codec: None,
       audio_codec: None,
+      format_ids: vec![],
     }
   }
 
@@ -517,6 +793,97 @@ mod tests {
     assert_eq!(args, expected);
   }
 
+  #[test]
+  fn video_output_args_recode_with_codec_target() {
+    let format_options = make_video_format_options(None, None);
+
+    let mut settings = OutputSettings::default();
+    settings.video.policy = TranscodePolicy::AllowReencode;
+    settings.video.container = VideoContainer::Webm;
+    settings.video.reencode = Some(VideoReencodeTarget {
+      codec: VideoCodec::Vp9,
+      quality: VideoQuality::Crf { crf: 31 },
+      preset: Some("good".into()),
+      max_height: Some(720),
+    });
+    settings.add_thumbnail = false;
+
+    let args = build_output_args(&format_options, &settings);
+
+    let expected: Vec<String> = vec![
+      "--output-na-placeholder",
+      "None",
+      "--recode-video",
+      "webm",
+      "--merge-output-format",
+      "mkv",
+      "--postprocessor-args",
+      "VideoConvertor+ffmpeg_o:-c:v libvpx-vp9 -crf 31 -b:v 0 -deadline good \
+       -vf \"scale=-2:'min(720,ih)'\" -c:a libopus",
+      "--add-metadata",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    assert_eq!(args, expected);
+  }
+
+  #[test]
+  fn reencode_args_for_mkv_use_bitrate_and_other_intermediate() {
+    let target = VideoReencodeTarget {
+      codec: VideoCodec::H265,
+      quality: VideoQuality::Bitrate { kbps: 2500 },
+      preset: Some("slow".into()),
+      max_height: None,
+    };
+
+    let args = build_reencode_args(&target, VideoContainer::Mkv);
+    assert_eq!(
+      args,
+      vec![
+        "--merge-output-format",
+        "webm/mp4",
+        "--postprocessor-args",
+        "VideoConvertor+ffmpeg_o:-c:v libx265 -b:v 2500k -preset slow -c:a copy",
+      ]
+    );
+  }
+
+  #[test]
+  fn validation_rejects_invalid_reencode_targets() {
+    let mut video = OutputSettings::default().video;
+    let target = |codec, quality, preset: Option<&str>| {
+      Some(VideoReencodeTarget {
+        codec,
+        quality,
+        preset: preset.map(String::from),
+        max_height: None,
+      })
+    };
+
+    video.container = VideoContainer::Mov;
+    video.reencode = target(VideoCodec::Av1, VideoQuality::Crf { crf: 30 }, None);
+    assert_eq!(
+      video.validate().unwrap_err(),
+      "AV1 video cannot be stored in MOV"
+    );
+
+    video.container = VideoContainer::Webm;
+    video.reencode = target(VideoCodec::H264, VideoQuality::Crf { crf: 23 }, None);
+    assert!(video.validate().is_err());
+
+    video.container = VideoContainer::Mp4;
+    video.reencode = target(VideoCodec::H264, VideoQuality::Crf { crf: 60 }, None);
+    assert!(video.validate().is_err());
+
+    video.reencode = target(VideoCodec::H264, VideoQuality::Crf { crf: 23 }, Some("6"));
+    assert!(video.validate().is_err());
+
+    video.reencode = target(VideoCodec::Av1, VideoQuality::Crf { crf: 35 }, Some("6"));
+    assert!(video.validate().is_ok());
+  }
+
   #[test]
   fn both_track_type_uses_video_policy_for_output() {
     let format_options = make_both_format_options(Some(720), Some(60));
@@ -555,6 +922,34 @@ mod tests {
     assert!(args.contains(&"--restrict-filenames".to_string()));
   }
 
+  #[test]
+  fn split_chapters_adds_flag_and_chapter_output() {
+    let format_options = make_video_format_options(Some(720), Some(60));
+    let settings = OutputSettings {
+      split_chapters: true,
+      chapter_file_name_template: "%(section_number)s.%(ext)s".into(),
+      ..OutputSettings::default()
+    };
+
+    let args = build_output_args(&format_options, &settings);
+    assert!(args.contains(&"--split-chapters".to_string()));
+
+    let context = TemplateContext {
+      values: Default::default(),
+    };
+    let args = build_location_args(
+      &TrackType::Video,
+      &context,
+      &settings,
+      &PathPreferences::default(),
+      PathBuf::from("downloads"),
+    );
+    assert_eq!(args.len(), 4);
+    assert_eq!(args[2], "-o");
+    assert!(args[3].starts_with("chapter:downloads"));
+    assert!(args[3].ends_with("%(section_number)s.%(ext)s"));
+  }
+
   #[test]
   fn restrict_filenames_omits_flag_on_disable() {
     let format_options = make_video_format_options(Some(720), Some(60));
@@ -567,4 +962,192 @@ mod tests {
 
     assert!(!args.contains(&"--restrict-filenames".to_string()));
   }
+
+  #[test]
+  fn live_args_record_from_start_and_wait() {
+    let args = build_live_args(&LiveRecordingOptions::default());
+
+    assert_eq!(args, vec!["--live-from-start", "--wait-for-video", "60"]);
+  }
+
+  #[test]
+  fn live_args_without_waiting() {
+    let options = LiveRecordingOptions {
+      from_start: false,
+      wait_for_video_secs: None,
+      max_duration_secs: Some(3600),
+    };
+
+    let args = build_live_args(&options);
+
+    assert_eq!(args, vec!["--no-wait-for-video"]);
+  }
+
+  #[test]
+  fn exact_format_ids_replace_sort_heuristics() {
+    let mut format_options = make_both_format_options(Some(1080), Some(60));
+    format_options.format_ids = vec!["137".into(), " 140 ".into()];
+    let settings = OutputSettings::default();
+
+    let args = build_format_args(&format_options, &settings);
+
+    assert_eq!(args, vec!["-f", "137+140"]);
+  }
+
+  #[test]
+  fn exact_format_id_for_audio_still_extracts() {
+    let mut format_options = make_audio_format_options(None);
+    format_options.format_ids = vec!["251".into()];
+    let settings = OutputSettings::default();
+
+    let args = build_format_args(&format_options, &settings);
+
+    assert_eq!(args, vec!["-x", "-f", "251"]);
+  }
+
+  #[test]
+  fn sections_map_to_download_sections() {
+    let sections = SectionOptions {
+      ranges: vec![
+        TimeRange {
+          start: 10.0,
+          end: Some(20.5),
+        },
+        TimeRange {
+          start: 60.0,
+          end: None,
+        },
+      ],
+      chapters: vec!["^Intro$".into(), "  ".into()],
+      force_keyframes_at_cuts: true,
+    };
+
+    assert_eq!(
+      build_section_args(&sections),
+      vec![
+        "--download-sections",
+        "*10-20.5",
+        "--download-sections",
+        "*60-inf",
+        "--download-sections",
+        "^Intro$",
+        "--force-keyframes-at-cuts"
+      ]
+    );
+  }
+
+  #[test]
+  fn section_template_keeps_extension_last() {
+    assert_eq!(
+      section_file_name_template("%(title)s.%(ext)s"),
+      "%(title)s (%(section_title,section_start)s).%(ext)s"
+    );
+  }
+
+  #[test]
+  fn metadata_rewrites_and_overrides() {
+    let options = MetadataOptions {
+      fields: MediaMetadata {
+        artist: Some("Daft \"Punk\"".into()),
+        year: Some(2001),
+        ..MediaMetadata::default()
+      },
+      rewrites: vec![
+        MetadataRewrite::Parse {
+          from: "title".into(),
+          pattern: "(?P<artist>.+?) - (?P<title>.+)".into(),
+        },
+        MetadataRewrite::Replace {
+          fields: vec!["title".into(), " album ".into()],
+          regex: r"\s*\(Official Video\)".into(),
+          replacement: String::new(),
+        },
+      ],
+      playlist_as_album: true,
+    };
+    let context = TemplateContext {
+      values: [
+        ("playlist_title".to_string(), "Discovery".to_string()),
+        ("playlist_autonumber".to_string(), "3".to_string()),
+      ]
+      .into(),
+    };
+
+    assert_eq!(
+      build_metadata_args(&options, &context, true),
+      vec![
+        "--parse-metadata",
+        "title:(?P<artist>.+?) - (?P<title>.+)",
+        "--replace-in-metadata",
+        "title,album",
+        r"\s*\(Official Video\)",
+        "",
+        "--postprocessor-args",
+        r#"Metadata+ffmpeg_o:-metadata "artist=Daft \"Punk\"" -metadata "album=Discovery" -metadata "track=3" -metadata "date=2001""#,
+      ]
+    );
+
+    let args = build_metadata_args(&options, &context, false);
+    assert_eq!(args[0], "--add-metadata");
+    assert_eq!(args.iter().filter(|a| *a == "--add-metadata").count(), 1);
+  }
+
+  #[test]
+  fn square_crop_converts_and_filters_thumbnails() {
+    let settings = ThumbnailSettings {
+      sidecar: true,
+      square_crop: true,
+      max_height: Some(500),
+      ..ThumbnailSettings::default()
+    };
+
+    assert_eq!(
+      build_thumbnail_args(&settings),
+      vec![
+        "--write-thumbnail",
+        "--use-postprocessor",
+        "FFmpegThumbnailsConvertor:format=png;when=before_dl",
+        "--convert-thumbnails",
+        "jpg",
+        "--postprocessor-args",
+        r#"ThumbnailsConvertor+ffmpeg_o:-vf "crop='min(iw,ih)':'min(iw,ih)',scale=-2:'min(500,ih)'""#,
+      ]
+    );
+  }
+
+  #[test]
+  fn filtered_png_thumbnails_go_through_another_format() {
+    let settings = ThumbnailSettings {
+      format: Some(ThumbnailFormat::Png),
+      max_height: Some(300),
+      ..ThumbnailSettings::default()
+    };
+    let args = build_thumbnail_args(&settings);
+    assert_eq!(
+      args[..4],
+      [
+        "--use-postprocessor",
+        "FFmpegThumbnailsConvertor:format=webp;when=before_dl",
+        "--convert-thumbnails",
+        "png",
+      ]
+    );
+  }
+
+  #[test]
+  fn unfiltered_conversions_need_no_intermediate_format() {
+    let settings = ThumbnailSettings {
+      format: Some(ThumbnailFormat::Webp),
+      ..ThumbnailSettings::default()
+    };
+    assert_eq!(
+      build_thumbnail_args(&settings),
+      vec!["--convert-thumbnails", "webp"]
+    );
+  }
+
+  #[test]
+  fn default_thumbnail_settings_add_nothing() {
+    assert!(build_thumbnail_args(&ThumbnailSettings::default()).is_empty());
+  }
 }
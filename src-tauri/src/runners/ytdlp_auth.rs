@@ -0,0 +1,191 @@
+use crate::stronghold::stronghold_state::AuthSecrets;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory under the app's data directory that holds the credential files. The data directory
+/// lives in the user's profile, which other users cannot read on Windows either.
+pub const AUTH_DIR_NAME: &str = "auth";
+
+/// Per-job yt-dlp config file holding the credentials, so they never show up in the process
+/// list. Only the current user can read it and it is removed when dropped.
+#[derive(Debug)]
+pub struct AuthConfigFile {
+  path: PathBuf,
+}
+
+impl AuthConfigFile {
+  /// Writes the secrets to a new file in `dir`, `None` when there is nothing to write. `dir` is
+  /// created if missing and made private to the current user.
+  pub fn create(dir: &Path, secrets: &AuthSecrets) -> io::Result<Option<Self>> {
+    let Some(contents) = build_auth_config(secrets) else {
+      return Ok(None);
+    };
+    create_private_dir(dir)?;
+
+    let path = dir.join(format!("ovd-auth-{}.conf", uuid::Uuid::new_v4()));
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::OpenOptionsExt;
+      options.mode(0o600);
+    }
+
+    let file = Self { path };
+    let mut handle = options.open(&file.path)?;
+    handle.write_all(contents.as_bytes())?;
+    handle.sync_all()?;
+    Ok(Some(file))
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// The only arguments that reference the credentials: a pointer to this file.
+  pub fn args(&self) -> [String; 2] {
+    [
+      "--config-locations".into(),
+      self.path().to_string_lossy().into_owned(),
+    ]
+  }
+}
+
+impl Drop for AuthConfigFile {
+  fn drop(&mut self) {
+    if let Err(e) = std::fs::remove_file(&self.path) {
+      if e.kind() != io::ErrorKind::NotFound {
+        tracing::warn!("Failed to remove {}: {e}", self.path.display());
+      }
+    }
+  }
+}
+
+/// Removes the credential files of runs that never cleaned up after themselves, e.g. because the
+/// app crashed. Call it before any run starts.
+pub fn remove_stale_auth_files(dir: &Path) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name();
+    let name = name.to_string_lossy();
+    if name.starts_with("ovd-auth-") && name.ends_with(".conf") {
+      if let Err(e) = std::fs::remove_file(entry.path()) {
+        tracing::warn!("Failed to remove {}: {e}", entry.path().display());
+      }
+    }
+  }
+}
+
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+  std::fs::create_dir_all(dir)?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+  }
+  Ok(())
+}
+
+/// Renders the secrets as yt-dlp config file lines, `None` when there are none.
+pub fn build_auth_config(secrets: &AuthSecrets) -> Option<String> {
+  let mut lines: Vec<String> = Vec::new();
+  let mut push = |flag: &str, value: &str| lines.push(format!("{flag} {}", quote(value)));
+
+  if let Some(username) = &secrets.username {
+    push("--username", username);
+  }
+  if let Some(password) = &secrets.password {
+    push("--password", password);
+  }
+  if let Some(video_password) = &secrets.video_password {
+    push("--video-password", video_password);
+  }
+  if let Some(token) = &secrets.bearer_token {
+    push("--add-header", &format!("Authorization:Bearer {token}"));
+  }
+  for header in &secrets.headers {
+    push("--add-header", header);
+  }
+
+  if lines.is_empty() {
+    None
+  } else {
+    Some(lines.join("\n") + "\n")
+  }
+}
+
+/// yt-dlp splits config files like a POSIX shell, so single quotes keep every value literal.
+fn quote(value: &str) -> String {
+  format!("'{}'", value.replace('\'', r#"'"'"'"#))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn secrets() -> AuthSecrets {
+    AuthSecrets {
+      username: Some("alice".into()),
+      password: Some("hunter2 it's #1".into()),
+      video_password: Some("vid-secret".into()),
+      bearer_token: Some("tok-secret".into()),
+      headers: vec!["X-Api-Key: header-secret".into()],
+    }
+  }
+
+  fn test_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("ovd-auth-test-{}", uuid::Uuid::new_v4()))
+  }
+
+  #[test]
+  fn config_file_holds_quoted_secrets_and_is_removed_on_drop() {
+    let dir = test_dir();
+    let file = AuthConfigFile::create(&dir, &secrets()).unwrap().unwrap();
+    let path = file.path().to_path_buf();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("--password 'hunter2 it'\"'\"'s #1'\n"));
+    assert!(contents.contains("--add-header 'Authorization:Bearer tok-secret'\n"));
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o600);
+      let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o700);
+    }
+
+    drop(file);
+    assert!(!path.exists());
+    std::fs::remove_dir(&dir).unwrap();
+  }
+
+  #[test]
+  fn stale_files_are_removed() {
+    let dir = test_dir();
+    let file = AuthConfigFile::create(&dir, &secrets()).unwrap().unwrap();
+    let stale = file.path().to_path_buf();
+    std::mem::forget(file);
+    let other = dir.join("keep.conf");
+    std::fs::write(&other, "").unwrap();
+
+    remove_stale_auth_files(&dir);
+    assert!(!stale.exists());
+    assert!(other.exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn no_file_without_secrets() {
+    let dir = test_dir();
+    assert!(AuthConfigFile::create(&dir, &AuthSecrets::default())
+      .unwrap()
+      .is_none());
+    assert!(!dir.exists());
+  }
+}
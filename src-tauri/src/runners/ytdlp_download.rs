@@ -1,16 +1,44 @@
 use crate::logging::LogStoreState;
+use crate::models::error::DiagnosticLevel;
+use crate::models::progress::MediaDestinationPath;
 use crate::models::{
-  MediaDiagnosticPayload, MediaFatalPayload, MediaProgressComplete, ProgressEvent,
+  MediaDiagnosticPayload, MediaFatalPayload, MediaProgressComplete, MediaProgressStage,
+  ProgressEvent, ProgressStage,
 };
 use crate::parsers::ytdlp_error::{DiagnosticMatcher, YtdlpErrorParser};
-use crate::parsers::ytdlp_progress::YtdlpProgressParser;
-use crate::runners::ytdlp_runner::{is_spawn_error_file_not_found, YtdlpCommandEvent, YtdlpRunner};
+use crate::parsers::ytdlp_progress::{is_archive_skip, YtdlpProgressParser};
+use crate::runners::ytdlp_runner::{
+  is_spawn_error_file_not_found, YtdlpChild, YtdlpCommandEvent, YtdlpRunner,
+};
+use crate::scheduling::bandwidth::{effective_rate_limit, BandwidthLease, BandwidthManager};
 use crate::scheduling::download_pipeline::DownloadEntry;
-use crate::scheduling::group_state::subscribe_group;
+use crate::scheduling::group_state::{subscribe_group, subscribe_item, GroupState};
+use crate::scheduling::hooks::{fire_hooks, HookPayload};
+use crate::scheduling::proxy_pool::ProxyPool;
+use crate::scheduling::recording::RecordingGuard;
+use crate::scheduling::retry::should_retry;
+use crate::state::config_models::HookEvent;
+use crate::state::history_models::{DownloadedMetadata, HistoryStatus, NewHistoryEntry};
+use crate::{SharedConfig, SharedHistory, SharedPresets};
 use std::fmt;
-use tauri::{AppHandle, Emitter, Manager, State};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 use tokio::sync::watch;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadOutcome {
+  Completed,
+  /// yt-dlp skipped the video because it is already in the download archive.
+  Skipped,
+  Cancelled,
+  /// The process was stopped without removing its `.part` files, so it can be resumed.
+  Paused,
+  /// yt-dlp failed with errors the retry policy considers transient.
+  Retry {
+    codes: Vec<String>,
+  },
+}
+
 #[derive(Debug)]
 pub enum YtdlpDownloadError {
   InvalidDiagnosticRules(String),
@@ -34,30 +62,94 @@ impl fmt::Display for YtdlpDownloadError {
 
 impl std::error::Error for YtdlpDownloadError {}
 
-pub async fn run_ytdlp_download(
-  app: AppHandle,
+pub async fn run_ytdlp_download<R: Runtime>(
+  app: AppHandle<R>,
   entry: DownloadEntry,
-) -> Result<(), YtdlpDownloadError> {
-  let runner = YtdlpRunner::new(&app)
-    .with_progress_args()
-    .with_network_args()
-    .with_auth_args()
-    .with_subtitle_args()
-    .with_sponsorblock_args()
-    .with_format_args(&entry.format)
-    .with_input_args()
-    .with_output_args(&entry.format)
-    .with_location_args(&entry.format.track_type, &entry.template_context)
-    .with_url(&entry.url);
+) -> Result<DownloadOutcome, YtdlpDownloadError> {
+  let mut cancel_rx = subscribe_group(&entry.group_id);
+  let mut item_pause_rx = subscribe_item(&entry.group_id, &entry.id);
+  if let Some(outcome) = interrupted_outcome(&cancel_rx) {
+    return Ok(outcome);
+  }
+  if *item_pause_rx.borrow() {
+    return Ok(DownloadOutcome::Paused);
+  }
+
+  let (rate_limit, pool_proxy) = {
+    let config = app.state::<SharedConfig>().load();
+    let pool_proxy = app
+      .state::<ProxyPool>()
+      .select_for_url(&config, &entry.url, &entry.group_id);
+    (
+      effective_rate_limit(&config.network, chrono::Local::now().time()),
+      pool_proxy,
+    )
+  };
+  let mut bandwidth = if entry.live.is_some() {
+    // Restarting a recording with a new limit would lose part of the stream.
+    BandwidthLease::untracked()
+  } else {
+    app
+      .state::<BandwidthManager>()
+      .acquire(&entry.id, rate_limit)
+  };
+
+  let started_at = chrono::Utc::now().timestamp_millis();
+  let metadata_path = std::env::temp_dir().join(format!("ovd-{}.metadata.json", entry.id));
+  let _ = std::fs::remove_file(&metadata_path);
+  let mut destination: Option<MediaDestinationPath> = None;
+
+  let extractor = entry
+    .template_context
+    .values
+    .get("extractor_key")
+    .map(String::as_str);
+  let preset = entry.preset.as_deref().and_then(|id| {
+    let preset = app.state::<SharedPresets>().get(id);
+    if preset.is_none() {
+      tracing::warn!("Preset {id} no longer exists, using the global settings");
+    }
+    preset
+  });
+  let format = preset
+    .as_ref()
+    .map_or(&entry.format, |preset| preset.format_or(&entry.format));
+
+  // yt-dlp is started again with the same arguments when the bandwidth share changes.
+  let app_ref = &app;
+  let build_runner = |rate_limit: Option<u64>| {
+    YtdlpRunner::new(app_ref)
+      .with_preset(preset.as_ref())
+      .with_progress_args()
+      .with_resume_args()
+      .with_rate_limit_args(rate_limit)
+      .with_archive_args()
+      .with_metadata_capture_args(&metadata_path)
+      .with_live_args(entry.live.as_ref())
+      .with_section_args(entry.sections.as_ref())
+      .with_metadata_args(entry.metadata.as_ref(), &entry.template_context)
+      .with_network_args(&entry.url, pool_proxy.as_deref())
+      .with_auth_args(&entry.url, extractor)
+      .with_subtitle_args()
+      .with_sponsorblock_args()
+      .with_format_args(format)
+      .with_input_args()
+      .with_output_args(format)
+      .with_location_args(&format.track_type, &entry.template_context)
+      .with_url(&entry.url)
+  };
+  let runner = build_runner(bandwidth.allotment());
 
   static RULES_JSON: &str = include_str!("../diagnostic_rules.json");
   let matcher = DiagnosticMatcher::from_json(RULES_JSON)
     .map_err(|e| YtdlpDownloadError::InvalidDiagnosticRules(e.to_string()))?;
 
   let error_parser = YtdlpErrorParser::new(&entry.id, &entry.group_id, matcher);
-  let mut progress_parser = YtdlpProgressParser::new(&entry.id, &entry.group_id);
+  let mut error_codes: Vec<String> = Vec::new();
+  let mut progress_parser = YtdlpProgressParser::new(&entry.id, &entry.group_id)
+    .with_thumbnail_files(runner.keeps_thumbnail_files());
 
-  let (mut rx, child) = match runner.spawn() {
+  let (mut rx, mut child) = match runner.spawn() {
     Ok(ok) => ok,
     Err(e) => {
       if is_spawn_error_file_not_found(&e) {
@@ -84,7 +176,16 @@ pub async fn run_ytdlp_download(
       return Err(YtdlpDownloadError::SpawnFailed(e));
     }
   };
-  let mut cancel_rx = subscribe_group(&entry.group_id);
+
+  let mut recording = entry
+    .live
+    .as_ref()
+    .map(|live| RecordingGuard::register(&entry.id, live.max_duration_secs));
+  let mut stopping = false;
+  let mut archive_skip = false;
+  let mut stage = ProgressStage::Initializing;
+  // Set while yt-dlp is killed to be started again with a new bandwidth share.
+  let mut restarting = false;
 
   loop {
     tokio::select! {
@@ -93,10 +194,8 @@ pub async fn run_ytdlp_download(
           break;
         };
 
-        if is_cancelled_now(&cancel_rx) {
-          tracing::info!("Cancelled processing for group_id {}", entry.group_id);
-          let _ = child.kill_tree();
-          return Ok(());
+        if let Some(outcome) = interrupted_outcome(&cancel_rx) {
+          return Ok(stop_child(&child, &entry, outcome));
         }
 
         let log_state = app.state::<LogStoreState>();
@@ -105,15 +204,79 @@ pub async fn run_ytdlp_download(
           YtdlpCommandEvent::Stdout(line) => {
             let line_str = String::from_utf8_lossy(&line);
             store_log_line(&line_str, &entry, log_state, &app);
-            parse_progress_line(&line_str, &mut progress_parser, &app);
+            archive_skip |= is_archive_skip(&line_str);
+            parse_progress_line(
+              &line_str,
+              &mut progress_parser,
+              &bandwidth,
+              &mut destination,
+              &mut stage,
+              &app,
+            );
           }
           YtdlpCommandEvent::Stderr(line) => {
             let line_str = String::from_utf8_lossy(&line);
             store_log_line(&line_str, &entry, log_state, &app);
-            parse_error_line(&line_str, &error_parser, &app);
+            if let Some(code) = parse_error_line(&line_str, &error_parser, &app) {
+              if !error_codes.contains(&code) {
+                error_codes.push(code);
+              }
+            }
+          }
+          YtdlpCommandEvent::Terminated(_) if restarting => {
+            restarting = false;
+            match build_runner(bandwidth.allotment()).spawn() {
+              Ok((next_rx, next_child)) => {
+                rx = next_rx;
+                child = next_child;
+              }
+              Err(e) => {
+                let _ = app.emit(
+                  "media_fatal",
+                  MediaFatalPayload::internal(
+                    entry.group_id.clone(),
+                    entry.id.clone(),
+                    format!("Download failed for group {}: {}", entry.group_id, e),
+                    Some(e.clone()),
+                  ),
+                );
+                return Err(YtdlpDownloadError::SpawnFailed(e));
+              }
+            }
           }
           YtdlpCommandEvent::Terminated(term) => {
-            if term.code == Some(0) {
+            // A stopped recording exits like an interrupted download but its file is complete.
+            if term.code == Some(0) || stopping {
+              if let Some(proxy) = &pool_proxy {
+                app.state::<ProxyPool>().report_success(proxy);
+              }
+              if archive_skip && !stopping {
+                tracing::info!(download_id = %entry.id, "Skipped download already in the archive");
+                let _ = app.emit(
+                  "media_skipped",
+                  MediaProgressComplete {
+                    id: entry.id.clone(),
+                    group_id: entry.group_id.clone(),
+                  },
+                );
+                return Ok(DownloadOutcome::Skipped);
+              }
+              let metadata = read_metadata(&metadata_path);
+              fire_media_hooks(
+                &app,
+                HookPayload::media(HookEvent::MediaComplete, &entry),
+                &metadata,
+                destination.as_ref(),
+              );
+              record_history(
+                &app,
+                &entry,
+                HistoryStatus::Completed,
+                metadata,
+                destination.map(|d| d.path),
+                None,
+                started_at,
+              );
               let _ = app.emit(
                 "media_complete",
                 MediaProgressComplete {
@@ -121,10 +284,32 @@ pub async fn run_ytdlp_download(
                   group_id: entry.group_id.clone(),
                 },
               );
-              return Ok(());
+              return Ok(DownloadOutcome::Completed);
             }
 
             let exit = term.code.unwrap_or(1);
+            let retry_settings = app.state::<SharedConfig>().load().performance.retry.clone();
+            if should_retry(&retry_settings, entry.attempt, &error_codes)
+              || fail_over_proxy(&app, &entry, pool_proxy.as_deref(), &error_codes)
+            {
+              return Ok(DownloadOutcome::Retry { codes: error_codes });
+            }
+
+            let metadata = read_metadata(&metadata_path);
+            let mut hook = HookPayload::media(HookEvent::MediaFatal, &entry);
+            hook.exit_code = Some(exit);
+            hook.error = error_codes.first().cloned();
+            fire_media_hooks(&app, hook, &metadata, destination.as_ref());
+            record_history(
+              &app,
+              &entry,
+              HistoryStatus::Failed,
+              metadata,
+              destination.map(|d| d.path),
+              error_codes.first().cloned(),
+              started_at,
+            );
+
             let _ = app.emit(
               "media_fatal",
               MediaFatalPayload::with_exit(
@@ -137,6 +322,19 @@ pub async fn run_ytdlp_download(
             return Err(YtdlpDownloadError::NonZeroExit(exit));
           }
           YtdlpCommandEvent::Error(err) => {
+            let metadata = read_metadata(&metadata_path);
+            let mut hook = HookPayload::media(HookEvent::MediaFatal, &entry);
+            hook.error = Some(err.clone());
+            fire_media_hooks(&app, hook, &metadata, destination.as_ref());
+            record_history(
+              &app,
+              &entry,
+              HistoryStatus::Failed,
+              metadata,
+              destination.map(|d| d.path),
+              error_codes.first().cloned(),
+              started_at,
+            );
             let msg = format!("Download failed for group {}: {}", entry.group_id, err);
             let _ = app.emit(
               "media_fatal",
@@ -152,28 +350,188 @@ pub async fn run_ytdlp_download(
           }
         }
       }
-      _ = cancel_rx.changed() => {
-        if is_cancelled_now(&cancel_rx) {
-          tracing::info!("Cancelled processing for group_id {}", entry.group_id);
+      _ = wait_for_stop(&mut recording), if !stopping => {
+        stopping = true;
+        tracing::info!("Stopping recording {} in group {}", entry.id, entry.group_id);
+        let _ = app.emit(
+          "media_progress_stage",
+          MediaProgressStage {
+            id: entry.id.clone(),
+            group_id: entry.group_id.clone(),
+            stage: ProgressStage::Stopping,
+          },
+        );
+        child.interrupt();
+      }
+      allotment = bandwidth.changed(), if !stopping && !restarting => {
+        // Post-processing does not use the network, so it is not interrupted.
+        if matches!(stage, ProgressStage::Initializing | ProgressStage::Downloading) {
+          tracing::info!(
+            download_id = %entry.id,
+            "Restarting yt-dlp with rate limit {allotment:?}"
+          );
+          restarting = true;
           let _ = child.kill_tree();
-          return Ok(());
         }
       }
+      _ = cancel_rx.changed() => {
+        if let Some(outcome) = interrupted_outcome(&cancel_rx) {
+          return Ok(stop_child(&child, &entry, outcome));
+        }
+      }
+      _ = wait_for_item_pause(&mut item_pause_rx) => {
+        return Ok(stop_child(&child, &entry, DownloadOutcome::Paused));
+      }
     }
   }
 
   Err(YtdlpDownloadError::EventStreamEnded)
 }
 
-fn is_cancelled_now(cancel_rx: &watch::Receiver<bool>) -> bool {
-  !*cancel_rx.borrow()
+/// Marks the proxy unhealthy when the errors point at it and tells whether the download should
+/// be retried through another proxy of the pool.
+fn fail_over_proxy<R: Runtime>(
+  app: &AppHandle<R>,
+  entry: &DownloadEntry,
+  proxy: Option<&str>,
+  error_codes: &[String],
+) -> bool {
+  let Some(proxy) = proxy else {
+    return false;
+  };
+  let config = app.state::<SharedConfig>().load();
+  let settings = &config.network.proxy_pool;
+  let Some(code) = error_codes
+    .iter()
+    .find(|code| settings.failover_codes.contains(code))
+  else {
+    return false;
+  };
+
+  let pool = app.state::<ProxyPool>();
+  pool.report_failure(proxy, code);
+  tracing::warn!(
+    download_id = %entry.id,
+    group_id = %entry.group_id,
+    code = %code,
+    "Proxy failed, switching to another one"
+  );
+  entry.attempt < config.performance.retry.max_attempts && pool.has_alternative(settings, proxy)
+}
+
+/// Reads and removes the metadata yt-dlp printed after moving the final file.
+fn read_metadata(path: &Path) -> DownloadedMetadata {
+  let metadata = std::fs::read_to_string(path)
+    .ok()
+    .and_then(|text| {
+      text
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str(line).ok())
+    })
+    .unwrap_or_default();
+  let _ = std::fs::remove_file(path);
+  metadata
+}
+
+/// Runs the hooks for a finished download with what is known about its file.
+fn fire_media_hooks<R: Runtime>(
+  app: &AppHandle<R>,
+  mut payload: HookPayload,
+  metadata: &DownloadedMetadata,
+  destination: Option<&MediaDestinationPath>,
+) {
+  payload.title.clone_from(&metadata.title);
+  payload.path = metadata
+    .filepath
+    .clone()
+    .or_else(|| destination.map(|d| d.path.clone()));
+  fire_hooks(app, payload);
+}
+
+fn record_history<R: Runtime>(
+  app: &AppHandle<R>,
+  entry: &DownloadEntry,
+  status: HistoryStatus,
+  metadata: DownloadedMetadata,
+  destination: Option<String>,
+  failure_code: Option<String>,
+  started_at: i64,
+) {
+  let Some(history) = app.try_state::<SharedHistory>() else {
+    return;
+  };
+
+  // Numbering belongs to the original run; a re-download is numbered again.
+  let mut template_context = entry.template_context.clone();
+  template_context.values.remove("autonumber");
+  template_context.values.remove("playlist_autonumber");
+
+  let record = NewHistoryEntry {
+    item_id: entry.id.clone(),
+    group_id: entry.group_id.clone(),
+    url: entry.url.clone(),
+    file_path: metadata.filepath.clone().or(destination),
+    metadata,
+    format: entry.format.clone(),
+    template_context,
+    status,
+    failure_code,
+    started_at,
+    finished_at: chrono::Utc::now().timestamp_millis(),
+    preset: entry.preset.clone(),
+    sections: entry.sections.clone(),
+    metadata_options: entry.metadata.clone(),
+  };
+  if let Err(e) = history.record(&record) {
+    tracing::warn!(download_id = %entry.id, error = %e, "Failed to record download history");
+  }
+}
+
+async fn wait_for_stop(recording: &mut Option<RecordingGuard>) {
+  match recording {
+    Some(recording) => recording.stop_requested().await,
+    None => std::future::pending().await,
+  }
 }
 
-fn store_log_line(
+/// Resolves once the item was paused on its own.
+async fn wait_for_item_pause(pause_rx: &mut watch::Receiver<bool>) {
+  if pause_rx.wait_for(|paused| *paused).await.is_err() {
+    std::future::pending::<()>().await;
+  }
+}
+
+fn interrupted_outcome(cancel_rx: &watch::Receiver<GroupState>) -> Option<DownloadOutcome> {
+  match *cancel_rx.borrow() {
+    GroupState::Running => None,
+    GroupState::Paused => Some(DownloadOutcome::Paused),
+    GroupState::Cancelled => Some(DownloadOutcome::Cancelled),
+  }
+}
+
+fn stop_child(
+  child: &YtdlpChild,
+  entry: &DownloadEntry,
+  outcome: DownloadOutcome,
+) -> DownloadOutcome {
+  match outcome {
+    DownloadOutcome::Paused => {
+      tracing::info!("Paused download {} in group {}", entry.id, entry.group_id);
+    }
+    _ => {
+      tracing::info!("Cancelled processing for group_id {}", entry.group_id);
+    }
+  }
+  let _ = child.kill_tree();
+  outcome
+}
+
+fn store_log_line<R: Runtime>(
   line: &str,
   entry: &DownloadEntry,
   log_state: State<LogStoreState>,
-  app: &AppHandle,
+  app: &AppHandle<R>,
 ) {
   if line.is_empty() || line.starts_with("RAW") {
     return;
@@ -182,31 +540,58 @@ fn store_log_line(
   store.append_line(app, &entry.group_id, line);
 }
 
-fn parse_progress_line(line: &str, progress_parser: &mut YtdlpProgressParser, app: &AppHandle) {
+fn parse_progress_line<R: Runtime>(
+  line: &str,
+  progress_parser: &mut YtdlpProgressParser,
+  bandwidth: &BandwidthLease,
+  final_destination: &mut Option<MediaDestinationPath>,
+  stage: &mut ProgressStage,
+  app: &AppHandle<R>,
+) {
   let progress_events = progress_parser.parse_line(line);
 
   for progress_event in progress_events {
     match progress_event {
       ProgressEvent::Destination(destination) => {
+        // Chapter and thumbnail files come on top of the full download, which stays the item's destination.
+        if destination.chapter.is_none()
+          && !destination.is_thumbnail
+          && final_destination
+            .as_ref()
+            .is_none_or(|current| destination.destination.confidence >= current.confidence)
+        {
+          *final_destination = Some(destination.destination.clone());
+        }
         app.emit("media_destination", destination).ok();
       }
       ProgressEvent::Progress(progress) => {
+        if let Some(speed_bps) = progress.speed_bps {
+          bandwidth.report_speed(speed_bps);
+        }
+        *stage = ProgressStage::Downloading;
         app.emit("media_progress", progress).ok();
       }
       ProgressEvent::StageChange(progress) => {
+        *stage = progress.stage.clone();
         app.emit("media_progress_stage", progress).ok();
       }
     }
   }
 }
 
-fn parse_error_line(line: &str, error_parser: &YtdlpErrorParser, app: &AppHandle) {
-  if let Some(event) = error_parser.parse_line(line) {
-    app
-      .emit(
-        "media_diagnostic",
-        MediaDiagnosticPayload::from_diagnostic_event(event),
-      )
-      .ok();
-  }
+/// Emits the diagnostic for an error line and returns its code when it is an error.
+fn parse_error_line<R: Runtime>(
+  line: &str,
+  error_parser: &YtdlpErrorParser,
+  app: &AppHandle<R>,
+) -> Option<String> {
+  let event = error_parser.parse_line(line)?;
+  let code = matches!(event.level, DiagnosticLevel::Error).then(|| event.code.clone());
+  app
+    .emit(
+      "media_diagnostic",
+      MediaDiagnosticPayload::from_diagnostic_event(event),
+    )
+    .ok();
+  code
 }
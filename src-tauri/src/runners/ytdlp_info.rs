@@ -1,12 +1,14 @@
 use crate::logging::LogStoreState;
 use crate::models::download::FormatOptions;
-use crate::models::{MediaDiagnosticPayload, MediaFatalPayload, ParsedMedia, TrackType};
+use crate::models::{MediaDiagnosticPayload, MediaFatalPayload, ParsedMedia};
 use crate::parsers::ytdlp_error::{DiagnosticMatcher, YtdlpErrorParser};
 use crate::parsers::ytdlp_info::parse_ytdlp_info;
 use crate::runners::ytdlp_runner::{is_spawn_error_file_not_found, YtdlpRunner};
+use crate::scheduling::proxy_pool::ProxyPool;
+use crate::SharedConfig;
 use std::borrow::Cow;
 use std::fmt;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 #[derive(Debug)]
 pub enum YtdlpInfoFetchError {
@@ -29,8 +31,8 @@ impl fmt::Display for YtdlpInfoFetchError {
 
 impl std::error::Error for YtdlpInfoFetchError {}
 
-pub async fn run_ytdlp_info_fetch(
-  app: &AppHandle,
+pub async fn run_ytdlp_info_fetch<R: Runtime>(
+  app: &AppHandle<R>,
   id: String,
   group_id: String,
   url: &str,
@@ -38,18 +40,15 @@ pub async fn run_ytdlp_info_fetch(
 ) -> Result<Option<ParsedMedia>, YtdlpInfoFetchError> {
   static RULES_JSON: &str = include_str!("../diagnostic_rules.json");
 
+  let pool_proxy =
+    app
+      .state::<ProxyPool>()
+      .select_for_url(&app.state::<SharedConfig>().load(), url, &group_id);
   let runner = YtdlpRunner::new(app)
-    .with_format_args(&format.unwrap_or(FormatOptions {
-      track_type: TrackType::Both,
-      abr: None,
-      height: None,
-      fps: None,
-      video_codec: None,
-      audio_codec: None,
-    }))
+    .with_format_args(&format.unwrap_or_default())
     .with_input_args()
-    .with_auth_args()
-    .with_network_args()
+    .with_auth_args(url, None)
+    .with_network_args(url, pool_proxy.as_deref())
     .with_args(["-J", "--flat-playlist"])
     .with_url(url);
 
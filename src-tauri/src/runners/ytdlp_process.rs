@@ -13,9 +13,18 @@ use std::os::windows::io::AsRawHandle;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
+#[cfg(windows)]
+use windows_sys::core::BOOL;
+
 #[cfg(windows)]
 use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
 
+#[cfg(windows)]
+use windows_sys::Win32::System::Console::{
+  AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, GetConsoleWindow, SetConsoleCtrlHandler,
+  CTRL_C_EVENT,
+};
+
 #[cfg(windows)]
 use windows_sys::Win32::System::JobObjects::{
   AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
@@ -32,6 +41,8 @@ pub struct PlatformProcess {
   pub pgid: Option<i32>,
   #[cfg(windows)]
   pub job: Option<JobHandle>,
+  #[cfg(windows)]
+  pub pid: u32,
 }
 
 #[cfg(windows)]
@@ -93,7 +104,10 @@ pub fn platform_process_from_child(child: &std::process::Child) -> Result<Platfo
     let job = create_job_for_child(child)
       .map(Some)
       .map_err(|e| format!("yt-dlp job object error: {e}"))?;
-    Ok(PlatformProcess { job })
+    Ok(PlatformProcess {
+      job,
+      pid: child.id(),
+    })
   }
 
   #[cfg(not(any(unix, windows)))]
@@ -121,6 +135,63 @@ pub fn kill_platform_process(platform: &PlatformProcess) {
   }
 }
 
+/// Asks the process tree to stop the way Ctrl+C would, so yt-dlp can finalise its output.
+pub fn interrupt_platform_process(platform: &PlatformProcess) {
+  #[cfg(unix)]
+  {
+    if let Some(pgid) = platform.pgid {
+      unsafe {
+        libc::killpg(pgid, libc::SIGINT);
+      }
+    }
+  }
+
+  #[cfg(windows)]
+  {
+    if !send_ctrl_c(platform.pid) {
+      tracing::warn!("Could not send Ctrl+C to yt-dlp, stopping it outright instead");
+      kill_platform_process(platform);
+    }
+  }
+
+  #[cfg(not(any(unix, windows)))]
+  {
+    kill_platform_process(platform);
+  }
+}
+
+/// Sends Ctrl+C to the hidden console yt-dlp runs in, which reaches it and its ffmpeg like in a
+/// terminal. Returns `false` when the event could not be sent.
+#[cfg(windows)]
+fn send_ctrl_c(pid: u32) -> bool {
+  // A process can only be attached to one console, so interrupts are sent one at a time.
+  static CONSOLE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+  static IGNORE_CTRL_C: std::sync::Once = std::sync::Once::new();
+
+  unsafe extern "system" fn ignore_ctrl_c(_ctrl_type: u32) -> BOOL {
+    1
+  }
+
+  let _guard = CONSOLE.lock().unwrap_or_else(|e| e.into_inner());
+  unsafe {
+    // Attaching means leaving the console this process already has, e.g. the headless CLI's.
+    if !GetConsoleWindow().is_null() {
+      return false;
+    }
+    // The event also reaches this process while it is attached. A handler, unlike ignoring
+    // Ctrl+C altogether, is not inherited by the yt-dlp processes started afterwards.
+    IGNORE_CTRL_C.call_once(|| {
+      SetConsoleCtrlHandler(Some(ignore_ctrl_c), 1);
+    });
+    if AttachConsole(pid) == 0 {
+      return false;
+    }
+    let sent = GenerateConsoleCtrlEvent(CTRL_C_EVENT, 0) != 0;
+    FreeConsole();
+    sent
+  }
+}
+
 #[cfg(windows)]
 fn create_job_for_child(child: &std::process::Child) -> io::Result<JobHandle> {
   let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null::<u16>()) };
@@ -1,23 +1,34 @@
-use crate::models::download::FormatOptions;
+use crate::models::download::{
+  FormatOptions, LiveRecordingOptions, MetadataOptions, SectionOptions,
+};
 use crate::models::TrackType;
 use crate::paths::PathsManager;
+use crate::runners::site_rules::{build_cookie_args, build_network_args, resolve_site_settings};
 use crate::runners::template_context::TemplateContext;
-use crate::runners::ytdlp_args::{build_format_args, build_location_args, build_output_args};
+use crate::runners::ytdlp_args::{
+  build_format_args, build_live_args, build_location_args, build_metadata_args, build_output_args,
+  build_section_args, section_file_name_template,
+};
+use crate::runners::ytdlp_auth::{AuthConfigFile, AUTH_DIR_NAME};
 use crate::runners::ytdlp_process::{
-  configure_command, kill_platform_process, platform_process_from_child, PlatformProcess,
+  configure_command, interrupt_platform_process, kill_platform_process,
+  platform_process_from_child, PlatformProcess,
 };
+use crate::scheduling::proxy_pool::redact_proxy;
+use crate::state::archive::DownloadArchive;
 use crate::state::config_models::{Config, SubtitleSettings};
 use crate::state::preferences_models::Preferences;
+use crate::state::presets_models::Preset;
 use crate::stronghold::stronghold_state::{AuthSecrets, StrongholdState};
 use crate::{SharedConfig, SharedPreferences};
 use std::collections::HashSet;
 use std::io;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 use std::sync::Arc;
 use std::thread;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime, Wry};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 #[derive(Debug, Clone)]
@@ -46,18 +57,24 @@ pub struct YtdlpChild {
   platform: PlatformProcess,
 }
 
-pub struct YtdlpRunner<'a> {
-  app: &'a AppHandle,
+pub struct YtdlpRunner<'a, R: Runtime = Wry> {
+  app: &'a AppHandle<R>,
   cfg: Arc<Config>,
   prefs: Arc<Preferences>,
   args: Vec<String>,
   bin_dir: PathBuf,
+  auth_dir: PathBuf,
+  auth_file: Option<AuthConfigFile>,
+  /// Set when the credentials could not be handed over, so the run fails instead of going on
+  /// without them.
+  auth_error: Option<String>,
 }
 
-impl<'a> YtdlpRunner<'a> {
-  pub fn new(app: &'a AppHandle) -> Self {
+impl<'a, R: Runtime> YtdlpRunner<'a, R> {
+  pub fn new(app: &'a AppHandle<R>) -> Self {
     let paths_manager = app.state::<PathsManager>();
     let bin_dir = paths_manager.bin_dir().clone();
+    let auth_dir = paths_manager.app_dir().join(AUTH_DIR_NAME);
     let args = vec!["--encoding".into(), "utf-8".into()];
     let cfg_handle = app.state::<SharedConfig>();
     let cfg = cfg_handle.load();
@@ -70,7 +87,62 @@ impl<'a> YtdlpRunner<'a> {
       prefs,
       args,
       bin_dir,
+      auth_dir,
+      auth_file: None,
+      auth_error: None,
+    }
+  }
+
+  /// Swaps the global output, subtitle, SponsorBlock and directory settings for the preset's.
+  /// Call it before any other `with_*` method that reads those settings.
+  pub fn with_preset(mut self, preset: Option<&Preset>) -> Self {
+    if let Some(preset) = preset {
+      let mut cfg = self.cfg.as_ref().clone();
+      let mut prefs = self.prefs.as_ref().clone();
+      preset.apply(&mut cfg, &mut prefs);
+      self.cfg = Arc::new(cfg);
+      self.prefs = Arc::new(prefs);
+    }
+    self
+  }
+
+  /// Downloads only the given sections. Call it before `with_location_args`, as sections that
+  /// end up in several files get the section in their file names.
+  pub fn with_section_args(mut self, sections: Option<&SectionOptions>) -> Self {
+    let Some(sections) = sections else {
+      return self;
+    };
+    self.args.extend(build_section_args(sections));
+    if sections.is_multi_part() {
+      let mut cfg = self.cfg.as_ref().clone();
+      cfg.output.file_name_template = section_file_name_template(&cfg.output.file_name_template);
+      cfg.output.audio_file_name_template =
+        section_file_name_template(&cfg.output.audio_file_name_template);
+      self.cfg = Arc::new(cfg);
+    }
+    self
+  }
+
+  pub fn with_metadata_args(
+    mut self,
+    metadata: Option<&MetadataOptions>,
+    template_context: &TemplateContext,
+  ) -> Self {
+    if let Some(metadata) = metadata {
+      let embeds_metadata = self.cfg.output.add_metadata;
+      self.args.extend(build_metadata_args(
+        metadata,
+        template_context,
+        embeds_metadata,
+      ));
     }
+    self
+  }
+
+  /// Whether thumbnails are kept as files rather than only embedded.
+  pub fn keeps_thumbnail_files(&self) -> bool {
+    let thumbnails = &self.cfg.output.thumbnails;
+    thumbnails.sidecar || thumbnails.write_all
   }
 
   pub fn with_args<I, S>(mut self, args: I) -> Self
@@ -82,47 +154,25 @@ impl<'a> YtdlpRunner<'a> {
     self
   }
 
-  pub fn with_network_args(mut self) -> Self {
-    let proxy_enabled = self.cfg.network.enable_proxy.is_some_and(|enabled| enabled);
-    if proxy_enabled {
-      if let Some(proxy) = self.cfg.network.proxy.as_ref() {
-        self.args.push("--proxy".to_string());
-        self.args.push(proxy.clone());
-      }
-    }
-
-    match self.cfg.network.impersonate.as_str() {
-      "none" => {}
-      "any" => {
-        self.args.push("--impersonate".to_string());
-        self.args.push(String::new());
-      }
-      other => {
-        self.args.push("--impersonate".to_string());
-        self.args.push(other.to_string());
-      }
+  /// Adds proxy, impersonation and extra headers, taking the site rule matching the URL into
+  /// account. `pool_proxy` replaces the global proxy when set.
+  pub fn with_network_args(mut self, url: &str, pool_proxy: Option<&str>) -> Self {
+    let mut settings = resolve_site_settings(&self.cfg, url);
+    if let Some(proxy) = pool_proxy {
+      settings.proxy = Some(proxy.to_string());
     }
-
+    self.args.extend(build_network_args(&settings));
     self
   }
 
-  pub fn with_auth_args(mut self) -> Self {
-    if self.cfg.auth.cookie_browser != "none" {
-      self.args.extend_from_slice(&[
-        "--cookies-from-browser".into(),
-        self.cfg.auth.cookie_browser.clone(),
-      ]);
-    }
-    if self.cfg.auth.cookie_file.is_some() {
-      self.args.extend_from_slice(&[
-        "--cookies".into(),
-        self.cfg.auth.cookie_file.clone().unwrap(),
-      ]);
-    }
+  /// Adds cookies and the credentials of the profile bound to the URL or extractor, if any.
+  pub fn with_auth_args(mut self, url: &str, extractor: Option<&str>) -> Self {
+    let settings = resolve_site_settings(&self.cfg, url);
+    self.args.extend(build_cookie_args(&settings));
 
     if let Some(sh_state) = self.app.try_state::<StrongholdState>() {
-      if let Ok(secrets) = sh_state.load_auth_secrets() {
-        self.apply_auth_secrets(secrets);
+      if let Ok(Some(secrets)) = sh_state.load_auth_secrets(url, extractor) {
+        self.apply_auth_secrets(&secrets);
       }
     }
 
@@ -167,13 +217,65 @@ impl<'a> YtdlpRunner<'a> {
             "--progress".into(),
             "--no-color".into(),
             "--progress-template".into(),
-            "RAW|%(progress.percent|)s|%(progress._percent_str|)s|%(progress.speed|)s|%(progress.eta|)s|%(progress.downloaded_bytes|)s|%(progress.total_bytes|)s|%(progress.total_bytes_estimate|)s|%(progress.fragment_index|)s|%(progress.fragment_count|)s".into(),
+            "RAW|%(progress.percent|)s|%(progress._percent_str|)s|%(progress.speed|)s|%(progress.eta|)s|%(progress.downloaded_bytes|)s|%(progress.total_bytes|)s|%(progress.total_bytes_estimate|)s|%(progress.fragment_index|)s|%(progress.fragment_count|)s|%(progress.elapsed|)s".into(),
             "--progress-delta".into(),
             "0.5".into(),
         ]);
     self
   }
 
+  /// Keeps `.part` files and continues them, so a download that was interrupted
+  /// (app restart, crash) picks up where it stopped when it is re-submitted.
+  pub fn with_resume_args(mut self) -> Self {
+    self
+      .args
+      .extend_from_slice(&["--continue".into(), "--part".into()]);
+    self
+  }
+
+  /// Records finished downloads in the app's archive, which also makes yt-dlp skip videos that
+  /// are already in it.
+  pub fn with_archive_args(mut self) -> Self {
+    if !self.cfg.output.download_archive {
+      return self;
+    }
+    if let Some(archive) = self.app.try_state::<DownloadArchive>() {
+      self.args.extend_from_slice(&[
+        "--download-archive".into(),
+        archive.path().to_string_lossy().into_owned(),
+      ]);
+    }
+    self
+  }
+
+  /// Appends the metadata of the finished download to `path` as a JSON line.
+  pub fn with_metadata_capture_args(mut self, path: &Path) -> Self {
+    self.args.extend_from_slice(&[
+      "--print-to-file".into(),
+      "after_move:%(.{id,title,uploader,extractor_key,duration,filesize,filesize_approx,filepath})j"
+        .into(),
+      path.to_string_lossy().into_owned(),
+    ]);
+    self
+  }
+
+  pub fn with_live_args(mut self, options: Option<&LiveRecordingOptions>) -> Self {
+    if let Some(options) = options {
+      self.args.extend(build_live_args(options));
+    }
+    self
+  }
+
+  /// Caps this process at `limit` bytes per second, unlimited when `None`.
+  pub fn with_rate_limit_args(mut self, limit: Option<u64>) -> Self {
+    if let Some(limit) = limit {
+      self
+        .args
+        .extend_from_slice(&["--limit-rate".into(), limit.to_string()]);
+    }
+    self
+  }
+
   pub fn with_format_args(mut self, format_options: &FormatOptions) -> Self {
     self
       .args
@@ -222,16 +324,37 @@ impl<'a> YtdlpRunner<'a> {
     self
   }
 
+  /// The arguments for the log, with credentials stripped from proxy URLs.
+  fn logged_args(&self) -> String {
+    let mut after_proxy = false;
+    self
+      .args
+      .iter()
+      .map(|arg| {
+        let logged = if after_proxy {
+          redact_proxy(arg)
+        } else {
+          arg.clone()
+        };
+        after_proxy = arg == "--proxy";
+        logged
+      })
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+
   pub async fn output(self) -> Result<YtdlpOutput, String> {
-    tracing::info!("Running command: yt-dlp {}", self.args.join(" "));
+    self.check_auth()?;
+    tracing::info!("Running command: yt-dlp {}", self.logged_args());
     let mut command = self.build_command();
 
     configure_command(&mut command).map_err(|e| format!("yt-dlp spawn setup failed: {e}"))?;
 
+    let auth_file = self.auth_file;
     tauri::async_runtime::spawn_blocking(move || {
-      let output = command
-        .output()
-        .map_err(|e| format!("yt-dlp failed to run: {e}"))?;
+      let output = command.output();
+      drop(auth_file);
+      let output = output.map_err(|e| format!("yt-dlp failed to run: {e}"))?;
       Ok(YtdlpOutput {
         status: output.status,
         stdout: output.stdout,
@@ -243,7 +366,8 @@ impl<'a> YtdlpRunner<'a> {
   }
 
   pub fn spawn(self) -> Result<(UnboundedReceiver<YtdlpCommandEvent>, YtdlpChild), String> {
-    tracing::info!("Running command: yt-dlp {}", self.args.join(" "));
+    self.check_auth()?;
+    tracing::info!("Running command: yt-dlp {}", self.logged_args());
     let mut command = self.build_command();
     command
       .stdin(Stdio::piped())
@@ -276,8 +400,10 @@ impl<'a> YtdlpRunner<'a> {
     }
 
     let wait_tx = tx.clone();
+    let auth_file = self.auth_file;
     thread::spawn(move || {
       let status = raw_child.wait();
+      drop(auth_file);
       match status {
         Ok(status) => {
           let payload = TerminatedPayload {
@@ -297,43 +423,60 @@ impl<'a> YtdlpRunner<'a> {
   }
 
   fn build_command(&self) -> Command {
-    let separator = if cfg!(windows) { ';' } else { ':' };
-    let path_env = std::env::var("PATH").unwrap_or_default();
-    let new_path = format!("{}{}{}", self.bin_dir.display(), separator, path_env);
-    let mut command = Command::new("yt-dlp");
-    command.args(&self.args).env("PATH", new_path);
-    command
+    build_ytdlp_command(&self.bin_dir, &self.args, self.auth_file.as_ref())
   }
 
-  fn apply_auth_secrets(&mut self, s: AuthSecrets) {
-    if let Some(u) = s.username {
-      self.args.push("--username".into());
-      self.args.push(u);
-    }
-    if let Some(p) = s.password {
-      self.args.push("--password".into());
-      self.args.push(p);
-    }
-    if let Some(vp) = s.video_password {
-      self.args.push("--video-password".into());
-      self.args.push(vp);
+  /// Hands the secrets to yt-dlp through a private config file in the app's data directory
+  /// instead of argv.
+  fn apply_auth_secrets(&mut self, s: &AuthSecrets) {
+    match write_auth_file(&self.auth_dir, s) {
+      Ok(file) => self.auth_file = file,
+      Err(e) => self.auth_error = Some(e),
     }
-    if let Some(token) = s.bearer_token {
-      self.args.push("--add-header".into());
-      self.args.push(format!("Authorization:Bearer {token}"));
-    }
-    for h in s.headers {
-      self.args.push("--add-header".into());
-      self.args.push(h);
+  }
+
+  fn check_auth(&self) -> Result<(), String> {
+    match &self.auth_error {
+      Some(e) => Err(e.clone()),
+      None => Ok(()),
     }
   }
 }
 
+/// The yt-dlp command for the arguments, pointed at the credentials file when there is one.
+fn build_ytdlp_command(
+  bin_dir: &Path,
+  args: &[String],
+  auth_file: Option<&AuthConfigFile>,
+) -> Command {
+  let separator = if cfg!(windows) { ';' } else { ':' };
+  let path_env = std::env::var("PATH").unwrap_or_default();
+  let new_path = format!("{}{}{}", bin_dir.display(), separator, path_env);
+  let mut command = Command::new("yt-dlp");
+  if let Some(file) = auth_file {
+    command.args(file.args());
+  }
+  command.args(args).env("PATH", new_path);
+  command
+}
+
+/// Writes the credentials file for a run, an error the run fails with when that is impossible.
+fn write_auth_file(dir: &Path, secrets: &AuthSecrets) -> Result<Option<AuthConfigFile>, String> {
+  AuthConfigFile::create(dir, secrets).map_err(|e| {
+    tracing::error!("Failed to write credentials config: {e}");
+    format!("Failed to write the credentials for yt-dlp: {e}")
+  })
+}
+
 impl YtdlpChild {
   pub fn kill_tree(&self) -> Result<(), String> {
     kill_platform_process(&self.platform);
     Ok(())
   }
+
+  pub fn interrupt(&self) {
+    interrupt_platform_process(&self.platform);
+  }
 }
 
 /// 判断 spawn/run 失败是否因系统找不到可执行文件（如 yt-dlp 未安装）。
@@ -485,8 +628,72 @@ fn sanitize_vec(items: &[String]) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-  use super::build_subtitle_args;
-  use crate::state::config_models::SubtitleSettings;
+  use super::*;
+
+  fn test_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("ovd-runner-test-{}", uuid::Uuid::new_v4()))
+  }
+
+  #[test]
+  fn command_never_contains_secrets() {
+    let dir = test_dir();
+    let secrets = AuthSecrets {
+      username: Some("alice".into()),
+      password: Some("hunter2 it's #1".into()),
+      video_password: Some("vid-secret".into()),
+      bearer_token: Some("tok-secret".into()),
+      headers: vec!["X-Api-Key: header-secret".into()],
+    };
+    let auth_file = write_auth_file(&dir, &secrets).unwrap();
+    let args: Vec<String> = vec![
+      "--encoding".into(),
+      "utf-8".into(),
+      "https://a.example/watch?v=1".into(),
+    ];
+
+    let command = build_ytdlp_command(Path::new(""), &args, auth_file.as_ref());
+    let argv: Vec<String> = command
+      .get_args()
+      .map(|arg| arg.to_string_lossy().into_owned())
+      .collect();
+    for secret in [
+      "alice",
+      "hunter2",
+      "vid-secret",
+      "tok-secret",
+      "header-secret",
+    ] {
+      assert!(
+        argv.iter().all(|arg| !arg.contains(secret)),
+        "{secret} leaked into {argv:?}"
+      );
+    }
+    assert!(argv.ends_with(&args));
+    let config = argv
+      .iter()
+      .position(|arg| arg == "--config-locations")
+      .map(|i| PathBuf::from(&argv[i + 1]))
+      .expect("config file argument");
+    assert!(config.starts_with(&dir));
+
+    drop(auth_file);
+    assert!(!config.exists());
+    std::fs::remove_dir(&dir).unwrap();
+  }
+
+  #[test]
+  fn unwritable_credentials_fail_the_run() {
+    let file = test_dir();
+    std::fs::write(&file, "").unwrap();
+    let secrets = AuthSecrets {
+      password: Some("hunter2".into()),
+      ..AuthSecrets::default()
+    };
+    // A file where the directory should be makes creating the config fail.
+    let err = write_auth_file(&file, &secrets).unwrap_err();
+    std::fs::remove_file(&file).unwrap();
+    assert!(err.starts_with("Failed to write the credentials"), "{err}");
+  }
 
   #[test]
   fn subtitles_disabled_returns_none() {
@@ -0,0 +1,405 @@
+use crate::scheduling::schedule::{is_within_window, parse_time_of_day};
+use crate::state::config_models::NetworkSettings;
+use crate::SharedConfig;
+use chrono::NaiveTime;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::watch;
+
+/// Smallest share kept by a download that barely uses its share, so it can pick up again.
+const MIN_ALLOTMENT: u64 = 16 * 1024;
+/// A download that uses less than this part of its share leaves the rest to the others.
+const USED_SHARE_RATIO: f64 = 0.8;
+/// Room a download that leaves bandwidth to others keeps above its measured speed.
+const SPEED_HEADROOM: f64 = 1.25;
+/// Raises smaller than this part of the current share are not worth restarting yt-dlp for.
+const MIN_RAISE_RATIO: f64 = 0.1;
+const SCHEDULE_TICK: Duration = Duration::from_secs(30);
+
+struct ActiveTransfer {
+  allotment: Option<u64>,
+  speed_bps: Option<f64>,
+  updates: watch::Sender<Option<u64>>,
+}
+
+impl ActiveTransfer {
+  /// What the download needs, `None` when it would take more than it has.
+  fn demand(&self) -> Option<u64> {
+    let allotment = self.allotment? as f64;
+    let speed = self
+      .speed_bps
+      .filter(|speed| speed.is_finite() && *speed >= 0.0)?;
+    (speed < allotment * USED_SHARE_RATIO)
+      .then(|| ((speed * SPEED_HEADROOM) as u64).max(MIN_ALLOTMENT))
+  }
+}
+
+#[derive(Default)]
+struct BandwidthState {
+  limit: Option<u64>,
+  active: HashMap<String, ActiveTransfer>,
+}
+
+impl BandwidthState {
+  /// Divides the limit again and tells every download whose share changed.
+  fn rebalance(&mut self) {
+    let shares: Vec<Option<u64>> = match self.limit {
+      Some(limit) => {
+        let demands: Vec<Option<u64>> = self.active.values().map(ActiveTransfer::demand).collect();
+        allot_shares(limit, &demands)
+          .into_iter()
+          .map(Some)
+          .collect()
+      }
+      None => vec![None; self.active.len()],
+    };
+
+    for (transfer, share) in self.active.values_mut().zip(shares) {
+      let small_raise = matches!(
+        (transfer.allotment, share),
+        (Some(current), Some(share))
+          if share > current && ((share - current) as f64) < current as f64 * MIN_RAISE_RATIO
+      );
+      if transfer.allotment != share && !small_raise {
+        transfer.allotment = share;
+        transfer.updates.send_replace(share);
+      }
+    }
+  }
+}
+
+/// Divides the global rate limit across the running yt-dlp processes.
+///
+/// yt-dlp only accepts `--limit-rate` at spawn time. Whenever a download starts or finishes, or
+/// the limit changes with the time of day, the shares are divided again and every download whose
+/// share changed is told through its lease, so it can restart yt-dlp with the new limit and
+/// continue its `.part` files. Downloads that use less than their share (by their reported speed)
+/// leave the rest to the others. The shares never add up to more than the limit.
+#[derive(Clone, Default)]
+pub struct BandwidthManager {
+  state: Arc<Mutex<BandwidthState>>,
+}
+
+pub struct BandwidthLease {
+  tracked: Option<(BandwidthManager, String)>,
+  updates: watch::Receiver<Option<u64>>,
+}
+
+impl BandwidthManager {
+  /// Registers a starting download under the limit that applies now.
+  pub fn acquire(&self, id: &str, limit: Option<u64>) -> BandwidthLease {
+    let mut state = self.state.lock().unwrap();
+    state.limit = limit;
+    state.active.insert(
+      id.to_string(),
+      ActiveTransfer {
+        allotment: None,
+        speed_bps: None,
+        updates: watch::Sender::new(None),
+      },
+    );
+    state.rebalance();
+
+    BandwidthLease {
+      tracked: Some((self.clone(), id.to_string())),
+      updates: state.active[id].updates.subscribe(),
+    }
+  }
+
+  /// Applies a new global limit to the running downloads.
+  pub fn set_limit(&self, limit: Option<u64>) {
+    let mut state = self.state.lock().unwrap();
+    if state.limit != limit {
+      state.limit = limit;
+      state.rebalance();
+    }
+  }
+
+  fn report_speed(&self, id: &str, speed_bps: f64) {
+    if let Some(transfer) = self.state.lock().unwrap().active.get_mut(id) {
+      transfer.speed_bps = Some(speed_bps);
+    }
+  }
+
+  fn release(&self, id: &str) {
+    let mut state = self.state.lock().unwrap();
+    if state.active.remove(id).is_some() {
+      state.rebalance();
+    }
+  }
+}
+
+impl BandwidthLease {
+  /// A lease outside of the limit, for live recordings that cannot be restarted without losing
+  /// part of the stream.
+  pub fn untracked() -> Self {
+    Self {
+      tracked: None,
+      updates: watch::channel(None).1,
+    }
+  }
+
+  /// The `--limit-rate` value for this download, `None` when unlimited.
+  pub fn allotment(&self) -> Option<u64> {
+    *self.updates.borrow()
+  }
+
+  /// Resolves with the new share once it changed.
+  pub async fn changed(&mut self) -> Option<u64> {
+    if self.updates.changed().await.is_err() {
+      std::future::pending::<()>().await;
+    }
+    *self.updates.borrow_and_update()
+  }
+
+  pub fn report_speed(&self, speed_bps: f64) {
+    if let Some((manager, id)) = &self.tracked {
+      manager.report_speed(id, speed_bps);
+    }
+  }
+}
+
+impl Drop for BandwidthLease {
+  fn drop(&mut self) {
+    if let Some((manager, id)) = &self.tracked {
+      manager.release(id);
+    }
+  }
+}
+
+/// Splits `limit` across the downloads so the shares add up to at most `limit`. A download with
+/// a demand below its fair share gets its demand, the rest is split evenly among the others.
+pub fn allot_shares(limit: u64, demands: &[Option<u64>]) -> Vec<u64> {
+  let mut shares = vec![0; demands.len()];
+  let mut open: Vec<usize> = (0..demands.len()).collect();
+  let mut remaining = limit;
+  while !open.is_empty() {
+    let fair = remaining / open.len() as u64;
+    let (satisfied, rest): (Vec<usize>, Vec<usize>) = open
+      .iter()
+      .partition(|&&i| demands[i].is_some_and(|demand| demand <= fair));
+    if satisfied.is_empty() {
+      for i in rest {
+        // yt-dlp reads a zero limit as no limit at all.
+        shares[i] = fair.max(1);
+      }
+      break;
+    }
+    for i in satisfied {
+      let demand = demands[i].unwrap_or_default();
+      shares[i] = demand;
+      remaining -= demand;
+    }
+    open = rest;
+  }
+  shares
+}
+
+/// Follows the rate limit schedule, so running downloads are re-limited when a window starts or
+/// ends.
+pub fn start_rate_limit_schedule<R: Runtime>(app: &AppHandle<R>) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(SCHEDULE_TICK).await;
+      let limit = effective_rate_limit(
+        &app.state::<SharedConfig>().load().network,
+        chrono::Local::now().time(),
+      );
+      app.state::<BandwidthManager>().set_limit(limit);
+    }
+  });
+}
+
+/// Resolves the limit that applies at the given local time of day.
+pub fn effective_rate_limit(settings: &NetworkSettings, now: NaiveTime) -> Option<u64> {
+  for window in &settings.rate_limit_schedule {
+    let (Some(start), Some(end)) = (
+      parse_time_of_day(&window.start),
+      parse_time_of_day(&window.end),
+    ) else {
+      tracing::warn!(
+        "Ignoring rate limit window with invalid times: {}-{}",
+        window.start,
+        window.end
+      );
+      continue;
+    };
+    if is_within_window(now, start, end) {
+      return window.limit.filter(|limit| *limit > 0);
+    }
+  }
+  settings.rate_limit.filter(|limit| *limit > 0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::config_models::RateLimitWindow;
+
+  fn time(value: &str) -> NaiveTime {
+    parse_time_of_day(value).expect("valid time")
+  }
+
+  fn settings_with_night_window() -> NetworkSettings {
+    NetworkSettings {
+      rate_limit: Some(1_000_000),
+      rate_limit_schedule: vec![RateLimitWindow {
+        start: "22:00".into(),
+        end: "06:00".into(),
+        limit: None,
+      }],
+      ..NetworkSettings::default()
+    }
+  }
+
+  #[test]
+  fn limit_applies_outside_schedule() {
+    let settings = settings_with_night_window();
+    assert_eq!(
+      effective_rate_limit(&settings, time("12:00")),
+      Some(1_000_000)
+    );
+    assert_eq!(
+      effective_rate_limit(&settings, time("06:00")),
+      Some(1_000_000)
+    );
+  }
+
+  #[test]
+  fn window_wrapping_midnight_overrides_limit() {
+    let settings = settings_with_night_window();
+    assert_eq!(effective_rate_limit(&settings, time("23:30")), None);
+    assert_eq!(effective_rate_limit(&settings, time("00:15")), None);
+    assert_eq!(effective_rate_limit(&settings, time("22:00")), None);
+  }
+
+  #[test]
+  fn invalid_windows_are_ignored() {
+    let mut settings = settings_with_night_window();
+    settings.rate_limit_schedule[0].start = "late".into();
+    assert_eq!(
+      effective_rate_limit(&settings, time("23:30")),
+      Some(1_000_000)
+    );
+  }
+
+  #[test]
+  fn first_download_gets_whole_limit() {
+    assert_eq!(allot_shares(1_000_000, &[None]), vec![1_000_000]);
+  }
+
+  #[test]
+  fn unused_bandwidth_goes_to_the_others() {
+    // The first download only needs 200 KB/s.
+    assert_eq!(
+      allot_shares(1_000_000, &[Some(200_000), None]),
+      vec![200_000, 800_000]
+    );
+  }
+
+  #[test]
+  fn busy_downloads_split_the_limit_evenly() {
+    assert_eq!(
+      allot_shares(900_000, &[None, None, None]),
+      vec![300_000, 300_000, 300_000]
+    );
+    // A demand above the fair share does not count as spare bandwidth.
+    assert_eq!(
+      allot_shares(900_000, &[Some(600_000), None, None]),
+      vec![300_000, 300_000, 300_000]
+    );
+  }
+
+  #[test]
+  fn shares_never_exceed_the_limit() {
+    let demands = [
+      vec![],
+      vec![None],
+      vec![Some(10), None, Some(5_000_000)],
+      vec![Some(100_000), Some(100_000), Some(100_000), None, None],
+      vec![Some(450_000), Some(450_000), Some(1)],
+      vec![None; 7],
+    ];
+    for limit in [1_000, 333_333, 900_000, 1_000_000] {
+      for demands in &demands {
+        let total: u64 = allot_shares(limit, demands).iter().sum();
+        assert!(total <= limit, "{total} > {limit} for {demands:?}");
+      }
+    }
+  }
+
+  fn total_allotted(manager: &BandwidthManager) -> u64 {
+    let state = manager.state.lock().unwrap();
+    state
+      .active
+      .values()
+      .filter_map(|transfer| transfer.allotment)
+      .sum()
+  }
+
+  #[test]
+  fn manager_rebalances_on_start_and_finish() {
+    let manager = BandwidthManager::default();
+    let first = manager.acquire("a", Some(900_000));
+    assert_eq!(first.allotment(), Some(900_000));
+
+    let second = manager.acquire("b", Some(900_000));
+    let third = manager.acquire("c", Some(900_000));
+    assert_eq!(first.allotment(), Some(300_000));
+    assert_eq!(second.allotment(), Some(300_000));
+    assert_eq!(third.allotment(), Some(300_000));
+    assert_eq!(total_allotted(&manager), 900_000);
+
+    // The first download only uses 100 KB/s, so the next split leaves it 125 KB/s.
+    first.report_speed(100_000.0);
+    drop(third);
+    assert_eq!(first.allotment(), Some(125_000));
+    assert_eq!(second.allotment(), Some(775_000));
+    assert!(total_allotted(&manager) <= 900_000);
+
+    drop(first);
+    assert_eq!(second.allotment(), Some(900_000));
+  }
+
+  #[tokio::test]
+  async fn window_change_limits_running_downloads() {
+    let manager = BandwidthManager::default();
+    let mut first = manager.acquire("a", None);
+    let second = manager.acquire("b", None);
+    assert_eq!(first.allotment(), None);
+
+    manager.set_limit(Some(1_000_000));
+    assert_eq!(first.changed().await, Some(500_000));
+    assert_eq!(second.allotment(), Some(500_000));
+    assert_eq!(total_allotted(&manager), 1_000_000);
+
+    manager.set_limit(None);
+    assert_eq!(first.changed().await, None);
+  }
+
+  #[test]
+  fn small_raises_keep_the_running_share() {
+    let manager = BandwidthManager::default();
+    let first = manager.acquire("a", Some(1_000_000));
+    let _second = manager.acquire("b", Some(1_000_000));
+
+    manager.set_limit(Some(1_050_000));
+    assert_eq!(first.allotment(), Some(500_000));
+
+    manager.set_limit(Some(1_200_000));
+    assert_eq!(first.allotment(), Some(600_000));
+    assert_eq!(total_allotted(&manager), 1_200_000);
+  }
+
+  #[test]
+  fn untracked_leases_stay_unlimited() {
+    let manager = BandwidthManager::default();
+    let _limited = manager.acquire("a", Some(1_000_000));
+    let lease = BandwidthLease::untracked();
+    assert_eq!(lease.allotment(), None);
+    assert_eq!(manager.state.lock().unwrap().active.len(), 1);
+  }
+}
@@ -1,5 +1,7 @@
 use crate::scheduling::concurrency::DynamicSemaphore;
-use crate::scheduling::group_state::{is_group_running, remove_group};
+use crate::scheduling::group_state::{
+  is_group_active, is_group_paused, is_group_running, remove_group,
+};
 use crate::scheduling::numbering::NumberingManager;
 use futures::Future;
 use std::{collections::VecDeque, sync::Arc};
@@ -10,12 +12,22 @@ pub trait DispatchEntry: Clone + Send + Sync + 'static {
   fn group_id(&self) -> &String;
   fn group_key(&self) -> Option<&String>;
   fn set_numbering(&mut self, autonumber: u64, group_autonumber: Option<u64>);
+  /// Entries that were already numbered (e.g. re-queued after a pause) keep their numbers,
+  /// so their output template does not change.
+  fn is_numbered(&self) -> bool {
+    false
+  }
+  /// Entries paused on their own are set aside while the rest of their group runs.
+  fn is_paused(&self) -> bool {
+    false
+  }
 }
 
 #[derive(Clone)]
 pub enum DispatchRequest<Req> {
   Pipeline(Req),
   Cleanup { group_id: String },
+  Resume { group_id: String },
 }
 
 pub struct GenericDispatcher<Req> {
@@ -53,19 +65,30 @@ where
       let mut numbering = NumberingManager::new();
       let mut queues: VecDeque<(String, VecDeque<Entry>)> = VecDeque::new();
       let mut pending_requeue: VecDeque<(String, VecDeque<Entry>)> = VecDeque::new();
+      // Entries of paused groups wait here until the group is resumed.
+      let mut parked: VecDeque<(String, VecDeque<Entry>)> = VecDeque::new();
+      let mut parked_entries: VecDeque<Entry> = VecDeque::new();
       loop {
         while let Ok(req) = rx.try_recv() {
           match req {
             DispatchRequest::Cleanup { group_id } => {
               queues.retain(|(gid, _)| gid != &group_id);
+              parked.retain(|(gid, _)| gid != &group_id);
+              parked_entries.retain(|entry| entry.group_id() != &group_id);
               remove_group(&group_id);
             }
+            DispatchRequest::Resume { group_id } => {
+              tracing::trace!(group_id = %group_id, "Dispatcher: resuming group");
+            }
             DispatchRequest::Pipeline(inner) => {
               let mut entries = make_entries(inner.clone());
               if !entries.is_empty() {
                 let gid = entries[0].group_id().clone();
-                if is_group_running(&gid) {
+                if is_group_active(&gid) {
                   for entry in entries.iter_mut() {
+                    if entry.is_numbered() {
+                      continue;
+                    }
                     let group_key = entry.group_key();
                     let (autonumber, group_autonumber) = numbering.assign_for(group_key);
                     entry.set_numbering(autonumber, group_autonumber);
@@ -79,12 +102,44 @@ where
           }
         }
 
-        queues.retain(|(gid, _)| is_group_running(gid));
-
         if !pending_requeue.is_empty() {
           queues.append(&mut pending_requeue);
         }
 
+        // Move groups between the active and parked queues based on their current state.
+        let mut still_parked = VecDeque::new();
+        for (gid, q) in parked.drain(..) {
+          if is_group_running(&gid) {
+            queues.push_back((gid, q));
+          } else if is_group_paused(&gid) {
+            still_parked.push_back((gid, q));
+          }
+        }
+        parked = still_parked;
+
+        let mut still_parked_entries = VecDeque::new();
+        for entry in parked_entries.drain(..) {
+          if !is_group_active(entry.group_id()) {
+            continue;
+          }
+          if entry.is_paused() {
+            still_parked_entries.push_back(entry);
+          } else {
+            queues.push_back((entry.group_id().clone(), VecDeque::from([entry])));
+          }
+        }
+        parked_entries = still_parked_entries;
+
+        let mut runnable = VecDeque::new();
+        for (gid, q) in queues.drain(..) {
+          if is_group_running(&gid) {
+            runnable.push_back((gid, q));
+          } else if is_group_paused(&gid) {
+            parked.push_back((gid, q));
+          }
+        }
+        queues = runnable;
+
         // If no work, block until the next request.
         if queues.is_empty() {
           if let Some(req) = rx.recv().await {
@@ -106,9 +161,12 @@ where
           // Pop the next group and its next entry
           let (group_id, mut q) = queues.pop_front().unwrap();
 
-          // Skip cancelled groups.
+          // Park paused groups and skip cancelled groups.
           let still_running = is_group_running(&group_id);
           if !still_running {
+            if is_group_paused(&group_id) {
+              parked.push_back((group_id, q));
+            }
             drop(permit);
             continue;
           }
@@ -116,6 +174,15 @@ where
           // Grab the entry that is going to be run.
           let entry = q.pop_front().unwrap();
 
+          if entry.is_paused() {
+            parked_entries.push_back(entry);
+            if !q.is_empty() {
+              pending_requeue.push_back((group_id, q));
+            }
+            drop(permit);
+            continue;
+          }
+
           tracing::trace!(
             group_id = %group_id,
             remaining_in_group = q.len(),
@@ -163,7 +230,9 @@ where
 mod tests {
   use super::*;
   use crate::scheduling::concurrency::DynamicSemaphore;
-  use crate::scheduling::group_state::ensure_group_running;
+  use crate::scheduling::group_state::{
+    ensure_group_running, is_item_paused, pause_group, pause_item, resume_group, resume_item,
+  };
   use std::sync::atomic::{AtomicUsize, Ordering};
   use std::sync::Arc;
   use tauri::test::mock_app;
@@ -184,6 +253,9 @@ mod tests {
       None
     }
     fn set_numbering(&mut self, _autonumber: u64, _group_autonumber: Option<u64>) {}
+    fn is_paused(&self) -> bool {
+      is_item_paused(&format!("{}:{}", self.group_id, self.index))
+    }
   }
 
   async fn run_concurrency_test(max_concurrency: usize, total: usize) -> usize {
@@ -446,4 +518,139 @@ mod tests {
       "expected new group to run before the last queued entry of existing group"
     );
   }
+
+  #[tokio::test]
+  async fn dispatcher_parks_paused_group_until_resumed() {
+    let app = mock_app();
+    let group_id = "paused-group".to_string();
+    ensure_group_running(&group_id);
+    pause_group(&group_id);
+
+    let sem = Arc::new(DynamicSemaphore::new(1));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(Notify::new());
+    let total = 2usize;
+
+    let completed_job = completed.clone();
+    let done_job = done.clone();
+
+    let dispatcher = GenericDispatcher::start(
+      app.handle().clone(),
+      sem,
+      {
+        let group_id = group_id.clone();
+        move |count: usize| {
+          (0..count)
+            .map(|index| TestEntry {
+              group_id: group_id.clone(),
+              index,
+            })
+            .collect()
+        }
+      },
+      move |_tx, _app, _entry: TestEntry| {
+        let completed_job = completed_job.clone();
+        let done_job = done_job.clone();
+        async move {
+          let done_count = completed_job.fetch_add(1, Ordering::SeqCst) + 1;
+          if done_count == total {
+            done_job.notify_one();
+          }
+        }
+      },
+    );
+
+    dispatcher
+      .sender()
+      .send(DispatchRequest::Pipeline(total))
+      .unwrap();
+
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+      completed.load(Ordering::SeqCst),
+      0,
+      "expected paused group to stay parked"
+    );
+
+    resume_group(&group_id);
+    dispatcher
+      .sender()
+      .send(DispatchRequest::Resume {
+        group_id: group_id.clone(),
+      })
+      .unwrap();
+
+    timeout(Duration::from_secs(5), done.notified())
+      .await
+      .expect("dispatcher did not resume the parked group");
+  }
+
+  #[tokio::test]
+  async fn dispatcher_sets_paused_entry_aside_until_resumed() {
+    let app = mock_app();
+    let group_id = "item-group".to_string();
+    let paused_id = format!("{group_id}:0");
+    ensure_group_running(&group_id);
+    pause_item(&group_id, &paused_id);
+
+    let sem = Arc::new(DynamicSemaphore::new(1));
+    let started: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(Vec::new()));
+    let done = Arc::new(Notify::new());
+    let total = 2usize;
+
+    let started_job = started.clone();
+    let done_job = done.clone();
+
+    let dispatcher = GenericDispatcher::start(
+      app.handle().clone(),
+      sem,
+      {
+        let group_id = group_id.clone();
+        move |count: usize| {
+          (0..count)
+            .map(|index| TestEntry {
+              group_id: group_id.clone(),
+              index,
+            })
+            .collect()
+        }
+      },
+      move |_tx, _app, entry: TestEntry| {
+        let started_job = started_job.clone();
+        let done_job = done_job.clone();
+        async move {
+          let mut guard = started_job.lock().await;
+          guard.push(entry.index);
+          if guard.len() == total {
+            done_job.notify_one();
+          }
+        }
+      },
+    );
+
+    dispatcher
+      .sender()
+      .send(DispatchRequest::Pipeline(total))
+      .unwrap();
+
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+      *started.lock().await,
+      vec![1],
+      "expected only the entry that is not paused to run"
+    );
+
+    assert!(resume_item(&paused_id));
+    dispatcher
+      .sender()
+      .send(DispatchRequest::Resume {
+        group_id: group_id.clone(),
+      })
+      .unwrap();
+
+    timeout(Duration::from_secs(5), done.notified())
+      .await
+      .expect("dispatcher did not run the resumed entry");
+    assert_eq!(*started.lock().await, vec![1, 0]);
+  }
 }
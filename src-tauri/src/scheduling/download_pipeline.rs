@@ -1,15 +1,29 @@
-use crate::models::download::FormatOptions;
+use crate::models::download::{
+  FormatOptions, LiveRecordingOptions, MetadataOptions, SectionOptions,
+};
+use crate::models::payloads::{GroupCompletePayload, MediaRetryPayload};
 use crate::models::DownloadItem;
+use crate::models::{MediaProgressStage, ProgressStage};
 use crate::runners::template_context::TemplateContext;
-use crate::runners::ytdlp_download::{run_ytdlp_download, YtdlpDownloadError};
+use crate::runners::ytdlp_download::{run_ytdlp_download, DownloadOutcome, YtdlpDownloadError};
 use crate::scheduling::concurrency::DynamicSemaphore;
 use crate::scheduling::dispatcher::{DispatchEntry, DispatchRequest, GenericDispatcher};
+use crate::scheduling::group_state::{
+  ensure_group_running, forget_item, is_item_paused, pause_group, pause_item,
+};
+use crate::scheduling::hooks::{fire_hooks, HookPayload};
+use crate::scheduling::proxy_pool::ProxyPool;
+use crate::scheduling::retry::retry_delay;
+use crate::scheduling::schedule::DownloadSchedule;
+use crate::scheduling::scheduler::schedule_group;
+use crate::state::queue_models::QueueEntryState;
+use crate::{SharedConfig, SharedQueue, SharedSubscriptions};
 use std::sync::LazyLock;
 use std::{
   collections::HashMap,
   sync::{Arc, Mutex},
 };
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Clone)]
@@ -20,7 +34,11 @@ pub enum DownloadRequest {
   Batch {
     group_id: String,
     items: Vec<DownloadItem>,
+    /// Holds the group until the schedule opens, `None` to start right away.
+    schedule: Option<DownloadSchedule>,
   },
+  /// Puts an already numbered entry back into the dispatcher, e.g. after its group was paused.
+  Requeue { entry: Box<DownloadEntry> },
 }
 
 #[derive(Clone)]
@@ -30,6 +48,12 @@ pub struct DownloadEntry {
   pub url: String,
   pub format: FormatOptions,
   pub template_context: TemplateContext,
+  /// The number of this attempt, starting at 1.
+  pub attempt: u32,
+  pub live: Option<LiveRecordingOptions>,
+  pub preset: Option<String>,
+  pub sections: Option<SectionOptions>,
+  pub metadata: Option<MetadataOptions>,
 }
 
 impl From<(DownloadItem, String)> for DownloadEntry {
@@ -40,6 +64,11 @@ impl From<(DownloadItem, String)> for DownloadEntry {
       url: item.0.url,
       format: item.0.format,
       template_context: item.0.template_context,
+      attempt: 1,
+      live: item.0.live,
+      preset: item.0.preset,
+      sections: item.0.sections,
+      metadata: item.0.metadata,
     }
   }
 }
@@ -51,6 +80,12 @@ impl DispatchEntry for DownloadEntry {
   fn group_key(&self) -> Option<&String> {
     self.template_context.values.get("playlist_id")
   }
+  fn is_numbered(&self) -> bool {
+    self.template_context.values.contains_key("autonumber")
+  }
+  fn is_paused(&self) -> bool {
+    is_item_paused(&self.id)
+  }
   fn set_numbering(&mut self, autonumber: u64, group_autonumber: Option<u64>) {
     self
       .template_context
@@ -65,49 +100,135 @@ impl DispatchEntry for DownloadEntry {
   }
 }
 
-static DOWNLOAD_COUNTERS: LazyLock<Mutex<HashMap<String, usize>>> =
+#[derive(Default)]
+struct GroupTally {
+  remaining: usize,
+  completed: usize,
+  skipped: usize,
+  failed: usize,
+  cancelled: usize,
+}
+
+static DOWNLOAD_COUNTERS: LazyLock<Mutex<HashMap<String, GroupTally>>> =
   LazyLock::new(|| Mutex::new(HashMap::new()));
 
-pub fn setup_download_dispatcher(
-  app: &AppHandle,
+pub fn setup_download_dispatcher<R: Runtime>(
+  app: &AppHandle<R>,
   sem: Arc<DynamicSemaphore>,
 ) -> GenericDispatcher<DownloadRequest> {
+  let queue_app = app.clone();
   GenericDispatcher::start(
     app.clone(),
     sem,
-    |req: DownloadRequest| match req {
-      DownloadRequest::Batch { group_id, items } => {
+    move |req: DownloadRequest| match req {
+      DownloadRequest::Batch {
+        group_id,
+        items,
+        schedule,
+      } => {
+        if let Some(queue) = queue_app.try_state::<SharedQueue>() {
+          queue.enqueue(&group_id, &items, schedule.as_ref());
+        }
+        if let Some(schedule) = schedule {
+          schedule_group(&queue_app, &group_id, schedule);
+        }
         let total = items.len();
-        DOWNLOAD_COUNTERS
-          .lock()
-          .unwrap()
-          .insert(group_id.clone(), total);
+        DOWNLOAD_COUNTERS.lock().unwrap().insert(
+          group_id.clone(),
+          GroupTally {
+            remaining: total,
+            ..GroupTally::default()
+          },
+        );
         items
           .into_iter()
           .map(|item| DownloadEntry::from((item, group_id.clone())))
           .collect()
       }
+      DownloadRequest::Requeue { entry } => vec![*entry],
     },
-    |tx, app: AppHandle, entry: DownloadEntry| async move {
+    |tx, app: AppHandle<R>, entry: DownloadEntry| async move {
       tracing::info!("starting download id={} url={}", entry.id, entry.url);
 
-      if let Err(e) = run_ytdlp_download(app.clone(), entry.clone()).await {
+      if let Some(queue) = app.try_state::<SharedQueue>() {
+        queue.mark_running(&entry.id, &entry.template_context);
+      }
+
+      let result = run_ytdlp_download(app.clone(), entry.clone()).await;
+
+      if let Ok(DownloadOutcome::Paused) = result {
+        if let Some(queue) = app.try_state::<SharedQueue>() {
+          queue.set_state(&entry.id, QueueEntryState::Paused);
+        }
+        let _ = app.emit(
+          "media_progress_stage",
+          MediaProgressStage {
+            id: entry.id.clone(),
+            group_id: entry.group_id.clone(),
+            stage: ProgressStage::Paused,
+          },
+        );
+        // The entry keeps its numbering, so it resumes into the same output template.
+        let _ = tx.send(DispatchRequest::Pipeline(DownloadRequest::Requeue {
+          entry: Box::new(entry.clone()),
+        }));
+        return;
+      }
+
+      if let Ok(DownloadOutcome::Retry { codes }) = &result {
+        schedule_retry(&app, &tx, &entry, codes.clone());
+        return;
+      }
+
+      // Finished, failed and cancelled entries are no longer resumable.
+      if let Some(queue) = app.try_state::<SharedQueue>() {
+        queue.remove(&entry.id);
+      }
+      forget_item(&entry.id);
+      if let Some(subscriptions) = app.try_state::<SharedSubscriptions>() {
+        // Cancelled entries count as taken, failed ones are picked up by the next check.
+        subscriptions.finish_download(
+          &entry.id,
+          matches!(
+            result,
+            Ok(DownloadOutcome::Completed | DownloadOutcome::Skipped | DownloadOutcome::Cancelled)
+          ),
+        );
+      }
+
+      if let Err(e) = &result {
         tracing::warn!(
           download_id = %entry.id,
           group_id = %entry.group_id,
           error = %e,
           "Failed to run ytdlp download",
         );
-        if should_report_to_sentry(&e) {
-          sentry::capture_error(&e);
+        if should_report_to_sentry(e) {
+          sentry::capture_error(e);
         }
       }
 
       let mut counters = DOWNLOAD_COUNTERS.lock().unwrap();
-      if let Some(cnt) = counters.get_mut(&entry.group_id) {
-        *cnt -= 1;
-        if *cnt == 0 {
-          counters.remove(&entry.group_id);
+      if let Some(tally) = counters.get_mut(&entry.group_id) {
+        tally.remaining -= 1;
+        match &result {
+          Ok(DownloadOutcome::Completed) => tally.completed += 1,
+          Ok(DownloadOutcome::Skipped) => tally.skipped += 1,
+          Ok(DownloadOutcome::Cancelled) => tally.cancelled += 1,
+          _ => tally.failed += 1,
+        }
+        if tally.remaining == 0 {
+          let tally = counters.remove(&entry.group_id).unwrap_or_default();
+          let payload = GroupCompletePayload {
+            group_id: entry.group_id.clone(),
+            completed: tally.completed,
+            skipped: tally.skipped,
+            failed: tally.failed,
+            cancelled: tally.cancelled,
+          };
+          fire_hooks(&app, HookPayload::group(&payload));
+          let _ = app.emit("group_complete", payload);
+          app.state::<ProxyPool>().forget_group(&entry.group_id);
           let _ = tx.send(DispatchRequest::Cleanup {
             group_id: entry.group_id.clone(),
           });
@@ -117,6 +238,90 @@ pub fn setup_download_dispatcher(
   )
 }
 
+fn schedule_retry<R: Runtime>(
+  app: &AppHandle<R>,
+  tx: &UnboundedSender<DispatchRequest<DownloadRequest>>,
+  entry: &DownloadEntry,
+  codes: Vec<String>,
+) {
+  let settings = app.state::<SharedConfig>().load().performance.retry.clone();
+  let delay = retry_delay(&settings, entry.attempt);
+
+  tracing::info!(
+    download_id = %entry.id,
+    group_id = %entry.group_id,
+    attempt = entry.attempt,
+    delay_ms = delay.as_millis() as u64,
+    "Retrying failed download"
+  );
+
+  if let Some(queue) = app.try_state::<SharedQueue>() {
+    queue.set_state(&entry.id, QueueEntryState::Queued);
+  }
+
+  let mut next = entry.clone();
+  next.attempt += 1;
+
+  let _ = app.emit(
+    "media_retry",
+    MediaRetryPayload {
+      group_id: next.group_id.clone(),
+      id: next.id.clone(),
+      attempt: next.attempt,
+      max_attempts: settings.max_attempts,
+      delay_ms: delay.as_millis() as u64,
+      codes,
+    },
+  );
+
+  // Wait outside of the dispatcher so the permit is free for other downloads meanwhile.
+  let tx = tx.clone();
+  tauri::async_runtime::spawn(async move {
+    tokio::time::sleep(delay).await;
+    let _ = tx.send(DispatchRequest::Pipeline(DownloadRequest::Requeue {
+      entry: Box::new(next),
+    }));
+  });
+}
+
+/// Re-submits every download that was still queued or running when the app last exited.
+pub fn restore_download_queue<R: Runtime>(app: &AppHandle<R>, sender: &DownloadSender) {
+  let Some(queue) = app.try_state::<SharedQueue>() else {
+    return;
+  };
+
+  for (group_id, entries) in queue.pending_groups() {
+    tracing::info!(
+      group_id = %group_id,
+      count = entries.len(),
+      "Restoring unfinished downloads"
+    );
+    ensure_group_running(&group_id);
+    if entries
+      .iter()
+      .all(|entry| entry.state == QueueEntryState::Paused)
+    {
+      pause_group(&group_id);
+    } else {
+      for entry in entries
+        .iter()
+        .filter(|entry| entry.state == QueueEntryState::Paused)
+      {
+        pause_item(&group_id, &entry.id);
+      }
+    }
+    let schedule = entries.iter().find_map(|entry| entry.schedule.clone());
+    let items = entries.iter().map(|entry| entry.to_item()).collect();
+    let _ = sender
+      .0
+      .send(DispatchRequest::Pipeline(DownloadRequest::Batch {
+        group_id,
+        items,
+        schedule,
+      }));
+  }
+}
+
 fn should_report_to_sentry(err: &YtdlpDownloadError) -> bool {
   matches!(
     err,
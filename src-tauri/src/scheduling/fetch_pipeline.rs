@@ -1,18 +1,24 @@
-use crate::models::download::FormatOptions;
-use crate::models::payloads::MediaAddWithFormatPayload;
-use crate::models::{MediaAddPayload, MediaFatalPayload};
+use crate::models::download::{FormatOptions, LiveRecordingOptions};
+use crate::models::payloads::{MediaAddWithFormatPayload, MediaFetchPayload};
+use crate::models::{DownloadItem, MediaAddPayload};
+use crate::runners::template_context::TemplateContext;
 use crate::runners::ytdlp_info::{run_ytdlp_info_fetch, YtdlpInfoFetchError};
+use crate::scheduling::download_pipeline::{DownloadRequest, DownloadSender};
+use crate::scheduling::group_state::ensure_group_running;
+use crate::scheduling::subscriptions::playlist_template_context;
+use crate::state::archive::{archive_key, make_archive_key, DownloadArchive};
 use crate::{
   models::{ParsedMedia, ParsedPlaylist},
   scheduling::concurrency::DynamicSemaphore,
   scheduling::dispatcher::{DispatchEntry, DispatchRequest, GenericDispatcher},
 };
+use crate::{SharedConfig, SharedPresets};
 use std::sync::LazyLock;
 use std::{
   collections::HashMap,
   sync::{Arc, Mutex},
 };
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
@@ -25,6 +31,8 @@ pub enum FetchRequest {
     group_id: String,
     id: String,
     url: String,
+    /// Downloads the result right away with this preset instead of adding it to the list.
+    download_preset: Option<String>,
   },
   Playlist {
     group_id: String,
@@ -50,6 +58,7 @@ pub struct FetchEntry {
   pub url: String,
   pub total: usize,
   pub format: Option<FormatOptions>,
+  pub download_preset: Option<String>,
 }
 
 impl DispatchEntry for FetchEntry {
@@ -65,15 +74,15 @@ impl DispatchEntry for FetchEntry {
 static GROUP_COUNTERS: LazyLock<Mutex<HashMap<String, usize>>> =
   LazyLock::new(|| Mutex::new(HashMap::new()));
 
-pub fn setup_fetch_dispatcher(
-  app: &AppHandle,
+pub fn setup_fetch_dispatcher<R: Runtime>(
+  app: &AppHandle<R>,
   sem: Arc<DynamicSemaphore>,
 ) -> GenericDispatcher<FetchRequest> {
   GenericDispatcher::start(
     app.clone(),
     sem,
     expand_fetch_request,
-    |tx: UnboundedSender<DispatchRequest<FetchRequest>>, app: AppHandle, entry: FetchEntry| async move {
+    |tx: UnboundedSender<DispatchRequest<FetchRequest>>, app: AppHandle<R>, entry: FetchEntry| async move {
       handle_fetch_entry(tx, app, entry).await;
     },
   )
@@ -81,13 +90,19 @@ pub fn setup_fetch_dispatcher(
 
 fn expand_fetch_request(req: FetchRequest) -> Vec<FetchEntry> {
   match req {
-    FetchRequest::Initial { group_id, id, url } => {
+    FetchRequest::Initial {
+      group_id,
+      id,
+      url,
+      download_preset,
+    } => {
       vec![FetchEntry {
         group_id,
         id,
         url,
         total: 1,
         format: None,
+        download_preset,
       }]
     }
     FetchRequest::Playlist { group_id, playlist } => {
@@ -101,6 +116,7 @@ fn expand_fetch_request(req: FetchRequest) -> Vec<FetchEntry> {
           url: e.video_url,
           total,
           format: None,
+          download_preset: None,
         })
         .collect()
     }
@@ -116,6 +132,7 @@ fn expand_fetch_request(req: FetchRequest) -> Vec<FetchEntry> {
         url,
         total: 1,
         format: Some(format),
+        download_preset: None,
       }]
     }
     FetchRequest::SizePlaylist {
@@ -133,15 +150,16 @@ fn expand_fetch_request(req: FetchRequest) -> Vec<FetchEntry> {
           url: e.video_url,
           total,
           format: Some(format.clone()),
+          download_preset: None,
         })
         .collect()
     }
   }
 }
 
-async fn handle_fetch_entry(
+async fn handle_fetch_entry<R: Runtime>(
   tx: UnboundedSender<DispatchRequest<FetchRequest>>,
-  app: AppHandle,
+  app: AppHandle<R>,
   entry: FetchEntry,
 ) {
   let FetchEntry {
@@ -150,6 +168,7 @@ async fn handle_fetch_entry(
     url,
     total,
     format,
+    download_preset,
   } = entry.clone();
 
   let result = run_ytdlp_info_fetch(&app, id.clone(), group_id.clone(), &url, format.clone()).await;
@@ -172,13 +191,23 @@ async fn handle_fetch_entry(
     }
   };
 
+  let result = result.map(|media| mark_archived(&app, media));
+
+  let result = match (result, download_preset) {
+    (Some(media), Some(preset)) => {
+      enqueue_download(&app, &group_id, media, &url, preset);
+      return;
+    }
+    (result, _) => result,
+  };
+
   match result {
     Some(ParsedMedia::Single(single)) => {
       if let Some(format) = format {
         let payload = MediaAddWithFormatPayload {
           group_id: group_id.clone(),
           total,
-          item: single,
+          item: *single,
           format,
         };
         let _ = app.emit("media_size", payload);
@@ -227,10 +256,13 @@ async fn handle_fetch_entry(
         let _ = app.emit("media_add", payload);
       }
     }
-    Some(ParsedMedia::Livestream(_)) => {
-      let payload =
-        MediaFatalPayload::internal(group_id.clone(), id, "Livestreams unsupported".into(), None);
-      let _ = app.emit("media_fatal", payload);
+    Some(ParsedMedia::Livestream(livestream)) => {
+      let payload = MediaAddPayload {
+        group_id: group_id.clone(),
+        total,
+        item: livestream,
+      };
+      let _ = app.emit("media_add", payload);
       let mut counters = GROUP_COUNTERS.lock().unwrap();
       if let Some(cnt) = counters.get_mut(&group_id) {
         *cnt -= 1;
@@ -248,6 +280,153 @@ async fn handle_fetch_entry(
   }
 }
 
+/// Fetches a URL in a group of its own, like adding it in the UI. With a preset, by id or name,
+/// the result is downloaded right away. Returns the group id.
+pub fn submit_url<R: Runtime>(
+  app: &AppHandle<R>,
+  url: String,
+  preset: Option<&str>,
+) -> Result<String, String> {
+  let download_preset = preset.and_then(|name| {
+    let preset = app.state::<SharedPresets>().find(name);
+    if preset.is_none() {
+      tracing::warn!("Preset {name} does not exist, adding {url} to the list");
+    }
+    preset.map(|p| p.id)
+  });
+
+  let group_id = Uuid::new_v4().to_string();
+  let id = Uuid::new_v4().to_string();
+  if download_preset.is_none() {
+    announce_fetch(app, &group_id, &id, &url);
+  }
+  ensure_group_running(&group_id);
+  app
+    .state::<FetchSender>()
+    .0
+    .send(DispatchRequest::Pipeline(FetchRequest::Initial {
+      group_id: group_id.clone(),
+      id,
+      url,
+      download_preset,
+    }))
+    .map_err(|_| "The fetch queue is not running".to_string())?;
+  Ok(group_id)
+}
+
+/// Lets the frontend create the group of a fetch it did not start itself. Without it the
+/// `media_add` event of the fetch would point at a group the UI does not know.
+pub fn announce_fetch<R: Runtime>(app: &AppHandle<R>, group_id: &str, id: &str, url: &str) {
+  let payload = MediaFetchPayload {
+    group_id: group_id.to_string(),
+    id: id.to_string(),
+    url: url.to_string(),
+  };
+  let _ = app.emit("media_fetch", payload);
+}
+
+/// Downloads fetched media with a preset, skipping the list the user picks formats from.
+fn enqueue_download<R: Runtime>(
+  app: &AppHandle<R>,
+  group_id: &str,
+  media: ParsedMedia,
+  url: &str,
+  preset: String,
+) {
+  let format = app
+    .state::<SharedPresets>()
+    .get(&preset)
+    .and_then(|preset| preset.format)
+    .unwrap_or_default();
+  let items = media_download_items(media, url, &format, Some(&preset));
+  tracing::info!(group_id = %group_id, count = items.len(), "Downloading fetched media");
+  let _ = app
+    .state::<DownloadSender>()
+    .0
+    .send(DispatchRequest::Pipeline(DownloadRequest::Batch {
+      group_id: group_id.to_string(),
+      items,
+      schedule: None,
+    }));
+}
+
+/// One download item per video of the fetched media, playlists numbered like the UI does.
+pub fn media_download_items(
+  media: ParsedMedia,
+  url: &str,
+  format: &FormatOptions,
+  preset: Option<&String>,
+) -> Vec<DownloadItem> {
+  let item = |url: String, template_context: TemplateContext, live| DownloadItem {
+    id: Uuid::new_v4().to_string(),
+    url,
+    format: format.clone(),
+    template_context,
+    live,
+    preset: preset.cloned(),
+    sections: None,
+    metadata: None,
+  };
+  let extractor_context = |extractor: Option<String>| TemplateContext {
+    values: extractor
+      .map(|extractor| HashMap::from([("extractor_key".to_string(), extractor)]))
+      .unwrap_or_default(),
+  };
+
+  match media {
+    ParsedMedia::Single(single) => vec![item(
+      single.url.unwrap_or_else(|| url.to_string()),
+      extractor_context(single.extractor),
+      None,
+    )],
+    ParsedMedia::Livestream(live) => vec![item(
+      live.url.unwrap_or_else(|| url.to_string()),
+      extractor_context(live.extractor),
+      Some(LiveRecordingOptions::default()),
+    )],
+    ParsedMedia::Playlist(playlist) => playlist
+      .entries
+      .iter()
+      .map(|entry| {
+        item(
+          entry.video_url.clone(),
+          playlist_template_context(&playlist, entry.index),
+          None,
+        )
+      })
+      .collect(),
+  }
+}
+
+/// Flags videos that are already in the download archive so the UI can point them out.
+fn mark_archived<R: Runtime>(app: &AppHandle<R>, mut media: ParsedMedia) -> ParsedMedia {
+  if !app.state::<SharedConfig>().load().output.download_archive {
+    return media;
+  }
+  let Some(archive) = app.try_state::<DownloadArchive>() else {
+    return media;
+  };
+  let keys = archive.keys();
+  if keys.is_empty() {
+    return media;
+  }
+
+  match &mut media {
+    ParsedMedia::Single(single) => {
+      if let (Some(extractor), Some(video_id)) = (&single.extractor, &single.video_id) {
+        single.archived = keys.contains(&make_archive_key(extractor, video_id));
+      }
+    }
+    ParsedMedia::Playlist(playlist) => {
+      for entry in playlist.entries.iter_mut() {
+        entry.archived = keys.contains(&archive_key(entry));
+      }
+    }
+    ParsedMedia::Livestream(_) => {}
+  }
+  media
+}
+
 fn should_report_to_sentry(err: &YtdlpInfoFetchError) -> bool {
   matches!(
     err,
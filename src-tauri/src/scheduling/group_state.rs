@@ -2,49 +2,165 @@ use std::collections::HashMap;
 use std::sync::{LazyLock, Mutex as StdMutex};
 use tokio::sync::watch;
 
-static RUNNING_GROUPS: LazyLock<StdMutex<HashMap<String, watch::Sender<bool>>>> =
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupState {
+  Running,
+  Paused,
+  Cancelled,
+}
+
+static RUNNING_GROUPS: LazyLock<StdMutex<HashMap<String, watch::Sender<GroupState>>>> =
   LazyLock::new(|| StdMutex::new(HashMap::new()));
 
-pub fn ensure_group_running(group_id: &str) {
+/// Items paused on their own while the rest of their group goes on, by item id.
+static ITEMS: LazyLock<StdMutex<HashMap<String, ItemState>>> =
+  LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+struct ItemState {
+  group_id: String,
+  paused: watch::Sender<bool>,
+}
+
+fn set_group_state(group_id: &str, state: GroupState) {
   let mut map = RUNNING_GROUPS.lock().unwrap();
   if let Some(tx) = map.get(group_id) {
-    let _ = tx.send(true);
+    let _ = tx.send(state);
   } else {
-    let (tx, _rx) = watch::channel(true);
+    let (tx, _rx) = watch::channel(state);
     map.insert(group_id.to_string(), tx);
   }
 }
 
+pub fn ensure_group_running(group_id: &str) {
+  set_group_state(group_id, GroupState::Running);
+}
+
 pub fn cancel_group(group_id: &str) {
-  let mut map = RUNNING_GROUPS.lock().unwrap();
-  if let Some(tx) = map.get(group_id) {
-    let _ = tx.send(false);
-  } else {
-    let (tx, _rx) = watch::channel(false);
-    map.insert(group_id.to_string(), tx);
+  set_group_state(group_id, GroupState::Cancelled);
+}
+
+/// Pauses a group. Returns `false` when the group is unknown or already cancelled.
+pub fn pause_group(group_id: &str) -> bool {
+  let map = RUNNING_GROUPS.lock().unwrap();
+  let Some(tx) = map.get(group_id) else {
+    return false;
+  };
+  if *tx.borrow() == GroupState::Cancelled {
+    return false;
+  }
+  let _ = tx.send(GroupState::Paused);
+  true
+}
+
+/// Resumes a paused group. Returns `false` when the group is unknown or cancelled.
+pub fn resume_group(group_id: &str) -> bool {
+  let map = RUNNING_GROUPS.lock().unwrap();
+  let Some(tx) = map.get(group_id) else {
+    return false;
+  };
+  if *tx.borrow() == GroupState::Cancelled {
+    return false;
   }
+  let _ = tx.send(GroupState::Running);
+  true
 }
 
 pub fn remove_group(group_id: &str) {
   RUNNING_GROUPS.lock().unwrap().remove(group_id);
+  ITEMS
+    .lock()
+    .unwrap()
+    .retain(|_, item| item.group_id != group_id);
 }
 
-pub fn subscribe_group(group_id: &str) -> watch::Receiver<bool> {
+pub fn subscribe_group(group_id: &str) -> watch::Receiver<GroupState> {
   let mut map = RUNNING_GROUPS.lock().unwrap();
   if let Some(tx) = map.get(group_id) {
     tx.subscribe()
   } else {
-    let (tx, rx) = watch::channel(true);
+    let (tx, rx) = watch::channel(GroupState::Running);
     map.insert(group_id.to_string(), tx);
     rx
   }
 }
 
-pub fn is_group_running(group_id: &str) -> bool {
+pub fn group_state(group_id: &str) -> Option<GroupState> {
   RUNNING_GROUPS
     .lock()
     .unwrap()
     .get(group_id)
     .map(|tx| *tx.borrow())
-    .unwrap_or(false)
+}
+
+pub fn is_group_running(group_id: &str) -> bool {
+  group_state(group_id) == Some(GroupState::Running)
+}
+
+pub fn is_group_paused(group_id: &str) -> bool {
+  group_state(group_id) == Some(GroupState::Paused)
+}
+
+/// A group is active while it is running or paused; its entries must be kept.
+pub fn is_group_active(group_id: &str) -> bool {
+  matches!(
+    group_state(group_id),
+    Some(GroupState::Running | GroupState::Paused)
+  )
+}
+
+/// Pauses a single item of a group. Returns `false` when the group is not active.
+pub fn pause_item(group_id: &str, id: &str) -> bool {
+  if !is_group_active(group_id) {
+    return false;
+  }
+  item_sender(group_id, id).send_replace(true);
+  true
+}
+
+/// Resumes an item paused on its own. Returns `false` when the item is not paused.
+pub fn resume_item(id: &str) -> bool {
+  let items = ITEMS.lock().unwrap();
+  let Some(item) = items.get(id) else {
+    return false;
+  };
+  item.paused.send_replace(false)
+}
+
+/// Resumes every item of the group that was paused on its own.
+pub fn resume_group_items(group_id: &str) {
+  for item in ITEMS.lock().unwrap().values() {
+    if item.group_id == group_id {
+      item.paused.send_replace(false);
+    }
+  }
+}
+
+pub fn subscribe_item(group_id: &str, id: &str) -> watch::Receiver<bool> {
+  item_sender(group_id, id).subscribe()
+}
+
+pub fn is_item_paused(id: &str) -> bool {
+  ITEMS
+    .lock()
+    .unwrap()
+    .get(id)
+    .is_some_and(|item| *item.paused.borrow())
+}
+
+/// Drops the state of an item that finished, failed or was cancelled.
+pub fn forget_item(id: &str) {
+  ITEMS.lock().unwrap().remove(id);
+}
+
+fn item_sender(group_id: &str, id: &str) -> watch::Sender<bool> {
+  ITEMS
+    .lock()
+    .unwrap()
+    .entry(id.to_string())
+    .or_insert_with(|| ItemState {
+      group_id: group_id.to_string(),
+      paused: watch::Sender::new(false),
+    })
+    .paused
+    .clone()
 }
@@ -0,0 +1,288 @@
+use crate::logging::LogStoreState;
+use crate::models::payloads::GroupCompletePayload;
+use crate::scheduling::download_pipeline::DownloadEntry;
+use crate::state::config_models::{Hook, HookAction, HookEvent};
+use crate::SharedConfig;
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+#[cfg(windows)]
+use windows_sys::Win32::System::Threading::CREATE_NO_WINDOW;
+
+/// Delay before the second attempt, doubled for every further one.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// What a hook is told about the event, as the webhook body and as `OVD_*` variables.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookPayload {
+  pub event: HookEvent,
+  pub group_id: String,
+  pub id: Option<String>,
+  pub url: Option<String>,
+  pub title: Option<String>,
+  /// Final file of the download.
+  pub path: Option<String>,
+  pub exit_code: Option<i32>,
+  pub error: Option<String>,
+  pub completed: Option<usize>,
+  pub skipped: Option<usize>,
+  pub failed: Option<usize>,
+  pub cancelled: Option<usize>,
+}
+
+impl HookPayload {
+  pub fn media(event: HookEvent, entry: &DownloadEntry) -> Self {
+    Self {
+      id: Some(entry.id.clone()),
+      url: Some(entry.url.clone()),
+      ..Self::empty(event, &entry.group_id)
+    }
+  }
+
+  pub fn group(payload: &GroupCompletePayload) -> Self {
+    Self {
+      completed: Some(payload.completed),
+      skipped: Some(payload.skipped),
+      failed: Some(payload.failed),
+      cancelled: Some(payload.cancelled),
+      ..Self::empty(HookEvent::GroupComplete, &payload.group_id)
+    }
+  }
+
+  fn empty(event: HookEvent, group_id: &str) -> Self {
+    Self {
+      event,
+      group_id: group_id.to_string(),
+      id: None,
+      url: None,
+      title: None,
+      path: None,
+      exit_code: None,
+      error: None,
+      completed: None,
+      skipped: None,
+      failed: None,
+      cancelled: None,
+    }
+  }
+
+  fn env_vars(&self) -> Vec<(&'static str, String)> {
+    let mut vars = vec![
+      ("OVD_EVENT", self.event.as_str().to_string()),
+      ("OVD_GROUP_ID", self.group_id.clone()),
+    ];
+    let optional = [
+      ("OVD_ID", self.id.clone()),
+      ("OVD_URL", self.url.clone()),
+      ("OVD_TITLE", self.title.clone()),
+      ("OVD_PATH", self.path.clone()),
+      ("OVD_EXIT_CODE", self.exit_code.map(|c| c.to_string())),
+      ("OVD_ERROR", self.error.clone()),
+      ("OVD_COMPLETED", self.completed.map(|c| c.to_string())),
+      ("OVD_SKIPPED", self.skipped.map(|c| c.to_string())),
+      ("OVD_FAILED", self.failed.map(|c| c.to_string())),
+      ("OVD_CANCELLED", self.cancelled.map(|c| c.to_string())),
+    ];
+    vars.extend(
+      optional
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value))),
+    );
+    vars
+  }
+}
+
+/// Runs every enabled hook for the event in the background, so a slow hook never holds up
+/// downloads.
+pub fn fire_hooks<R: Runtime>(app: &AppHandle<R>, payload: HookPayload) {
+  let hooks: Vec<Hook> = app
+    .state::<SharedConfig>()
+    .load()
+    .hooks
+    .iter()
+    .filter(|hook| hook.enabled && hook.events.contains(&payload.event))
+    .cloned()
+    .collect();
+
+  for hook in hooks {
+    let app = app.clone();
+    let payload = payload.clone();
+    tauri::async_runtime::spawn(async move {
+      let result = run_with_retries(&hook, &payload).await;
+      let name = if hook.name.is_empty() {
+        "unnamed"
+      } else {
+        &hook.name
+      };
+      let line = match &result {
+        Ok(attempts) => format!("[hook] {name} succeeded after {attempts} attempt(s)"),
+        Err(e) => format!("[hook] {name} failed: {e}"),
+      };
+      if let Err(e) = &result {
+        tracing::warn!(hook = %name, group_id = %payload.group_id, "Hook failed: {e}");
+      }
+      app
+        .state::<LogStoreState>()
+        .write()
+        .append_line(&app, &payload.group_id, &line);
+    });
+  }
+}
+
+/// Returns the number of attempts it took, or the last error.
+async fn run_with_retries(hook: &Hook, payload: &HookPayload) -> Result<u32, String> {
+  let timeout = Duration::from_secs(hook.timeout_secs.max(1));
+  let max_attempts = hook.max_attempts.max(1);
+  let mut attempt = 1;
+  loop {
+    let result = match tokio::time::timeout(timeout, run_once(&hook.action, payload, timeout)).await
+    {
+      Ok(result) => result,
+      Err(_) => Err(format!("timed out after {}s", timeout.as_secs())),
+    };
+    match result {
+      Ok(()) => return Ok(attempt),
+      Err(e) if attempt >= max_attempts => return Err(e),
+      Err(e) => {
+        tracing::debug!("Hook {} attempt {attempt} failed: {e}", hook.name);
+        tokio::time::sleep(retry_delay(attempt)).await;
+        attempt += 1;
+      }
+    }
+  }
+}
+
+/// Delay after the failed `attempt`, doubling up to `MAX_RETRY_DELAY`.
+fn retry_delay(attempt: u32) -> Duration {
+  let factor = 2u32
+    .checked_pow(attempt.saturating_sub(1))
+    .unwrap_or(u32::MAX);
+  RETRY_DELAY.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+async fn run_once(
+  action: &HookAction,
+  payload: &HookPayload,
+  timeout: Duration,
+) -> Result<(), String> {
+  match action {
+    HookAction::Command { program, args } => {
+      if program.trim().is_empty() {
+        return Err("no program set".into());
+      }
+      let mut command = tokio::process::Command::new(program);
+      command
+        .args(args)
+        .envs(payload.env_vars())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        // A timed out hook is killed when its future is dropped.
+        .kill_on_drop(true);
+      #[cfg(windows)]
+      command.creation_flags(CREATE_NO_WINDOW);
+
+      let status = command
+        .status()
+        .await
+        .map_err(|e| format!("failed to run {program}: {e}"))?;
+      if status.success() {
+        Ok(())
+      } else {
+        Err(format!("{program} exited with {status}"))
+      }
+    }
+    HookAction::Webhook { url, headers } => {
+      let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+      let mut request = client.post(url).json(payload);
+      for header in headers {
+        if let Some((name, value)) = header.split_once(':') {
+          request = request.header(name.trim(), value.trim());
+        }
+      }
+      let response = request.send().await.map_err(|e| e.to_string())?;
+      let status = response.status();
+      if status.is_success() {
+        Ok(())
+      } else {
+        Err(format!("{url} returned HTTP {status}"))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn env_vars_skip_missing_values() {
+    let payload = HookPayload {
+      id: Some("i".into()),
+      exit_code: Some(1),
+      ..HookPayload::empty(HookEvent::MediaFatal, "g")
+    };
+    assert_eq!(
+      payload.env_vars(),
+      vec![
+        ("OVD_EVENT", "media_fatal".to_string()),
+        ("OVD_GROUP_ID", "g".to_string()),
+        ("OVD_ID", "i".to_string()),
+        ("OVD_EXIT_CODE", "1".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn retry_delay_doubles_up_to_the_cap() {
+    assert_eq!(retry_delay(1), Duration::from_secs(2));
+    assert_eq!(retry_delay(3), Duration::from_secs(8));
+    assert_eq!(retry_delay(6), MAX_RETRY_DELAY);
+    assert_eq!(retry_delay(40), MAX_RETRY_DELAY);
+    assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY);
+  }
+
+  #[test]
+  fn hooks_with_out_of_range_settings_are_rejected() {
+    let hook = Hook::default();
+    assert!(hook.validate().is_ok());
+    for invalid in [
+      Hook {
+        max_attempts: 0,
+        ..Hook::default()
+      },
+      Hook {
+        max_attempts: 1000,
+        ..Hook::default()
+      },
+      Hook {
+        timeout_secs: 0,
+        ..Hook::default()
+      },
+    ] {
+      assert!(invalid.validate().is_err());
+    }
+  }
+
+  #[tokio::test]
+  async fn reports_the_error_of_the_last_attempt() {
+    let hook = Hook {
+      name: "missing".into(),
+      action: HookAction::Command {
+        program: String::new(),
+        args: vec![],
+      },
+      max_attempts: 1,
+      ..Hook::default()
+    };
+    let result = run_with_retries(&hook, &HookPayload::empty(HookEvent::MediaComplete, "g")).await;
+    assert_eq!(result, Err("no program set".to_string()));
+  }
+}
@@ -1,6 +1,15 @@
+pub mod bandwidth;
 pub mod concurrency;
 pub mod dispatcher;
 pub mod download_pipeline;
 pub mod fetch_pipeline;
 pub mod group_state;
+pub mod hooks;
 pub mod numbering;
+pub mod proxy_pool;
+pub mod recording;
+pub mod retry;
+pub mod schedule;
+pub mod scheduler;
+pub mod subscriptions;
+pub mod watch_folders;
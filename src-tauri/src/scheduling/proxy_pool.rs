@@ -0,0 +1,414 @@
+use crate::runners::site_rules::match_site_rule;
+use crate::state::config_models::{Config, ProxyPoolSettings, ProxyStrategy};
+use crate::SharedConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+const CHECK_TICK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default)]
+struct ProxyHealth {
+  /// `None` until the proxy was checked or used.
+  healthy: Option<bool>,
+  latency_ms: Option<u64>,
+  last_checked_at: Option<i64>,
+  last_error: Option<String>,
+  consecutive_failures: u32,
+}
+
+#[derive(Default)]
+struct PoolState {
+  health: HashMap<String, ProxyHealth>,
+  cursor: usize,
+  /// Proxy each group sticks to with `ProxyStrategy::Sticky`.
+  sticky: HashMap<String, String>,
+  last_check_at: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStatus {
+  /// The proxy URL without its `user:pass@` part.
+  pub proxy: String,
+  pub healthy: Option<bool>,
+  pub latency_ms: Option<u64>,
+  pub last_checked_at: Option<i64>,
+  pub last_error: Option<String>,
+  pub consecutive_failures: u32,
+}
+
+/// Hands out proxies from `NetworkSettings::proxy_pool` and tracks their health.
+///
+/// Proxies that failed a health check or a download are skipped until a later check succeeds.
+/// When every proxy is unhealthy the pool keeps handing them out rather than falling back to a
+/// direct connection.
+#[derive(Clone, Default)]
+pub struct ProxyPool {
+  state: Arc<Mutex<PoolState>>,
+}
+
+impl ProxyPool {
+  /// The proxy the download of `group_id` should use, `None` when the pool is off or empty.
+  pub fn select(&self, settings: &ProxyPoolSettings, group_id: &str) -> Option<String> {
+    if !settings.enabled || settings.proxies.is_empty() {
+      return None;
+    }
+
+    let mut state = self.state.lock().unwrap();
+    let usable = |state: &PoolState, proxy: &String| {
+      state
+        .health
+        .get(proxy)
+        .is_none_or(|health| health.healthy != Some(false))
+    };
+    let mut candidates: Vec<&String> = settings
+      .proxies
+      .iter()
+      .filter(|proxy| usable(&state, proxy))
+      .collect();
+    if candidates.is_empty() {
+      candidates = settings.proxies.iter().collect();
+    }
+
+    match settings.strategy {
+      ProxyStrategy::Failover => candidates.first().map(|proxy| (*proxy).clone()),
+      ProxyStrategy::RoundRobin => {
+        let proxy = candidates[state.cursor % candidates.len()].clone();
+        state.cursor = state.cursor.wrapping_add(1);
+        Some(proxy)
+      }
+      ProxyStrategy::Sticky => {
+        if let Some(current) = state.sticky.get(group_id) {
+          if candidates.contains(&current) {
+            return Some(current.clone());
+          }
+        }
+        let proxy = candidates[state.cursor % candidates.len()].clone();
+        state.cursor = state.cursor.wrapping_add(1);
+        state.sticky.insert(group_id.to_string(), proxy.clone());
+        Some(proxy)
+      }
+    }
+  }
+
+  /// Like `select`, but leaves URLs whose site rule sets its own proxy alone.
+  pub fn select_for_url(&self, cfg: &Config, url: &str, group_id: &str) -> Option<String> {
+    if match_site_rule(&cfg.site_rules, url).is_some_and(|(_, rule)| rule.proxy.is_some()) {
+      return None;
+    }
+    self.select(&cfg.network.proxy_pool, group_id)
+  }
+
+  pub fn forget_group(&self, group_id: &str) {
+    self.state.lock().unwrap().sticky.remove(group_id);
+  }
+
+  /// Marks the proxy unhealthy so the next downloads switch to another one.
+  pub fn report_failure(&self, proxy: &str, reason: &str) {
+    let mut state = self.state.lock().unwrap();
+    let health = state.health.entry(proxy.to_string()).or_default();
+    health.healthy = Some(false);
+    health.last_error = Some(reason.to_string());
+    health.consecutive_failures += 1;
+    state.sticky.retain(|_, sticky| sticky != proxy);
+  }
+
+  pub fn report_success(&self, proxy: &str) {
+    let mut state = self.state.lock().unwrap();
+    let health = state.health.entry(proxy.to_string()).or_default();
+    health.healthy = Some(true);
+    health.consecutive_failures = 0;
+  }
+
+  /// Whether another proxy than `proxy` is still considered healthy.
+  pub fn has_alternative(&self, settings: &ProxyPoolSettings, proxy: &str) -> bool {
+    let state = self.state.lock().unwrap();
+    settings.proxies.iter().any(|candidate| {
+      candidate != proxy
+        && state
+          .health
+          .get(candidate)
+          .is_none_or(|health| health.healthy != Some(false))
+    })
+  }
+
+  pub fn statuses(&self, settings: &ProxyPoolSettings) -> Vec<ProxyStatus> {
+    let state = self.state.lock().unwrap();
+    settings
+      .proxies
+      .iter()
+      .map(|proxy| {
+        let health = state.health.get(proxy).cloned().unwrap_or_default();
+        ProxyStatus {
+          proxy: redact_proxy(proxy),
+          healthy: health.healthy,
+          latency_ms: health.latency_ms,
+          last_checked_at: health.last_checked_at,
+          last_error: health
+            .last_error
+            .map(|e| e.replace(proxy, &redact_proxy(proxy))),
+          consecutive_failures: health.consecutive_failures,
+        }
+      })
+      .collect()
+  }
+
+  /// Checks every proxy of the pool concurrently and records the results.
+  pub async fn check_all(&self, settings: &ProxyPoolSettings) -> Vec<ProxyStatus> {
+    self.state.lock().unwrap().last_check_at = Some(Instant::now());
+    let timeout = Duration::from_secs(settings.health_check_timeout_secs.max(1));
+    let checks = settings.proxies.iter().map(|proxy| async move {
+      let result = check_proxy(proxy, &settings.health_check_url, timeout).await;
+      (proxy, result)
+    });
+
+    for (proxy, result) in futures::future::join_all(checks).await {
+      self.record_check(proxy, result);
+    }
+    self.statuses(settings)
+  }
+
+  fn record_check(&self, proxy: &str, result: Result<u64, String>) {
+    let mut state = self.state.lock().unwrap();
+    let health = state.health.entry(proxy.to_string()).or_default();
+    health.last_checked_at = Some(chrono::Utc::now().timestamp_millis());
+    match result {
+      Ok(latency_ms) => {
+        health.healthy = Some(true);
+        health.latency_ms = Some(latency_ms);
+        health.last_error = None;
+        health.consecutive_failures = 0;
+      }
+      Err(e) => {
+        let redacted = redact_proxy(proxy);
+        tracing::warn!(
+          "Proxy health check failed for {redacted}: {}",
+          e.replace(proxy, &redacted)
+        );
+        health.healthy = Some(false);
+        health.latency_ms = None;
+        health.last_error = Some(e);
+        health.consecutive_failures += 1;
+      }
+    }
+  }
+
+  fn check_due(&self, interval: Duration) -> bool {
+    self
+      .state
+      .lock()
+      .unwrap()
+      .last_check_at
+      .is_none_or(|at| at.elapsed() >= interval)
+  }
+}
+
+/// The proxy URL without the credentials in its `user:pass@` part, for logs and the UI.
+pub fn redact_proxy(proxy: &str) -> String {
+  let (scheme, rest) = match proxy.split_once("://") {
+    Some((scheme, rest)) => (Some(scheme), rest),
+    None => (None, proxy),
+  };
+  let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+  let Some(at) = rest[..authority_end].rfind('@') else {
+    return proxy.to_string();
+  };
+  let host = &rest[at + 1..];
+  match scheme {
+    Some(scheme) => format!("{scheme}://{host}"),
+    None => host.to_string(),
+  }
+}
+
+/// Fetches `url` through the proxy and returns the round trip time in milliseconds.
+pub async fn check_proxy(proxy: &str, url: &str, timeout: Duration) -> Result<u64, String> {
+  let client = reqwest::Client::builder()
+    .proxy(reqwest::Proxy::all(proxy).map_err(|e| format!("invalid proxy: {e}"))?)
+    .timeout(timeout)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+  let started = Instant::now();
+  let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+  let status = response.status();
+  if status.is_client_error() || status.is_server_error() {
+    return Err(format!("health check returned HTTP {status}"));
+  }
+  Ok(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Periodically checks the proxy pool and emits `proxy_health` with the results.
+pub fn start_proxy_health_checks<R: Runtime>(app: &AppHandle<R>) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    loop {
+      let settings = app
+        .state::<SharedConfig>()
+        .load()
+        .network
+        .proxy_pool
+        .clone();
+      let pool = app.state::<ProxyPool>().inner().clone();
+      let interval = Duration::from_secs(settings.health_check_interval_secs);
+
+      if settings.enabled
+        && !settings.proxies.is_empty()
+        && !interval.is_zero()
+        && pool.check_due(interval)
+      {
+        let statuses = pool.check_all(&settings).await;
+        let _ = app.emit("proxy_health", &statuses);
+      }
+
+      tokio::time::sleep(CHECK_TICK).await;
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  fn settings(strategy: ProxyStrategy) -> ProxyPoolSettings {
+    ProxyPoolSettings {
+      enabled: true,
+      proxies: vec![
+        "http://a:1".into(),
+        "http://b:1".into(),
+        "http://c:1".into(),
+      ],
+      strategy,
+      ..ProxyPoolSettings::default()
+    }
+  }
+
+  /// Minimal HTTP proxy answering every request with the given status line.
+  async fn stand_in_proxy(status: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+      while let Ok((mut socket, _)) = listener.accept().await {
+        tokio::spawn(async move {
+          let mut buf = [0u8; 4096];
+          let _ = socket.read(&mut buf).await;
+          let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+          let _ = socket.write_all(response.as_bytes()).await;
+        });
+      }
+    });
+    format!("http://{addr}")
+  }
+
+  #[test]
+  fn round_robin_skips_failed_proxies() {
+    let pool = ProxyPool::default();
+    let settings = settings(ProxyStrategy::RoundRobin);
+
+    let picks: Vec<_> = (0..3)
+      .map(|_| pool.select(&settings, "g").unwrap())
+      .collect();
+    assert_eq!(picks, vec!["http://a:1", "http://b:1", "http://c:1"]);
+
+    pool.report_failure("http://b:1", "geoBlocked");
+    let picks: Vec<_> = (0..2)
+      .map(|_| pool.select(&settings, "g").unwrap())
+      .collect();
+    assert!(!picks.contains(&"http://b:1".to_string()));
+  }
+
+  #[test]
+  fn sticky_keeps_group_on_one_proxy_until_it_fails() {
+    let pool = ProxyPool::default();
+    let settings = settings(ProxyStrategy::Sticky);
+
+    let first = pool.select(&settings, "g1").unwrap();
+    assert_eq!(pool.select(&settings, "g1").unwrap(), first);
+    assert_ne!(pool.select(&settings, "g2").unwrap(), first);
+
+    pool.report_failure(&first, "accessForbidden403");
+    assert_ne!(pool.select(&settings, "g1").unwrap(), first);
+  }
+
+  #[test]
+  fn failover_uses_first_healthy_and_falls_back_to_all() {
+    let pool = ProxyPool::default();
+    let settings = settings(ProxyStrategy::Failover);
+
+    assert_eq!(pool.select(&settings, "g").unwrap(), "http://a:1");
+    pool.report_failure("http://a:1", "unableToConnectToProxy");
+    assert_eq!(pool.select(&settings, "g").unwrap(), "http://b:1");
+    assert!(pool.has_alternative(&settings, "http://b:1"));
+
+    pool.report_failure("http://b:1", "unableToConnectToProxy");
+    pool.report_failure("http://c:1", "unableToConnectToProxy");
+    assert!(!pool.has_alternative(&settings, "http://c:1"));
+    assert_eq!(pool.select(&settings, "g").unwrap(), "http://a:1");
+  }
+
+  #[test]
+  fn credentials_are_stripped_from_statuses() {
+    assert_eq!(
+      redact_proxy("socks5://user:p@ss@127.0.0.1:1080/"),
+      "socks5://127.0.0.1:1080/"
+    );
+    assert_eq!(redact_proxy("user:pass@proxy:8080"), "proxy:8080");
+    assert_eq!(
+      redact_proxy("http://proxy:8080/a@b"),
+      "http://proxy:8080/a@b"
+    );
+
+    let pool = ProxyPool::default();
+    let proxy = "http://user:secret@a:1";
+    let settings = ProxyPoolSettings {
+      enabled: true,
+      proxies: vec![proxy.into()],
+      ..ProxyPoolSettings::default()
+    };
+    pool.record_check(proxy, Err(format!("invalid proxy: {proxy}")));
+
+    let status = &pool.statuses(&settings)[0];
+    assert_eq!(status.proxy, "http://a:1");
+    assert_eq!(
+      status.last_error.as_deref(),
+      Some("invalid proxy: http://a:1")
+    );
+  }
+
+  #[test]
+  fn disabled_pool_selects_nothing() {
+    let pool = ProxyPool::default();
+    let mut settings = settings(ProxyStrategy::RoundRobin);
+    settings.enabled = false;
+    assert_eq!(pool.select(&settings, "g"), None);
+  }
+
+  #[tokio::test]
+  async fn health_check_against_stand_in_proxies() {
+    let healthy = stand_in_proxy("204 No Content").await;
+    let blocked = stand_in_proxy("403 Forbidden").await;
+    let dead = {
+      let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+      format!("http://{}", listener.local_addr().unwrap())
+    };
+
+    let pool = ProxyPool::default();
+    let settings = ProxyPoolSettings {
+      enabled: true,
+      proxies: vec![healthy.clone(), blocked, dead],
+      health_check_url: "http://health.invalid/generate_204".into(),
+      health_check_timeout_secs: 5,
+      ..ProxyPoolSettings::default()
+    };
+
+    let statuses = pool.check_all(&settings).await;
+    let healthy_flags: Vec<_> = statuses.iter().map(|s| s.healthy).collect();
+    assert_eq!(healthy_flags, vec![Some(true), Some(false), Some(false)]);
+    assert!(statuses[1].last_error.as_deref().unwrap().contains("403"));
+    assert_eq!(pool.select(&settings, "g"), Some(healthy));
+  }
+}
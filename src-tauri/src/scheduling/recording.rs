@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tokio::sync::watch;
+
+struct Registration {
+  /// Tells a registration apart from later ones for the same download, e.g. after a retry.
+  generation: u64,
+  stop_tx: watch::Sender<bool>,
+}
+
+static RECORDINGS: LazyLock<Mutex<HashMap<String, Registration>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Registration of a running livestream recording; unregisters itself when dropped.
+pub struct RecordingGuard {
+  id: String,
+  generation: u64,
+  stop_rx: watch::Receiver<bool>,
+  timer: Option<JoinHandle<()>>,
+}
+
+impl RecordingGuard {
+  pub fn register(id: &str, max_duration_secs: Option<u64>) -> Self {
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let (stop_tx, stop_rx) = watch::channel(false);
+    RECORDINGS.lock().unwrap().insert(
+      id.to_string(),
+      Registration {
+        generation,
+        stop_tx,
+      },
+    );
+
+    let timer = max_duration_secs.map(|secs| {
+      let id = id.to_string();
+      tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(secs)).await;
+        if stop_generation(&id, Some(generation)) {
+          tracing::info!(download_id = %id, "Stopping recording after {secs}s");
+        }
+      })
+    });
+
+    Self {
+      id: id.to_string(),
+      generation,
+      stop_rx,
+      timer,
+    }
+  }
+
+  /// Resolves once a stop was requested for this recording.
+  pub async fn stop_requested(&mut self) {
+    if self.stop_rx.wait_for(|stop| *stop).await.is_err() {
+      std::future::pending::<()>().await;
+    }
+  }
+}
+
+impl Drop for RecordingGuard {
+  fn drop(&mut self) {
+    if let Some(timer) = self.timer.take() {
+      timer.abort();
+    }
+    let mut recordings = RECORDINGS.lock().unwrap();
+    if recordings
+      .get(&self.id)
+      .is_some_and(|registration| registration.generation == self.generation)
+    {
+      recordings.remove(&self.id);
+    }
+  }
+}
+
+/// Asks a running recording to stop. Returns `false` when no such recording is running.
+pub fn stop_recording(id: &str) -> bool {
+  stop_generation(id, None)
+}
+
+/// Stops the recording, but only if it is still the given registration when one is passed.
+fn stop_generation(id: &str, generation: Option<u64>) -> bool {
+  let recordings = RECORDINGS.lock().unwrap();
+  let Some(registration) = recordings.get(id) else {
+    return false;
+  };
+  if generation.is_some_and(|generation| generation != registration.generation) {
+    return false;
+  }
+  registration.stop_tx.send_replace(true);
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dropping_an_old_guard_keeps_the_newer_registration() {
+    let first = RecordingGuard::register("retried", None);
+    let second = RecordingGuard::register("retried", None);
+
+    drop(first);
+    assert!(stop_recording("retried"));
+    assert!(*second.stop_rx.borrow());
+
+    drop(second);
+    assert!(!stop_recording("retried"));
+  }
+
+  #[tokio::test]
+  async fn timer_stops_the_recording() {
+    let mut guard = RecordingGuard::register("timed", Some(0));
+    let stopped = tokio::time::timeout(Duration::from_secs(5), guard.stop_requested()).await;
+    assert!(stopped.is_ok());
+  }
+
+  #[tokio::test]
+  async fn timer_of_a_dropped_guard_leaves_later_recordings_running() {
+    drop(RecordingGuard::register("restarted", Some(0)));
+    let second = RecordingGuard::register("restarted", None);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!*second.stop_rx.borrow());
+  }
+}
@@ -0,0 +1,122 @@
+use crate::state::config_models::RetrySettings;
+use rand::RngExt;
+use std::time::Duration;
+
+/// Decides whether a failed download is attempted again.
+///
+/// `attempt` is the number of the attempt that just failed (starting at 1) and `codes` are the
+/// diagnostic codes of all errors it reported. A download is only retried when every code is
+/// retryable, so a `membersOnly` error is never retried even if a 403 was reported as well.
+pub fn should_retry(settings: &RetrySettings, attempt: u32, codes: &[String]) -> bool {
+  if !settings.enabled || attempt >= settings.max_attempts {
+    return false;
+  }
+
+  if codes.is_empty() {
+    return settings.retry_unknown;
+  }
+
+  codes.iter().all(|code| {
+    settings.retry_codes.iter().any(|c| c == code) || (settings.retry_unknown && code == "unknown")
+  })
+}
+
+/// Exponential backoff for the given failed attempt, without jitter.
+pub fn backoff_delay(settings: &RetrySettings, attempt: u32) -> Duration {
+  let exponent = attempt.saturating_sub(1).min(16);
+  let delay = settings
+    .initial_delay_ms
+    .saturating_mul(1u64 << exponent)
+    .min(settings.max_delay_ms);
+  Duration::from_millis(delay)
+}
+
+pub fn retry_delay(settings: &RetrySettings, attempt: u32) -> Duration {
+  let delay = backoff_delay(settings, attempt);
+  let jitter = settings.jitter.clamp(0.0, 1.0);
+  if jitter == 0.0 {
+    return delay;
+  }
+
+  let factor = 1.0 + rand::rng().random_range(-jitter..=jitter);
+  delay.mul_f64(factor)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn settings() -> RetrySettings {
+    RetrySettings {
+      enabled: true,
+      max_attempts: 3,
+      initial_delay_ms: 1_000,
+      max_delay_ms: 10_000,
+      jitter: 0.0,
+      retry_codes: vec!["accessForbidden403".into(), "server5xx".into()],
+      retry_unknown: false,
+    }
+  }
+
+  #[test]
+  fn retries_retryable_codes_until_max_attempts() {
+    let settings = settings();
+    let codes = vec!["accessForbidden403".to_string()];
+
+    assert!(should_retry(&settings, 1, &codes));
+    assert!(should_retry(&settings, 2, &codes));
+    assert!(!should_retry(&settings, 3, &codes));
+  }
+
+  #[test]
+  fn never_retries_when_any_code_is_not_retryable() {
+    let settings = settings();
+    let codes = vec!["accessForbidden403".to_string(), "membersOnly".to_string()];
+
+    assert!(!should_retry(&settings, 1, &codes));
+    assert!(!should_retry(&settings, 1, &["geoBlocked".to_string()]));
+  }
+
+  #[test]
+  fn unknown_failures_follow_retry_unknown() {
+    let mut settings = settings();
+    assert!(!should_retry(&settings, 1, &[]));
+    assert!(!should_retry(&settings, 1, &["unknown".to_string()]));
+
+    settings.retry_unknown = true;
+    assert!(should_retry(&settings, 1, &[]));
+    assert!(should_retry(&settings, 1, &["unknown".to_string()]));
+  }
+
+  #[test]
+  fn disabled_policy_never_retries() {
+    let settings = RetrySettings {
+      enabled: false,
+      ..settings()
+    };
+    assert!(!should_retry(&settings, 1, &["server5xx".to_string()]));
+  }
+
+  #[test]
+  fn backoff_doubles_and_is_capped() {
+    let settings = settings();
+    assert_eq!(backoff_delay(&settings, 1), Duration::from_millis(1_000));
+    assert_eq!(backoff_delay(&settings, 2), Duration::from_millis(2_000));
+    assert_eq!(backoff_delay(&settings, 3), Duration::from_millis(4_000));
+    assert_eq!(backoff_delay(&settings, 5), Duration::from_millis(10_000));
+    assert_eq!(backoff_delay(&settings, 60), Duration::from_millis(10_000));
+  }
+
+  #[test]
+  fn jitter_stays_within_bounds() {
+    let settings = RetrySettings {
+      jitter: 0.5,
+      ..settings()
+    };
+    for _ in 0..100 {
+      let delay = retry_delay(&settings, 1);
+      assert!(delay >= Duration::from_millis(500));
+      assert!(delay <= Duration::from_millis(1_500));
+    }
+  }
+}
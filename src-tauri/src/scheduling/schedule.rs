@@ -0,0 +1,189 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// When the downloads of a group are allowed to run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+  tag = "kind",
+  rename_all = "camelCase",
+  rename_all_fields = "camelCase"
+)]
+pub enum DownloadSchedule {
+  /// Holds the group until the given Unix time in milliseconds, then runs it normally.
+  StartAt { start_at: i64 },
+  /// Runs the group only while the local time of day is within `[start, end)`, formatted as
+  /// `HH:MM`. The window wraps past midnight when `end` is not after `start`.
+  Window { start: String, end: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleStatus {
+  pub open: bool,
+  /// Unix time in milliseconds at which `open` changes next, `None` when it never does.
+  pub next_change_at: Option<i64>,
+}
+
+impl DownloadSchedule {
+  pub fn validate(&self) -> Result<(), String> {
+    match self {
+      Self::StartAt { start_at } => DateTime::from_timestamp_millis(*start_at)
+        .map(|_| ())
+        .ok_or_else(|| format!("Invalid start time: {start_at}")),
+      Self::Window { start, end } => {
+        let (Some(start_time), Some(end_time)) = (parse_time_of_day(start), parse_time_of_day(end))
+        else {
+          return Err(format!("Invalid schedule window: {start}-{end}"));
+        };
+        if start_time == end_time {
+          return Err(format!("Schedule window {start}-{end} is empty"));
+        }
+        Ok(())
+      }
+    }
+  }
+
+  /// Evaluates the schedule at `now`; times of day are read in the time zone of `now`.
+  pub fn status_at<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> ScheduleStatus {
+    match self {
+      Self::StartAt { start_at } => {
+        let open = now.timestamp_millis() >= *start_at;
+        ScheduleStatus {
+          open,
+          next_change_at: (!open).then_some(*start_at),
+        }
+      }
+      Self::Window { start, end } => {
+        let (Some(start), Some(end)) = (parse_time_of_day(start), parse_time_of_day(end)) else {
+          // Invalid windows are rejected when scheduling; never hold a group forever on one.
+          return ScheduleStatus {
+            open: true,
+            next_change_at: None,
+          };
+        };
+        let open = is_within_window(now.time(), start, end);
+        let boundary = if open { end } else { start };
+        ScheduleStatus {
+          open,
+          next_change_at: next_time_of_day(now, boundary).map(|at| at.timestamp_millis()),
+        }
+      }
+    }
+  }
+}
+
+/// Returns the first instant after `now` at which the local time of day is `time`.
+fn next_time_of_day<Tz: TimeZone>(now: &DateTime<Tz>, time: NaiveTime) -> Option<DateTime<Tz>> {
+  let timezone = now.timezone();
+  let today = now.date_naive();
+  (0..=2).find_map(|days| {
+    let candidate = (today + Duration::days(days)).and_time(time);
+    timezone
+      .from_local_datetime(&candidate)
+      .earliest()
+      .filter(|at| at > now)
+  })
+}
+
+pub fn parse_time_of_day(value: &str) -> Option<NaiveTime> {
+  NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Whether `now` falls in `[start, end)`, wrapping past midnight when `end <= start`.
+pub fn is_within_window(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+  if start < end {
+    now >= start && now < end
+  } else {
+    now >= start || now < end
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+
+  fn at(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+      .expect("valid timestamp")
+      .with_timezone(&Utc)
+  }
+
+  fn night_window() -> DownloadSchedule {
+    DownloadSchedule::Window {
+      start: "01:00".into(),
+      end: "06:00".into(),
+    }
+  }
+
+  #[test]
+  fn start_at_holds_until_the_given_time() {
+    let start = at("2024-05-01T12:00:00Z");
+    let schedule = DownloadSchedule::StartAt {
+      start_at: start.timestamp_millis(),
+    };
+
+    let before = schedule.status_at(&at("2024-05-01T11:59:00Z"));
+    assert!(!before.open);
+    assert_eq!(before.next_change_at, Some(start.timestamp_millis()));
+
+    let after = schedule.status_at(&at("2024-05-01T12:00:00Z"));
+    assert!(after.open);
+    assert_eq!(after.next_change_at, None);
+  }
+
+  #[test]
+  fn window_opens_at_start_of_next_day() {
+    let status = night_window().status_at(&at("2024-05-01T12:00:00Z"));
+    assert!(!status.open);
+    assert_eq!(
+      status.next_change_at,
+      Some(at("2024-05-02T01:00:00Z").timestamp_millis())
+    );
+  }
+
+  #[test]
+  fn window_closes_at_end() {
+    let status = night_window().status_at(&at("2024-05-01T02:30:00Z"));
+    assert!(status.open);
+    assert_eq!(
+      status.next_change_at,
+      Some(at("2024-05-01T06:00:00Z").timestamp_millis())
+    );
+  }
+
+  #[test]
+  fn window_wraps_past_midnight() {
+    let schedule = DownloadSchedule::Window {
+      start: "22:00".into(),
+      end: "06:00".into(),
+    };
+    assert!(schedule.status_at(&at("2024-05-01T23:00:00Z")).open);
+    assert!(schedule.status_at(&at("2024-05-01T05:59:00Z")).open);
+    assert!(!schedule.status_at(&at("2024-05-01T06:00:00Z")).open);
+  }
+
+  #[test]
+  fn validate_rejects_invalid_windows() {
+    assert!(night_window().validate().is_ok());
+    let invalid = DownloadSchedule::Window {
+      start: "25:00".into(),
+      end: "06:00".into(),
+    };
+    assert!(invalid.validate().is_err());
+    let empty = DownloadSchedule::Window {
+      start: "06:00".into(),
+      end: "06:00".into(),
+    };
+    assert!(empty.validate().is_err());
+  }
+
+  #[test]
+  fn schedule_serializes_with_kind_tag() {
+    let schedule = DownloadSchedule::StartAt { start_at: 42 };
+    let json = serde_json::to_value(&schedule).unwrap();
+    assert_eq!(
+      json,
+      serde_json::json!({ "kind": "startAt", "startAt": 42 })
+    );
+  }
+}
@@ -0,0 +1,109 @@
+use crate::scheduling::dispatcher::DispatchRequest;
+use crate::scheduling::download_pipeline::DownloadSender;
+use crate::scheduling::group_state::{is_group_active, pause_group, resume_group};
+use crate::scheduling::schedule::{DownloadSchedule, ScheduleStatus};
+use crate::state::queue_models::QueueEntryState;
+use crate::SharedQueue;
+use chrono::Local;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Upper bound between two checks, so clock changes and sleep/wake are picked up.
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+static SCHEDULED_GROUPS: LazyLock<Mutex<HashMap<String, DownloadSchedule>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Holds a group until its schedule opens and pauses it again whenever a window closes.
+///
+/// Must be called before the group's entries reach the dispatcher, so a closed schedule parks
+/// them instead of starting them.
+pub fn schedule_group<R: Runtime>(app: &AppHandle<R>, group_id: &str, schedule: DownloadSchedule) {
+  let status = schedule.status_at(&Local::now());
+  let already_watched = SCHEDULED_GROUPS
+    .lock()
+    .unwrap()
+    .insert(group_id.to_string(), schedule)
+    .is_some();
+
+  let held = !status.open && pause_group(group_id);
+  if held {
+    tracing::info!(group_id = %group_id, "Holding group until its schedule opens");
+  }
+
+  if !already_watched {
+    let app = app.clone();
+    let group_id = group_id.to_string();
+    tauri::async_runtime::spawn(watch_schedule(app, group_id, held));
+  }
+}
+
+pub fn group_schedule(group_id: &str) -> Option<DownloadSchedule> {
+  SCHEDULED_GROUPS.lock().unwrap().get(group_id).cloned()
+}
+
+/// Returns every active group that still has a schedule, with its current status.
+pub fn scheduled_groups() -> Vec<(String, DownloadSchedule, ScheduleStatus)> {
+  let now = Local::now();
+  SCHEDULED_GROUPS
+    .lock()
+    .unwrap()
+    .iter()
+    .filter(|(group_id, _)| is_group_active(group_id))
+    .map(|(group_id, schedule)| (group_id.clone(), schedule.clone(), schedule.status_at(&now)))
+    .collect()
+}
+
+fn unschedule_group(group_id: &str) {
+  SCHEDULED_GROUPS.lock().unwrap().remove(group_id);
+}
+
+async fn watch_schedule<R: Runtime>(app: AppHandle<R>, group_id: String, mut held: bool) {
+  while let Some(schedule) = group_schedule(&group_id) {
+    if !is_group_active(&group_id) {
+      unschedule_group(&group_id);
+      break;
+    }
+
+    let now = Local::now();
+    let status = schedule.status_at(&now);
+
+    if status.open && held {
+      tracing::info!(group_id = %group_id, "Schedule opened, starting group");
+      resume_scheduled_group(&app, &group_id);
+      held = false;
+    } else if !status.open && !held {
+      tracing::info!(group_id = %group_id, "Schedule closed, pausing group");
+      held = pause_group(&group_id);
+    }
+
+    // A start time only applies once; afterwards the group is an ordinary group.
+    if status.open && matches!(schedule, DownloadSchedule::StartAt { .. }) {
+      unschedule_group(&group_id);
+      break;
+    }
+
+    let wait = status
+      .next_change_at
+      .map(|at| Duration::from_millis((at - now.timestamp_millis()).max(0) as u64))
+      .unwrap_or(MAX_CHECK_INTERVAL)
+      .min(MAX_CHECK_INTERVAL);
+    tokio::time::sleep(wait).await;
+  }
+}
+
+fn resume_scheduled_group<R: Runtime>(app: &AppHandle<R>, group_id: &str) {
+  if !resume_group(group_id) {
+    return;
+  }
+  if let Some(queue) = app.try_state::<SharedQueue>() {
+    queue.set_group_state(group_id, QueueEntryState::Queued);
+  }
+  if let Some(sender) = app.try_state::<DownloadSender>() {
+    let _ = sender.0.send(DispatchRequest::Resume {
+      group_id: group_id.to_string(),
+    });
+  }
+}
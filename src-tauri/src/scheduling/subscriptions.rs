@@ -0,0 +1,302 @@
+use crate::models::{DownloadItem, ParsedMedia, ParsedPlaylist, PlaylistEntry};
+use crate::runners::template_context::TemplateContext;
+use crate::runners::ytdlp_info::run_ytdlp_info_fetch;
+use crate::scheduling::dispatcher::DispatchRequest;
+use crate::scheduling::download_pipeline::{DownloadRequest, DownloadSender};
+use crate::scheduling::group_state::ensure_group_running;
+use crate::state::archive::{archive_key, DownloadArchive};
+use crate::state::subscriptions_models::SubscriptionCheckResult;
+use crate::{SharedConfig, SharedSubscriptions};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+const CHECK_TICK: Duration = Duration::from_secs(60);
+
+/// Subscriptions with a check in flight, so "check now" and the timer never overlap.
+static CHECKING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Periodically checks every subscription whose interval has elapsed.
+pub fn start_subscription_watcher(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_TICK).await;
+
+      let settings = app.state::<SharedConfig>().load().subscriptions.clone();
+      if !settings.enabled || (!settings.check_in_background && is_in_background(&app)) {
+        continue;
+      }
+
+      let interval_ms = u128::from(settings.check_interval_minutes.max(1)) * 60_000;
+      let now = now_millis();
+      let due: Vec<String> = app
+        .state::<SharedSubscriptions>()
+        .list()
+        .into_iter()
+        .filter(|subscription| {
+          subscription
+            .last_checked_at
+            .is_none_or(|checked_at| now.saturating_sub(checked_at) >= interval_ms)
+        })
+        .map(|subscription| subscription.id)
+        .collect();
+
+      for id in due {
+        if let Err(e) = check_subscription(&app, &id).await {
+          tracing::warn!(subscription_id = %id, error = %e, "Subscription check failed");
+        }
+      }
+    }
+  });
+}
+
+/// Fetches the subscription's flat playlist and enqueues the entries that are not archived yet.
+pub async fn check_subscription(
+  app: &AppHandle,
+  id: &str,
+) -> Result<SubscriptionCheckResult, String> {
+  if !CHECKING.lock().unwrap().insert(id.to_string()) {
+    return Err(format!("Subscription {id} is already being checked"));
+  }
+  let result = run_check(app, id).await;
+  CHECKING.lock().unwrap().remove(id);
+
+  let subscriptions = app.state::<SharedSubscriptions>();
+  match &result {
+    Ok(result) => {
+      let _ = app.emit("subscription_checked", result);
+    }
+    Err(e) => subscriptions.record_error(id, e.clone(), now_millis()),
+  }
+  result
+}
+
+async fn run_check(app: &AppHandle, id: &str) -> Result<SubscriptionCheckResult, String> {
+  let subscriptions = app.state::<SharedSubscriptions>();
+  let subscription = subscriptions
+    .get(id)
+    .ok_or_else(|| format!("Subscription {id} not found"))?;
+
+  let fetch_id = format!("subscription-{id}");
+  let media = run_ytdlp_info_fetch(
+    app,
+    fetch_id.clone(),
+    fetch_id,
+    &subscription.url,
+    Some(subscription.format.clone()),
+  )
+  .await
+  .map_err(|e| e.to_string())?;
+
+  let Some(ParsedMedia::Playlist(playlist)) = media else {
+    return Err(format!("{} is not a channel or playlist", subscription.url));
+  };
+
+  let keys: Vec<String> = playlist.entries.iter().map(archive_key).collect();
+  let unseen: HashSet<String> = subscriptions.unseen(id, &keys).into_iter().collect();
+  // The first check only records what already exists, unless asked otherwise.
+  let baseline_only = subscription.last_checked_at.is_none() && !subscription.download_existing;
+  let archived = if baseline_only {
+    HashSet::new()
+  } else {
+    archived_keys(app)
+  };
+  let plan = plan_check(&playlist.entries, &unseen, &archived, baseline_only);
+
+  let mut group_id = None;
+  if !plan.download.is_empty() {
+    let gid = uuid::Uuid::new_v4().to_string();
+    let items: Vec<DownloadItem> = plan
+      .download
+      .iter()
+      .map(|entry| DownloadItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: entry.video_url.clone(),
+        format: subscription.format.clone(),
+        template_context: playlist_template_context(&playlist, entry.index),
+        live: None,
+        preset: None,
+        sections: None,
+        metadata: None,
+      })
+      .collect();
+    // The keys are archived once the downloads finish, so failed ones are retried next check.
+    let tracked: Vec<(String, String)> = items
+      .iter()
+      .zip(&plan.download)
+      .map(|(item, entry)| (item.id.clone(), archive_key(entry)))
+      .collect();
+    subscriptions.track_downloads(id, &tracked);
+
+    let sender = app.state::<DownloadSender>();
+    ensure_group_running(&gid);
+    sender
+      .0
+      .send(DispatchRequest::Pipeline(DownloadRequest::Batch {
+        group_id: gid.clone(),
+        items,
+        schedule: None,
+      }))
+      .map_err(|e| e.to_string())?;
+
+    tracing::info!(
+      subscription_id = %id,
+      group_id = %gid,
+      count = plan.download.len(),
+      "Enqueued new subscription items"
+    );
+    group_id = Some(gid);
+  }
+
+  subscriptions.record_check(id, &plan.seen, now_millis());
+
+  Ok(SubscriptionCheckResult {
+    subscription_id: id.to_string(),
+    group_id,
+    new_count: plan.download.len(),
+  })
+}
+
+/// What a check does with the entries of a subscription's playlist.
+#[derive(Debug)]
+struct CheckPlan<'a> {
+  /// Entries to enqueue.
+  download: Vec<&'a PlaylistEntry>,
+  /// Keys to archive right away, without downloading their entry.
+  seen: Vec<String>,
+}
+
+fn plan_check<'a>(
+  entries: &'a [PlaylistEntry],
+  unseen: &HashSet<String>,
+  archived: &HashSet<String>,
+  baseline_only: bool,
+) -> CheckPlan<'a> {
+  let mut plan = CheckPlan {
+    download: Vec::new(),
+    seen: Vec::new(),
+  };
+  for entry in entries {
+    let key = archive_key(entry);
+    if !unseen.contains(&key) {
+      continue;
+    }
+    if baseline_only || archived.contains(&key) {
+      plan.seen.push(key);
+    } else {
+      plan.download.push(entry);
+    }
+  }
+  plan
+}
+
+pub fn playlist_template_context(playlist: &ParsedPlaylist, index: usize) -> TemplateContext {
+  let mut values = HashMap::new();
+  values.insert("playlist_index".to_string(), (index + 1).to_string());
+  let optional = [
+    ("playlist_id", &playlist.playlist_id),
+    ("playlist_title", &playlist.title),
+    ("playlist", &playlist.title),
+    ("playlist_uploader", &playlist.uploader),
+    ("playlist_uploader_id", &playlist.uploader_id),
+  ];
+  for (key, value) in optional {
+    if let Some(value) = value {
+      values.insert(key.to_string(), value.clone());
+    }
+  }
+  if let Some(count) = playlist.playlist_count {
+    values.insert("playlist_count".to_string(), count.to_string());
+  }
+  values.insert("n_entries".to_string(), playlist.entries.len().to_string());
+  TemplateContext { values }
+}
+
+/// Videos downloaded outside of the subscription are skipped as well.
+fn archived_keys(app: &AppHandle) -> HashSet<String> {
+  if !app.state::<SharedConfig>().load().output.download_archive {
+    return HashSet::new();
+  }
+  app
+    .try_state::<DownloadArchive>()
+    .map(|archive| archive.keys())
+    .unwrap_or_default()
+}
+
+fn is_in_background(app: &AppHandle) -> bool {
+  app
+    .get_webview_window("main")
+    .is_none_or(|window| !window.is_visible().unwrap_or(false))
+}
+
+fn now_millis() -> u128 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::archive::make_archive_key;
+
+  fn entry(index: usize, id: &str) -> PlaylistEntry {
+    PlaylistEntry {
+      video_url: format!("https://example.com/watch?v={id}"),
+      index,
+      id: Some(id.to_string()),
+      extractor: Some("Example".to_string()),
+      title: None,
+      archived: false,
+    }
+  }
+
+  fn set(keys: &[&str]) -> HashSet<String> {
+    keys
+      .iter()
+      .map(|id| make_archive_key("example", id))
+      .collect()
+  }
+
+  fn downloaded(plan: &CheckPlan) -> Vec<String> {
+    plan
+      .download
+      .iter()
+      .filter_map(|entry| entry.id.clone())
+      .collect()
+  }
+
+  #[test]
+  fn first_check_only_records_existing_entries() {
+    let entries = vec![entry(0, "a"), entry(1, "b")];
+    let plan = plan_check(&entries, &set(&["a", "b"]), &HashSet::new(), true);
+    assert!(plan.download.is_empty());
+    assert_eq!(
+      plan.seen,
+      vec![
+        make_archive_key("example", "a"),
+        make_archive_key("example", "b")
+      ]
+    );
+  }
+
+  #[test]
+  fn first_check_with_download_existing_enqueues_everything() {
+    let entries = vec![entry(0, "a"), entry(1, "b")];
+    let plan = plan_check(&entries, &set(&["a", "b"]), &HashSet::new(), false);
+    assert_eq!(downloaded(&plan), vec!["a", "b"]);
+    // Enqueued entries are only archived once their download finished.
+    assert!(plan.seen.is_empty());
+  }
+
+  #[test]
+  fn later_checks_skip_seen_and_archived_entries() {
+    let entries = vec![entry(0, "a"), entry(1, "b"), entry(2, "c")];
+    let plan = plan_check(&entries, &set(&["b", "c"]), &set(&["c"]), false);
+    assert_eq!(downloaded(&plan), vec!["b"]);
+    assert_eq!(plan.seen, vec![make_archive_key("example", "c")]);
+  }
+}
@@ -0,0 +1,242 @@
+use crate::parsers::url_list::parse_url_file;
+use crate::scheduling::fetch_pipeline::submit_url;
+use crate::state::config_models::WatchFolder;
+use crate::SharedConfig;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+const DONE_DIR: &str = "done";
+const FAILED_DIR: &str = "failed";
+const LINK_EXTENSIONS: [&str; 5] = ["txt", "url", "webloc", "html", "htm"];
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Files changed more recently than this may still be being written and wait for the next scan.
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+
+/// Imported files that could not be moved away, so they are not imported again on every scan.
+static STUCK: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Periodically imports the link files dropped into the watched folders.
+pub fn start_folder_watcher(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    loop {
+      // Waiting first also gives the frontend time to listen for links added without a preset.
+      let interval_secs = app
+        .state::<SharedConfig>()
+        .load()
+        .watch_folders
+        .poll_interval_secs;
+      tokio::time::sleep(Duration::from_secs(interval_secs).max(MIN_POLL_INTERVAL)).await;
+
+      let settings = app.state::<SharedConfig>().load().watch_folders.clone();
+      if settings.enabled {
+        let scan_app = app.clone();
+        let _ = tauri::async_runtime::spawn_blocking(move || {
+          for folder in settings.folders.iter().filter(|f| f.enabled) {
+            scan_folder(&scan_app, folder);
+          }
+        })
+        .await;
+      }
+    }
+  });
+}
+
+/// Web URLs in a link file, an error when it has none.
+pub fn read_url_file(path: &Path) -> Result<Vec<String>, String> {
+  let text =
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+  let urls = parse_url_file(path, &text);
+  if urls.is_empty() {
+    return Err(format!("No URLs found in {}", path.display()));
+  }
+  Ok(urls)
+}
+
+fn scan_folder(app: &AppHandle, folder: &WatchFolder) {
+  if folder.path.trim().is_empty() {
+    return;
+  }
+  let dir = Path::new(&folder.path);
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(e) => {
+      tracing::warn!("Failed to read watch folder {}: {e}", dir.display());
+      return;
+    }
+  };
+
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if !is_link_file(&path) || !is_settled(&entry) || STUCK.lock().unwrap().contains(&path) {
+      continue;
+    }
+
+    let target = import_link_file(&path, |url| submit_url(app, url, folder.preset.as_deref()));
+    if let Err(e) = move_into(&dir.join(target), &path) {
+      tracing::error!("Failed to move {} to {target}: {e}", path.display());
+      STUCK.lock().unwrap().insert(path);
+    }
+  }
+}
+
+/// Submits the URLs of a link file and returns the folder it belongs in afterwards. A file only
+/// goes to `done` once every URL in it was handed to the fetch queue.
+fn import_link_file(
+  path: &Path,
+  mut submit: impl FnMut(String) -> Result<String, String>,
+) -> &'static str {
+  let urls = match read_url_file(path) {
+    Ok(urls) => urls,
+    Err(e) => {
+      tracing::warn!("Watch folder import failed: {e}");
+      return FAILED_DIR;
+    }
+  };
+
+  tracing::info!(count = urls.len(), "Importing URLs from {}", path.display());
+  let mut failed = false;
+  for url in urls {
+    if let Err(e) = submit(url) {
+      tracing::error!("Watch folder import failed: {e}");
+      failed = true;
+    }
+  }
+  if failed {
+    FAILED_DIR
+  } else {
+    DONE_DIR
+  }
+}
+
+fn is_link_file(path: &Path) -> bool {
+  let hidden = path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .is_none_or(|name| name.starts_with('.'));
+  let extension = path
+    .extension()
+    .and_then(|e| e.to_str())
+    .map(str::to_ascii_lowercase);
+  !hidden && path.is_file() && extension.is_some_and(|e| LINK_EXTENSIONS.contains(&e.as_str()))
+}
+
+fn is_settled(entry: &fs::DirEntry) -> bool {
+  entry
+    .metadata()
+    .and_then(|metadata| metadata.modified())
+    .ok()
+    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+    .is_some_and(|age| age >= SETTLE_TIME)
+}
+
+/// Moves the file into `dir`, numbering it when a file of the same name was imported before.
+fn move_into(dir: &Path, path: &Path) -> std::io::Result<()> {
+  fs::create_dir_all(dir)?;
+  let name = path.file_name().unwrap_or_default();
+  let mut target = dir.join(name);
+  let mut n = 1;
+  while target.exists() {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    target = match path.extension() {
+      Some(ext) => dir.join(format!("{stem} ({n}).{}", ext.to_string_lossy())),
+      None => dir.join(format!("{stem} ({n})")),
+    };
+    n += 1;
+  }
+  fs::rename(path, target)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs::File;
+
+  fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ovd-watch-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    path
+  }
+
+  #[test]
+  fn move_into_numbers_names_that_were_imported_before() {
+    let dir = temp_dir();
+    let done = dir.join(DONE_DIR);
+    for _ in 0..3 {
+      move_into(&done, &write(&dir, "links.txt", "x")).unwrap();
+    }
+    move_into(&done, &write(&dir, "noext", "x")).unwrap();
+    move_into(&done, &write(&dir, "noext", "x")).unwrap();
+
+    for name in [
+      "links.txt",
+      "links (1).txt",
+      "links (2).txt",
+      "noext",
+      "noext (1)",
+    ] {
+      assert!(done.join(name).is_file(), "{name} is missing");
+    }
+    assert!(!dir.join("links.txt").exists());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn files_only_settle_after_they_stop_changing() {
+    let dir = temp_dir();
+    let path = write(&dir, "links.txt", "https://example.com/a");
+    let entry = || fs::read_dir(&dir).unwrap().flatten().next().unwrap();
+    assert!(!is_settled(&entry()));
+
+    let past = SystemTime::now() - SETTLE_TIME * 2;
+    File::options()
+      .write(true)
+      .open(&path)
+      .unwrap()
+      .set_modified(past)
+      .unwrap();
+    assert!(is_settled(&entry()));
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn imports_are_routed_to_done_or_failed() {
+    let dir = temp_dir();
+    let links = write(
+      &dir,
+      "links.txt",
+      "https://example.com/a\nhttps://example.com/b\n",
+    );
+    let empty = write(&dir, "empty.txt", "nothing to see here");
+
+    let mut submitted = Vec::new();
+    let target = import_link_file(&links, |url| {
+      submitted.push(url);
+      Ok("group".into())
+    });
+    assert_eq!(target, DONE_DIR);
+    assert_eq!(
+      submitted,
+      ["https://example.com/a", "https://example.com/b"]
+    );
+
+    let target = import_link_file(&links, |_| Err("The fetch queue is not running".into()));
+    assert_eq!(target, FAILED_DIR);
+
+    let target = import_link_file(&empty, |_| panic!("nothing to submit"));
+    assert_eq!(target, FAILED_DIR);
+    move_into(&dir.join(target), &empty).unwrap();
+    assert!(dir.join(FAILED_DIR).join("empty.txt").is_file());
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,236 @@
+use crate::models::PlaylistEntry;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const ARCHIVE_FILE: &str = "download-archive.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+  /// The full archive line, `<extractor> <id>`.
+  pub key: String,
+  pub extractor: String,
+  pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivePage {
+  pub entries: Vec<ArchiveEntry>,
+  /// Number of entries matching the query, across all pages.
+  pub total: usize,
+}
+
+/// App-managed yt-dlp `--download-archive` file.
+///
+/// yt-dlp appends to the file itself while downloading; the app only reads it and rewrites it
+/// for imports and removals.
+pub struct DownloadArchive {
+  path: PathBuf,
+  write_lock: Mutex<()>,
+}
+
+impl DownloadArchive {
+  pub fn new(app_dir: &Path) -> Self {
+    Self {
+      path: app_dir.join(ARCHIVE_FILE),
+      write_lock: Mutex::new(()),
+    }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn entries(&self) -> Vec<ArchiveEntry> {
+    match fs::read_to_string(&self.path) {
+      Ok(text) => parse_archive(&text),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+      Err(e) => {
+        tracing::warn!("Failed to read download archive: {e}");
+        Vec::new()
+      }
+    }
+  }
+
+  pub fn keys(&self) -> HashSet<String> {
+    self.entries().into_iter().map(|entry| entry.key).collect()
+  }
+
+  /// Returns the newest entries first, filtered by a case-insensitive query.
+  pub fn search(&self, query: Option<&str>, offset: usize, limit: usize) -> ArchivePage {
+    let matching = filter_entries(self.entries(), query);
+    let total = matching.len();
+    let entries = matching
+      .into_iter()
+      .rev()
+      .skip(offset)
+      .take(limit)
+      .collect();
+    ArchivePage { entries, total }
+  }
+
+  pub fn remove(&self, key: &str) -> io::Result<bool> {
+    let _guard = self.write_lock.lock().unwrap();
+    let entries = self.entries();
+    let before = entries.len();
+    let remaining: Vec<ArchiveEntry> = entries.into_iter().filter(|e| e.key != key).collect();
+    if remaining.len() == before {
+      return Ok(false);
+    }
+    self.write_entries(&remaining)?;
+    Ok(true)
+  }
+
+  /// Merges the entries of another archive file and returns how many were new.
+  pub fn import(&self, source: &Path) -> io::Result<usize> {
+    let imported = parse_archive(&fs::read_to_string(source)?);
+    let _guard = self.write_lock.lock().unwrap();
+    let mut entries = self.entries();
+    let added = merge_entries(&mut entries, imported);
+    if added > 0 {
+      self.write_entries(&entries)?;
+    }
+    Ok(added)
+  }
+
+  pub fn export(&self, destination: &Path) -> io::Result<usize> {
+    let entries = self.entries();
+    fs::write(destination, format_archive(&entries))?;
+    Ok(entries.len())
+  }
+
+  fn write_entries(&self, entries: &[ArchiveEntry]) -> io::Result<()> {
+    let tmp_path = self.path.with_extension("txt.tmp");
+    {
+      let mut file = fs::File::create(&tmp_path)?;
+      file.write_all(format_archive(entries).as_bytes())?;
+      file.sync_all()?;
+    }
+    fs::rename(tmp_path, &self.path)
+  }
+}
+
+/// Builds the archive key yt-dlp records for a video.
+pub fn make_archive_key(extractor: &str, id: &str) -> String {
+  format!("{} {}", extractor.to_lowercase(), id)
+}
+
+/// Identifies a playlist entry by its archive key, falling back to its URL when the extractor
+/// did not report an id.
+pub fn archive_key(entry: &PlaylistEntry) -> String {
+  match (&entry.extractor, &entry.id) {
+    (Some(extractor), Some(id)) => make_archive_key(extractor, id),
+    _ => entry.video_url.clone(),
+  }
+}
+
+pub fn parse_archive(text: &str) -> Vec<ArchiveEntry> {
+  let mut seen = HashSet::new();
+  text
+    .lines()
+    .filter_map(parse_archive_line)
+    .filter(|entry| seen.insert(entry.key.clone()))
+    .collect()
+}
+
+fn parse_archive_line(line: &str) -> Option<ArchiveEntry> {
+  let (extractor, id) = line.trim().split_once(' ')?;
+  let id = id.trim();
+  if extractor.is_empty() || id.is_empty() {
+    return None;
+  }
+  Some(ArchiveEntry {
+    key: make_archive_key(extractor, id),
+    extractor: extractor.to_lowercase(),
+    id: id.to_string(),
+  })
+}
+
+fn format_archive(entries: &[ArchiveEntry]) -> String {
+  entries
+    .iter()
+    .map(|entry| format!("{}\n", entry.key))
+    .collect()
+}
+
+fn filter_entries(entries: Vec<ArchiveEntry>, query: Option<&str>) -> Vec<ArchiveEntry> {
+  let Some(query) = query.map(str::trim).filter(|q| !q.is_empty()) else {
+    return entries;
+  };
+  let query = query.to_lowercase();
+  entries
+    .into_iter()
+    .filter(|entry| entry.key.to_lowercase().contains(&query))
+    .collect()
+}
+
+fn merge_entries(entries: &mut Vec<ArchiveEntry>, imported: Vec<ArchiveEntry>) -> usize {
+  let mut keys: HashSet<String> = entries.iter().map(|entry| entry.key.clone()).collect();
+  let before = entries.len();
+  entries.extend(
+    imported
+      .into_iter()
+      .filter(|entry| keys.insert(entry.key.clone())),
+  );
+  entries.len() - before
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_archive_lines_and_skips_garbage() {
+    let entries = parse_archive("youtube abc123\n\n  Vimeo 42  \nbroken\nyoutube abc123\n");
+    assert_eq!(
+      entries,
+      vec![
+        ArchiveEntry {
+          key: "youtube abc123".into(),
+          extractor: "youtube".into(),
+          id: "abc123".into(),
+        },
+        ArchiveEntry {
+          key: "vimeo 42".into(),
+          extractor: "vimeo".into(),
+          id: "42".into(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn filters_case_insensitively() {
+    let entries = parse_archive("youtube AbC\nvimeo 42\n");
+    let matching = filter_entries(entries.clone(), Some("abc"));
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].id, "AbC");
+    assert_eq!(filter_entries(entries, Some("  ")).len(), 2);
+  }
+
+  #[test]
+  fn merge_only_adds_new_entries() {
+    let mut entries = parse_archive("youtube a\n");
+    let added = merge_entries(&mut entries, parse_archive("youtube a\nyoutube b\n"));
+    assert_eq!(added, 1);
+    assert_eq!(format_archive(&entries), "youtube a\nyoutube b\n");
+  }
+
+  #[test]
+  fn playlist_entry_key_matches_ytdlp() {
+    let entry = PlaylistEntry {
+      video_url: "https://example.com/watch?v=a".into(),
+      index: 0,
+      id: Some("a".into()),
+      extractor: Some("Youtube".into()),
+      title: None,
+      archived: false,
+    };
+    assert_eq!(archive_key(&entry), "youtube a");
+  }
+}
@@ -1,11 +1,13 @@
 use crate::commands::{register_shortcuts, unregister_shortcuts};
 use crate::i18n::I18nManager;
-use crate::state::config_models::Config;
+use crate::remote::RemoteApiServer;
+use crate::scheduling::bandwidth::{effective_rate_limit, BandwidthManager};
+use crate::state::config_models::{Config, Hook};
 use crate::state::json_handle::JsonStoreHandle;
 use crate::state::json_state::JsonBackedState;
 use crate::tray::{create_tray, destroy_tray};
 use crate::{DownloadLimiter, FetchLimiter};
-use tauri::{AppHandle, Manager, Wry};
+use tauri::{AppHandle, Manager, Runtime, Wry};
 use tauri_plugin_autostart::ManagerExt;
 
 impl JsonBackedState for Config {
@@ -16,7 +18,12 @@ impl JsonBackedState for Config {
     Config::default()
   }
 
-  fn before_initialized(app: &AppHandle<Wry>, value: &mut Self) {
+  fn validate(&self) -> Result<(), String> {
+    self.output.video.validate()?;
+    self.hooks.iter().try_for_each(Hook::validate)
+  }
+
+  fn before_initialized<R: Runtime>(app: &AppHandle<R>, value: &mut Self) {
     if value.network.enable_proxy.is_none() {
       value.network.enable_proxy =
         Some(value.network.proxy.as_ref().is_some_and(|v| !v.is_empty()));
@@ -45,6 +52,12 @@ impl JsonBackedState for Config {
         limiter.resize(max).await;
       });
     }
+    if let Some(bandwidth) = app.try_state::<BandwidthManager>() {
+      bandwidth.set_limit(effective_rate_limit(
+        &new_value.network,
+        chrono::Local::now().time(),
+      ));
+    }
 
     if new_value.input.global_shortcuts {
       register_shortcuts(app);
@@ -74,6 +87,10 @@ impl JsonBackedState for Config {
       }
     }
 
+    if let Some(server) = app.try_state::<RemoteApiServer>() {
+      server.apply(&new_value.remote_api);
+    }
+
     if new_value.system.auto_start_enabled {
       let _ = app.autolaunch().enable();
     } else {
@@ -0,0 +1,410 @@
+use crate::state::history_models::{
+  HistoryEntry, HistoryPage, HistoryQuery, HistoryStatus, NewHistoryEntry,
+};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use std::path::Path;
+use std::sync::Mutex;
+
+pub const HISTORY_FILE: &str = "history.sqlite3";
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1_000;
+
+const SCHEMA: &str = "
+  CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id TEXT NOT NULL,
+    group_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT,
+    uploader TEXT,
+    extractor TEXT,
+    video_id TEXT,
+    file_path TEXT,
+    format TEXT NOT NULL,
+    template_context TEXT NOT NULL,
+    filesize INTEGER,
+    duration REAL,
+    status TEXT NOT NULL,
+    failure_code TEXT,
+    started_at INTEGER NOT NULL,
+    finished_at INTEGER NOT NULL
+  );
+  CREATE INDEX IF NOT EXISTS history_finished_at ON history (finished_at DESC);
+  CREATE INDEX IF NOT EXISTS history_status ON history (status, finished_at DESC);
+";
+
+/// Changes to the table after its first version, applied in order and tracked by
+/// `PRAGMA user_version`.
+const MIGRATIONS: [&str; 1] = ["
+  ALTER TABLE history ADD COLUMN preset TEXT;
+  ALTER TABLE history ADD COLUMN sections TEXT;
+  ALTER TABLE history ADD COLUMN metadata_options TEXT;
+"];
+
+const COLUMNS: &str = "id, item_id, group_id, url, title, uploader, extractor, video_id, \
+  file_path, format, template_context, filesize, duration, status, failure_code, started_at, \
+  finished_at, preset, sections, metadata_options";
+
+/// SQLite backed history of finished and failed downloads. Queries are paginated in SQL, so
+/// only the requested page is ever loaded.
+pub struct HistoryHandle {
+  conn: Mutex<Connection>,
+}
+
+impl HistoryHandle {
+  pub fn open(path: &Path) -> rusqlite::Result<Self> {
+    Self::with_connection(Connection::open(path)?)
+  }
+
+  fn with_connection(conn: Connection) -> rusqlite::Result<Self> {
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.execute_batch(SCHEMA)?;
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+      conn.execute_batch(migration)?;
+      conn.pragma_update(None, "user_version", index + 1)?;
+    }
+    Ok(Self {
+      conn: Mutex::new(conn),
+    })
+  }
+
+  pub fn record(&self, entry: &NewHistoryEntry) -> rusqlite::Result<i64> {
+    let format = serde_json::to_string(&entry.format).unwrap_or_else(|_| "{}".into());
+    let template_context =
+      serde_json::to_string(&entry.template_context).unwrap_or_else(|_| "{\"values\":{}}".into());
+    let sections = entry
+      .sections
+      .as_ref()
+      .and_then(|sections| serde_json::to_string(sections).ok());
+    let metadata_options = entry
+      .metadata_options
+      .as_ref()
+      .and_then(|options| serde_json::to_string(options).ok());
+    let metadata = &entry.metadata;
+    let filesize = metadata
+      .filesize
+      .or(metadata.filesize_approx.map(|size| size as u64));
+
+    let conn = self.conn.lock().unwrap();
+    conn.execute(
+      "INSERT INTO history (item_id, group_id, url, title, uploader, extractor, video_id, \
+       file_path, format, template_context, filesize, duration, status, failure_code, \
+       started_at, finished_at, preset, sections, metadata_options) \
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, \
+       ?19)",
+      params![
+        entry.item_id,
+        entry.group_id,
+        entry.url,
+        metadata.title,
+        metadata.uploader,
+        metadata.extractor_key,
+        metadata.id,
+        entry.file_path,
+        format,
+        template_context,
+        filesize.map(|size| size as i64),
+        metadata.duration,
+        entry.status.as_str(),
+        entry.failure_code,
+        entry.started_at,
+        entry.finished_at,
+        entry.preset,
+        sections,
+        metadata_options,
+      ],
+    )?;
+    Ok(conn.last_insert_rowid())
+  }
+
+  pub fn get(&self, id: i64) -> rusqlite::Result<Option<HistoryEntry>> {
+    let conn = self.conn.lock().unwrap();
+    conn
+      .query_row(
+        &format!("SELECT {COLUMNS} FROM history WHERE id = ?1"),
+        [id],
+        read_entry,
+      )
+      .optional()
+  }
+
+  pub fn query(&self, query: &HistoryQuery) -> rusqlite::Result<HistoryPage> {
+    let (filter, mut values) = build_filter(query);
+    let conn = self.conn.lock().unwrap();
+
+    let total: i64 = conn.query_row(
+      &format!("SELECT COUNT(*) FROM history{filter}"),
+      params_from_iter(values.iter()),
+      |row| row.get(0),
+    )?;
+
+    let limit = query
+      .limit
+      .unwrap_or(DEFAULT_PAGE_SIZE)
+      .clamp(1, MAX_PAGE_SIZE);
+    values.push(SqlValue::Integer(i64::from(limit)));
+    values.push(SqlValue::Integer(i64::from(query.offset.unwrap_or(0))));
+
+    let mut statement = conn.prepare(&format!(
+      "SELECT {COLUMNS} FROM history{filter} ORDER BY finished_at DESC, id DESC LIMIT ? OFFSET ?"
+    ))?;
+    let entries = statement
+      .query_map(params_from_iter(values.iter()), read_entry)?
+      .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(HistoryPage {
+      entries,
+      total: total.max(0) as u64,
+    })
+  }
+}
+
+fn build_filter(query: &HistoryQuery) -> (String, Vec<SqlValue>) {
+  let mut clauses: Vec<&str> = Vec::new();
+  let mut values: Vec<SqlValue> = Vec::new();
+
+  if let Some(text) = query
+    .text
+    .as_deref()
+    .map(str::trim)
+    .filter(|t| !t.is_empty())
+  {
+    clauses.push(
+      "(url LIKE ? ESCAPE '\\' OR title LIKE ? ESCAPE '\\' OR uploader LIKE ? ESCAPE '\\' \
+       OR file_path LIKE ? ESCAPE '\\')",
+    );
+    let pattern = format!("%{}%", escape_like(text));
+    for _ in 0..4 {
+      values.push(SqlValue::Text(pattern.clone()));
+    }
+  }
+  if let Some(status) = query.status {
+    clauses.push("status = ?");
+    values.push(SqlValue::Text(status.as_str().into()));
+  }
+  if let Some(from) = query.from {
+    clauses.push("finished_at >= ?");
+    values.push(SqlValue::Integer(from));
+  }
+  if let Some(to) = query.to {
+    clauses.push("finished_at < ?");
+    values.push(SqlValue::Integer(to));
+  }
+
+  if clauses.is_empty() {
+    (String::new(), values)
+  } else {
+    (format!(" WHERE {}", clauses.join(" AND ")), values)
+  }
+}
+
+fn escape_like(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('%', "\\%")
+    .replace('_', "\\_")
+}
+
+fn read_entry(row: &Row) -> rusqlite::Result<HistoryEntry> {
+  let format: String = row.get("format")?;
+  let template_context: String = row.get("template_context")?;
+  let status: String = row.get("status")?;
+  let filesize: Option<i64> = row.get("filesize")?;
+  let sections: Option<String> = row.get("sections")?;
+  let metadata_options: Option<String> = row.get("metadata_options")?;
+
+  Ok(HistoryEntry {
+    id: row.get("id")?,
+    item_id: row.get("item_id")?,
+    group_id: row.get("group_id")?,
+    url: row.get("url")?,
+    title: row.get("title")?,
+    uploader: row.get("uploader")?,
+    extractor: row.get("extractor")?,
+    video_id: row.get("video_id")?,
+    file_path: row.get("file_path")?,
+    format: serde_json::from_str(&format).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Text, Box::new(e))
+    })?,
+    template_context: serde_json::from_str(&template_context).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(10, rusqlite::types::Type::Text, Box::new(e))
+    })?,
+    filesize: filesize.map(|size| size.max(0) as u64),
+    duration: row.get("duration")?,
+    status: HistoryStatus::parse(&status).unwrap_or(HistoryStatus::Failed),
+    failure_code: row.get("failure_code")?,
+    started_at: row.get("started_at")?,
+    finished_at: row.get("finished_at")?,
+    preset: row.get("preset")?,
+    sections: sections
+      .map(|sections| serde_json::from_str(&sections))
+      .transpose()
+      .map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(18, rusqlite::types::Type::Text, Box::new(e))
+      })?,
+    metadata_options: metadata_options
+      .map(|options| serde_json::from_str(&options))
+      .transpose()
+      .map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(19, rusqlite::types::Type::Text, Box::new(e))
+      })?,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::download::{FormatOptions, MetadataOptions, SectionOptions};
+  use crate::models::TrackType;
+  use crate::runners::template_context::TemplateContext;
+  use crate::state::history_models::DownloadedMetadata;
+  use std::collections::HashMap;
+
+  fn handle() -> HistoryHandle {
+    HistoryHandle::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+  }
+
+  fn entry(title: &str, status: HistoryStatus, finished_at: i64) -> NewHistoryEntry {
+    NewHistoryEntry {
+      item_id: format!("item-{finished_at}"),
+      group_id: "group".into(),
+      url: format!("https://example.com/{finished_at}"),
+      metadata: DownloadedMetadata {
+        title: Some(title.into()),
+        ..DownloadedMetadata::default()
+      },
+      file_path: None,
+      format: FormatOptions {
+        track_type: TrackType::Both,
+        abr: None,
+        height: Some(1080),
+        fps: None,
+        video_codec: None,
+        audio_codec: None,
+        format_ids: vec![],
+      },
+      template_context: TemplateContext {
+        values: HashMap::new(),
+      },
+      status,
+      failure_code: (status == HistoryStatus::Failed).then(|| "server5xx".into()),
+      started_at: finished_at - 10,
+      finished_at,
+      preset: None,
+      sections: None,
+      metadata_options: None,
+    }
+  }
+
+  #[test]
+  fn query_pages_newest_first() {
+    let history = handle();
+    for i in 0..5 {
+      history
+        .record(&entry(&format!("Video {i}"), HistoryStatus::Completed, i))
+        .unwrap();
+    }
+
+    let page = history
+      .query(&HistoryQuery {
+        offset: Some(1),
+        limit: Some(2),
+        ..HistoryQuery::default()
+      })
+      .unwrap();
+    assert_eq!(page.total, 5);
+    let titles: Vec<_> = page
+      .entries
+      .iter()
+      .map(|e| e.title.clone().unwrap())
+      .collect();
+    assert_eq!(titles, vec!["Video 3", "Video 2"]);
+  }
+
+  #[test]
+  fn query_filters_by_text_status_and_date() {
+    let history = handle();
+    history
+      .record(&entry("Cooking 100%", HistoryStatus::Completed, 100))
+      .unwrap();
+    history
+      .record(&entry("Cooking again", HistoryStatus::Failed, 200))
+      .unwrap();
+    history
+      .record(&entry("Travel", HistoryStatus::Completed, 300))
+      .unwrap();
+
+    let by_text = history
+      .query(&HistoryQuery {
+        text: Some("cooking".into()),
+        ..HistoryQuery::default()
+      })
+      .unwrap();
+    assert_eq!(by_text.total, 2);
+
+    let literal_percent = history
+      .query(&HistoryQuery {
+        text: Some("100%".into()),
+        ..HistoryQuery::default()
+      })
+      .unwrap();
+    assert_eq!(literal_percent.total, 1);
+
+    let failed = history
+      .query(&HistoryQuery {
+        status: Some(HistoryStatus::Failed),
+        ..HistoryQuery::default()
+      })
+      .unwrap();
+    assert_eq!(failed.total, 1);
+    assert_eq!(failed.entries[0].failure_code.as_deref(), Some("server5xx"));
+
+    let ranged = history
+      .query(&HistoryQuery {
+        from: Some(150),
+        to: Some(300),
+        ..HistoryQuery::default()
+      })
+      .unwrap();
+    assert_eq!(ranged.total, 1);
+    assert_eq!(ranged.entries[0].finished_at, 200);
+  }
+
+  #[test]
+  fn get_round_trips_download_options() {
+    let history = handle();
+    let id = history
+      .record(&NewHistoryEntry {
+        preset: Some("podcast".into()),
+        sections: Some(SectionOptions {
+          chapters: vec!["^Intro$".into()],
+          ..SectionOptions::default()
+        }),
+        metadata_options: Some(MetadataOptions {
+          playlist_as_album: true,
+          ..MetadataOptions::default()
+        }),
+        ..entry("Video", HistoryStatus::Completed, 1)
+      })
+      .unwrap();
+    let stored = history.get(id).unwrap().unwrap();
+    assert_eq!(stored.format.height, Some(1080));
+    assert_eq!(stored.preset.as_deref(), Some("podcast"));
+    assert_eq!(stored.sections.unwrap().chapters, vec!["^Intro$"]);
+    assert!(stored.metadata_options.unwrap().playlist_as_album);
+    assert!(history.get(id + 1).unwrap().is_none());
+  }
+
+  #[test]
+  fn migrates_tables_of_the_first_version() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(SCHEMA).unwrap();
+    let history = HistoryHandle::with_connection(conn).unwrap();
+    let id = history
+      .record(&entry("Video", HistoryStatus::Completed, 1))
+      .unwrap();
+    assert!(history.get(id).unwrap().unwrap().preset.is_none());
+  }
+}
@@ -0,0 +1,109 @@
+use crate::models::download::{FormatOptions, MetadataOptions, SectionOptions};
+use crate::runners::template_context::TemplateContext;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryStatus {
+  Completed,
+  Failed,
+}
+
+impl HistoryStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Completed => "completed",
+      Self::Failed => "failed",
+    }
+  }
+
+  pub fn parse(value: &str) -> Option<Self> {
+    match value {
+      "completed" => Some(Self::Completed),
+      "failed" => Some(Self::Failed),
+      _ => None,
+    }
+  }
+}
+
+/// What yt-dlp reported about a finished download.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DownloadedMetadata {
+  pub id: Option<String>,
+  pub title: Option<String>,
+  pub uploader: Option<String>,
+  pub extractor_key: Option<String>,
+  pub duration: Option<f64>,
+  pub filesize: Option<u64>,
+  pub filesize_approx: Option<f64>,
+  pub filepath: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+  /// Row id, used to address the entry from commands.
+  pub id: i64,
+  pub item_id: String,
+  pub group_id: String,
+  pub url: String,
+  pub title: Option<String>,
+  pub uploader: Option<String>,
+  pub extractor: Option<String>,
+  pub video_id: Option<String>,
+  pub file_path: Option<String>,
+  pub format: FormatOptions,
+  pub template_context: TemplateContext,
+  pub filesize: Option<u64>,
+  pub duration: Option<f64>,
+  pub status: HistoryStatus,
+  pub failure_code: Option<String>,
+  pub started_at: i64,
+  pub finished_at: i64,
+  /// Preset the download used, applied again when it is re-downloaded.
+  pub preset: Option<String>,
+  pub sections: Option<SectionOptions>,
+  /// Tag overrides of the download, unlike `title` and `uploader` which yt-dlp reported.
+  pub metadata_options: Option<MetadataOptions>,
+}
+
+/// A history row before it is stored.
+#[derive(Debug, Clone)]
+pub struct NewHistoryEntry {
+  pub item_id: String,
+  pub group_id: String,
+  pub url: String,
+  pub metadata: DownloadedMetadata,
+  pub file_path: Option<String>,
+  pub format: FormatOptions,
+  pub template_context: TemplateContext,
+  pub status: HistoryStatus,
+  pub failure_code: Option<String>,
+  pub started_at: i64,
+  pub finished_at: i64,
+  pub preset: Option<String>,
+  pub sections: Option<SectionOptions>,
+  pub metadata_options: Option<MetadataOptions>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistoryQuery {
+  /// Matched against url, title, uploader and file path.
+  pub text: Option<String>,
+  pub status: Option<HistoryStatus>,
+  /// Unix time in milliseconds, inclusive.
+  pub from: Option<i64>,
+  /// Unix time in milliseconds, exclusive.
+  pub to: Option<i64>,
+  pub offset: Option<u32>,
+  pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+  pub entries: Vec<HistoryEntry>,
+  /// Number of entries matching the query, across all pages.
+  pub total: u64,
+}
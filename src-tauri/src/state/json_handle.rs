@@ -3,16 +3,33 @@ use crate::state::json_state::{json_merge, JsonBackedState};
 use arc_swap::ArcSwap;
 use serde_json::{Map, Value};
 use std::{error::Error, sync::Arc};
-use tauri::{AppHandle, Manager, Wry};
+use tauri::{AppHandle, Manager, Runtime, Wry};
 use tauri_plugin_store::{Store, StoreExt};
 
+/// Writes to a plugin store whatever runtime opened it, so the handles are the same type in the
+/// app and in the headless CLI.
+pub trait StoreWriter: Send + Sync {
+  fn set(&self, key: &str, value: Value);
+  fn save(&self) -> tauri_plugin_store::Result<()>;
+}
+
+impl<R: Runtime> StoreWriter for Store<R> {
+  fn set(&self, key: &str, value: Value) {
+    Store::set(self, key, value);
+  }
+
+  fn save(&self) -> tauri_plugin_store::Result<()> {
+    Store::save(self)
+  }
+}
+
 pub struct JsonStoreHandle<T: JsonBackedState> {
   swap: Arc<ArcSwap<T>>,
-  store: Arc<Store<Wry>>,
+  store: Arc<dyn StoreWriter>,
 }
 
 impl<T: JsonBackedState> JsonStoreHandle<T> {
-  pub fn init(app: &AppHandle<Wry>) -> Result<Self, Box<dyn Error>> {
+  pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<Self, Box<dyn Error>> {
     let paths_manager = app.state::<PathsManager>();
     let data_root = paths_manager.app_dir();
     let store_path = data_root.join(T::STORE_FILE);
@@ -46,6 +63,7 @@ impl<T: JsonBackedState> JsonStoreHandle<T> {
     json_merge(&mut raw, patch);
 
     let new_value = T::materialize(&raw)?;
+    new_value.validate()?;
     T::on_updated(app, &new_value);
 
     let to_store = serde_json::to_value(&new_value)?;
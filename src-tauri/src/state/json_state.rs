@@ -1,7 +1,7 @@
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use std::error::Error;
-use tauri::{AppHandle, Wry};
+use tauri::{AppHandle, Runtime, Wry};
 
 pub trait JsonBackedState:
   Sized + Clone + Send + Sync + 'static + Serialize + DeserializeOwned
@@ -18,7 +18,12 @@ pub trait JsonBackedState:
     Ok(serde_json::from_value(merged)?)
   }
 
-  fn before_initialized(_app: &AppHandle<Wry>, _value: &mut Self) {}
+  /// Rejects patched values before they are stored.
+  fn validate(&self) -> Result<(), String> {
+    Ok(())
+  }
+
+  fn before_initialized<R: Runtime>(_app: &AppHandle<R>, _value: &mut Self) {}
   fn on_updated(_app: &AppHandle<Wry>, _new_value: &Self) {}
 }
 
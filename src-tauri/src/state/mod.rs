@@ -1,8 +1,17 @@
+pub mod archive;
 pub mod config;
 pub mod config_models;
+pub mod history;
+pub mod history_models;
 mod json_handle;
 mod json_state;
 pub mod preferences;
 pub mod preferences_models;
+pub mod presets;
+pub mod presets_models;
+pub mod queue;
+pub mod queue_models;
+pub mod subscriptions;
+pub mod subscriptions_models;
 
 pub use json_state::json_merge;
@@ -0,0 +1,185 @@
+use crate::paths::PathsManager;
+use crate::state::json_handle::StoreWriter;
+use crate::state::presets_models::Preset;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::error::Error;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "presets.store.json";
+const PRESETS_KEY: &str = "presets";
+
+/// Named download presets, kept in the order they were created.
+pub struct PresetsHandle {
+  presets: Mutex<IndexMap<String, Preset>>,
+  store: Arc<dyn StoreWriter>,
+}
+
+impl PresetsHandle {
+  pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<Self, Box<dyn Error>> {
+    let paths_manager = app.state::<PathsManager>();
+    let store_path = paths_manager.app_dir().join(STORE_FILE);
+    let store = app.store(store_path)?;
+
+    let raw = store.get(PRESETS_KEY).unwrap_or(Value::Array(Vec::new()));
+    let stored: Vec<Preset> = serde_json::from_value(raw).unwrap_or_else(|e| {
+      tracing::warn!("Discarding unreadable presets: {e}");
+      Vec::new()
+    });
+
+    Ok(Self {
+      presets: Mutex::new(
+        stored
+          .into_iter()
+          .map(|preset| (preset.id.clone(), preset))
+          .collect(),
+      ),
+      store,
+    })
+  }
+
+  pub fn list(&self) -> Vec<Preset> {
+    self.presets.lock().unwrap().values().cloned().collect()
+  }
+
+  pub fn get(&self, id: &str) -> Option<Preset> {
+    self.presets.lock().unwrap().get(id).cloned()
+  }
+
+  /// Looks a preset up by id, or by name ignoring case, as users type it on the command line.
+  pub fn find(&self, id_or_name: &str) -> Option<Preset> {
+    let presets = self.presets.lock().unwrap();
+    presets.get(id_or_name).cloned().or_else(|| {
+      presets
+        .values()
+        .find(|p| p.name.eq_ignore_ascii_case(id_or_name))
+        .cloned()
+    })
+  }
+
+  /// Adds the preset or replaces the one with the same id.
+  pub fn save(&self, preset: Preset) {
+    let mut presets = self.presets.lock().unwrap();
+    presets.insert(preset.id.clone(), preset);
+    self.persist(&presets);
+  }
+
+  pub fn remove(&self, id: &str) -> bool {
+    let mut presets = self.presets.lock().unwrap();
+    let removed = presets.shift_remove(id).is_some();
+    if removed {
+      self.persist(&presets);
+    }
+    removed
+  }
+
+  /// Reads presets exported by `export` and adds them, replacing presets with the same id.
+  /// Returns the number of presets read. Nothing is imported when one of them is invalid.
+  pub fn import(&self, path: &Path) -> Result<usize, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let imported = parse_presets(&contents)?;
+
+    let count = imported.len();
+    let mut presets = self.presets.lock().unwrap();
+    for preset in imported {
+      presets.insert(preset.id.clone(), preset);
+    }
+    self.persist(&presets);
+    Ok(count)
+  }
+
+  /// Writes every preset as a JSON array and returns the number written.
+  pub fn export(&self, path: &Path) -> Result<usize, String> {
+    let presets = self.list();
+    let json = serde_json::to_string_pretty(&presets).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())?;
+    Ok(presets.len())
+  }
+
+  fn persist(&self, presets: &IndexMap<String, Preset>) {
+    let values: Vec<&Preset> = presets.values().collect();
+    match serde_json::to_value(values) {
+      Ok(raw) => {
+        self.store.set(PRESETS_KEY, raw);
+        if let Err(e) = self.store.save() {
+          tracing::warn!("Failed to persist presets: {e}");
+        }
+      }
+      Err(e) => tracing::warn!("Failed to serialize presets: {e}"),
+    }
+  }
+}
+
+/// Parses an exported presets file and checks every preset like `preset_save` does.
+fn parse_presets(contents: &str) -> Result<Vec<Preset>, String> {
+  let imported: Vec<Preset> =
+    serde_json::from_str(contents).map_err(|e| format!("Invalid presets file: {e}"))?;
+  imported
+    .into_iter()
+    .map(|preset| {
+      if preset.id.trim().is_empty() {
+        return Err(format!("Preset {} has no id", preset.name));
+      }
+      let name = preset.name.clone();
+      preset
+        .normalize()
+        .map_err(|e| format!("Preset {name} is invalid: {e}"))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::download::{VideoCodec, VideoContainer, VideoQuality, VideoReencodeTarget};
+  use crate::state::config_models::OutputSettings;
+
+  fn preset(id: &str, name: &str) -> Preset {
+    Preset {
+      id: id.into(),
+      name: name.into(),
+      format: None,
+      output: None,
+      subtitles: None,
+      sponsor_block: None,
+      download_dir: None,
+    }
+  }
+
+  #[test]
+  fn imported_presets_are_normalized() {
+    let mut phone = preset("phone", "  Phone  ");
+    phone.download_dir = Some("  ".into());
+    let json = serde_json::to_string(&[phone]).unwrap();
+
+    let imported = parse_presets(&json).unwrap();
+    assert_eq!(imported[0].name, "Phone");
+    assert_eq!(imported[0].download_dir, None);
+  }
+
+  #[test]
+  fn invalid_presets_reject_the_file() {
+    let mut output = OutputSettings::default();
+    output.video.container = VideoContainer::Webm;
+    output.video.reencode = Some(VideoReencodeTarget {
+      codec: VideoCodec::H264,
+      quality: VideoQuality::Crf { crf: 23 },
+      preset: None,
+      max_height: None,
+    });
+    let mut broken = preset("broken", "Broken WebM");
+    broken.output = Some(output);
+    let json = serde_json::to_string(&[preset("ok", "Fine"), broken]).unwrap();
+
+    let err = parse_presets(&json).unwrap_err();
+    assert!(err.starts_with("Preset Broken WebM is invalid"), "{err}");
+
+    let json = serde_json::to_string(&[preset("unnamed", " ")]).unwrap();
+    assert!(parse_presets(&json).is_err());
+    let json = serde_json::to_string(&[preset("", "No id")]).unwrap();
+    assert_eq!(parse_presets(&json).unwrap_err(), "Preset No id has no id");
+  }
+}
@@ -0,0 +1,100 @@
+use crate::models::download::FormatOptions;
+use crate::state::config_models::{Config, OutputSettings, SponsorBlockSettings, SubtitleSettings};
+use crate::state::preferences_models::Preferences;
+use serde::{Deserialize, Serialize};
+
+/// Named bundle of download settings, e.g. "Podcast MP3 128k". Every part left `None` falls back
+/// to the item's format or the global settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preset {
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub format: Option<FormatOptions>,
+  #[serde(default)]
+  pub output: Option<OutputSettings>,
+  #[serde(default)]
+  pub subtitles: Option<SubtitleSettings>,
+  #[serde(default)]
+  pub sponsor_block: Option<SponsorBlockSettings>,
+  /// Target directory for both video and audio downloads.
+  #[serde(default)]
+  pub download_dir: Option<String>,
+}
+
+impl Preset {
+  /// Replaces the global settings the preset overrides.
+  pub fn apply(&self, cfg: &mut Config, prefs: &mut Preferences) {
+    if let Some(output) = &self.output {
+      cfg.output = output.clone();
+    }
+    if let Some(subtitles) = &self.subtitles {
+      cfg.subtitles = subtitles.clone();
+    }
+    if let Some(sponsor_block) = &self.sponsor_block {
+      cfg.sponsor_block = sponsor_block.clone();
+    }
+    if let Some(dir) = &self.download_dir {
+      cfg.output.download_dir = Some(dir.clone());
+      prefs.paths.video_download_dir = Some(dir.clone());
+      prefs.paths.audio_download_dir = Some(dir.clone());
+    }
+  }
+
+  /// Trims the name and download directory and checks the output settings, like every preset
+  /// that is saved or imported.
+  pub fn normalize(mut self) -> Result<Self, String> {
+    self.name = self.name.trim().to_string();
+    if self.name.is_empty() {
+      return Err("Preset name must not be empty".into());
+    }
+    if let Some(output) = &self.output {
+      output.video.validate()?;
+    }
+    self.download_dir = self
+      .download_dir
+      .map(|dir| dir.trim().to_string())
+      .filter(|dir| !dir.is_empty());
+    Ok(self)
+  }
+
+  /// The preset's format if it has one, otherwise the item's.
+  pub fn format_or<'a>(&'a self, item_format: &'a FormatOptions) -> &'a FormatOptions {
+    self.format.as_ref().unwrap_or(item_format)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn apply_only_overrides_what_the_preset_sets() {
+    let mut cfg = Config::default();
+    cfg.subtitles.enabled = true;
+    let mut prefs = Preferences::default();
+
+    let preset = Preset {
+      id: "phone".into(),
+      name: "Phone 720p MP4".into(),
+      format: None,
+      output: Some(OutputSettings {
+        file_name_template: "%(title)s.%(ext)s".into(),
+        ..OutputSettings::default()
+      }),
+      subtitles: None,
+      sponsor_block: None,
+      download_dir: Some("/media/phone".into()),
+    };
+    preset.apply(&mut cfg, &mut prefs);
+
+    assert_eq!(cfg.output.file_name_template, "%(title)s.%(ext)s");
+    assert_eq!(cfg.output.download_dir.as_deref(), Some("/media/phone"));
+    assert_eq!(
+      prefs.paths.audio_download_dir.as_deref(),
+      Some("/media/phone")
+    );
+    assert!(cfg.subtitles.enabled);
+  }
+}
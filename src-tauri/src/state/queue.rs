@@ -0,0 +1,264 @@
+use crate::models::DownloadItem;
+use crate::paths::PathsManager;
+use crate::runners::template_context::TemplateContext;
+use crate::scheduling::schedule::DownloadSchedule;
+use crate::state::json_handle::StoreWriter;
+use crate::state::queue_models::{QueueEntryState, QueuedDownload};
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "queue.store.json";
+const ROOT_KEY: &str = "entries";
+
+/// Durable copy of every download that has been handed to the download dispatcher
+/// but has not finished yet, so it can be re-submitted after a restart or crash.
+pub struct QueueHandle {
+  entries: Mutex<IndexMap<String, QueuedDownload>>,
+  store: Arc<dyn StoreWriter>,
+}
+
+impl QueueHandle {
+  pub fn init(app: &AppHandle<Wry>) -> Result<Self, Box<dyn Error>> {
+    let paths_manager = app.state::<PathsManager>();
+    let store_path = paths_manager.app_dir().join(STORE_FILE);
+    let store = app.store(store_path)?;
+    let raw = store.get(ROOT_KEY);
+    Ok(Self::restore(raw, store))
+  }
+
+  fn restore(raw: Option<Value>, store: Arc<dyn StoreWriter>) -> Self {
+    let raw = raw.unwrap_or(Value::Array(Vec::new()));
+    let stored: Vec<QueuedDownload> = serde_json::from_value(raw).unwrap_or_else(|e| {
+      tracing::warn!("Discarding unreadable download queue: {e}");
+      Vec::new()
+    });
+
+    let entries = stored
+      .into_iter()
+      .map(|entry| (entry.id.clone(), entry))
+      .collect();
+
+    Self {
+      entries: Mutex::new(entries),
+      store,
+    }
+  }
+
+  /// Records the items as queued. Items that are already known, like the ones restored after a
+  /// restart, keep their state and the time they were first added.
+  pub fn enqueue(
+    &self,
+    group_id: &str,
+    items: &[DownloadItem],
+    schedule: Option<&DownloadSchedule>,
+  ) {
+    let mut entries = self.entries.lock().unwrap();
+    for item in items {
+      let mut entry = QueuedDownload::from_item(group_id, item, schedule);
+      if let Some(existing) = entries.get(&item.id) {
+        entry.state = existing.state;
+        entry.added_at = existing.added_at;
+      }
+      entries.insert(item.id.clone(), entry);
+    }
+    self.persist(&entries);
+  }
+
+  /// Marks an entry as running and records its numbered template context, so a restored
+  /// entry renders the same output path and yt-dlp can continue its `.part` files.
+  pub fn mark_running(&self, id: &str, template_context: &TemplateContext) {
+    let mut entries = self.entries.lock().unwrap();
+    if let Some(entry) = entries.get_mut(id) {
+      entry.state = QueueEntryState::Running;
+      entry.template_context = template_context.clone();
+      self.persist(&entries);
+    }
+  }
+
+  pub fn set_state(&self, id: &str, state: QueueEntryState) {
+    let mut entries = self.entries.lock().unwrap();
+    if let Some(entry) = entries.get_mut(id) {
+      entry.state = state;
+      self.persist(&entries);
+    }
+  }
+
+  pub fn set_group_state(&self, group_id: &str, state: QueueEntryState) {
+    let mut entries = self.entries.lock().unwrap();
+    let mut changed = false;
+    for entry in entries.values_mut() {
+      if entry.group_id == group_id && entry.state != state {
+        entry.state = state;
+        changed = true;
+      }
+    }
+    if changed {
+      self.persist(&entries);
+    }
+  }
+
+  pub fn get(&self, id: &str) -> Option<QueuedDownload> {
+    self.entries.lock().unwrap().get(id).cloned()
+  }
+
+  pub fn remove(&self, id: &str) {
+    let mut entries = self.entries.lock().unwrap();
+    if entries.shift_remove(id).is_some() {
+      self.persist(&entries);
+    }
+  }
+
+  pub fn remove_group(&self, group_id: &str) {
+    let mut entries = self.entries.lock().unwrap();
+    let before = entries.len();
+    entries.retain(|_, entry| entry.group_id != group_id);
+    if entries.len() != before {
+      self.persist(&entries);
+    }
+  }
+
+  pub fn group_len(&self, group_id: &str) -> usize {
+    self
+      .entries
+      .lock()
+      .unwrap()
+      .values()
+      .filter(|entry| entry.group_id == group_id)
+      .count()
+  }
+
+  pub fn snapshot(&self) -> Vec<QueuedDownload> {
+    self.entries.lock().unwrap().values().cloned().collect()
+  }
+
+  /// Returns the unfinished entries grouped by their group id, in insertion order.
+  pub fn pending_groups(&self) -> IndexMap<String, Vec<QueuedDownload>> {
+    let mut groups: IndexMap<String, Vec<QueuedDownload>> = IndexMap::new();
+    for entry in self.entries.lock().unwrap().values() {
+      groups
+        .entry(entry.group_id.clone())
+        .or_default()
+        .push(entry.clone());
+    }
+    groups
+  }
+
+  fn persist(&self, entries: &IndexMap<String, QueuedDownload>) {
+    let values: Vec<&QueuedDownload> = entries.values().collect();
+    match serde_json::to_value(values) {
+      Ok(raw) => {
+        self.store.set(ROOT_KEY, raw);
+        if let Err(e) = self.store.save() {
+          tracing::warn!("Failed to persist download queue: {e}");
+        }
+      }
+      Err(e) => tracing::warn!("Failed to serialize download queue: {e}"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Keeps what would be written to disk so a second handle can be restored from it.
+  #[derive(Default)]
+  struct MemoryStore {
+    value: Mutex<Option<Value>>,
+  }
+
+  impl StoreWriter for MemoryStore {
+    fn set(&self, _key: &str, value: Value) {
+      *self.value.lock().unwrap() = Some(value);
+    }
+
+    fn save(&self) -> tauri_plugin_store::Result<()> {
+      Ok(())
+    }
+  }
+
+  fn item(id: &str) -> DownloadItem {
+    DownloadItem {
+      id: id.into(),
+      url: format!("https://example.com/{id}"),
+      format: Default::default(),
+      template_context: TemplateContext {
+        values: Default::default(),
+      },
+      live: None,
+      preset: None,
+      sections: None,
+      metadata: None,
+    }
+  }
+
+  fn handle() -> (QueueHandle, Arc<MemoryStore>) {
+    let store = Arc::new(MemoryStore::default());
+    (QueueHandle::restore(None, store.clone()), store)
+  }
+
+  fn restart(store: &MemoryStore) -> QueueHandle {
+    let raw = store.value.lock().unwrap().clone();
+    QueueHandle::restore(raw, Arc::new(MemoryStore::default()))
+  }
+
+  #[test]
+  fn entries_survive_a_restart() {
+    let (queue, store) = handle();
+    queue.enqueue("g1", &[item("a"), item("b")], None);
+    queue.set_state("b", QueueEntryState::Running);
+
+    let restored = restart(&store);
+    let snapshot = restored.snapshot();
+    let ids: Vec<_> = snapshot.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(snapshot[0].url, "https://example.com/a");
+    assert_eq!(snapshot[1].state, QueueEntryState::Running);
+    assert_eq!(restored.group_len("g1"), 2);
+  }
+
+  #[test]
+  fn pending_groups_keep_insertion_order() {
+    let (queue, _) = handle();
+    queue.enqueue("g2", &[item("a")], None);
+    queue.enqueue("g1", &[item("b")], None);
+    queue.enqueue("g2", &[item("c")], None);
+
+    let groups = queue.pending_groups();
+    let order: Vec<_> = groups.keys().map(String::as_str).collect();
+    assert_eq!(order, ["g2", "g1"]);
+    let g2: Vec<_> = groups["g2"].iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(g2, ["a", "c"]);
+
+    queue.remove_group("g2");
+    assert_eq!(queue.pending_groups().len(), 1);
+  }
+
+  #[test]
+  fn restored_entries_keep_their_state_when_requeued() {
+    let (queue, store) = handle();
+    queue.enqueue("g1", &[item("a"), item("b")], None);
+    queue.set_state("a", QueueEntryState::Paused);
+    let added_at = queue.get("a").unwrap().added_at;
+
+    // Restoring the queue hands the same items to the dispatcher, which enqueues them again.
+    let restored = restart(&store);
+    let items: Vec<_> = restored
+      .pending_groups()
+      .swap_remove("g1")
+      .unwrap()
+      .iter()
+      .map(QueuedDownload::to_item)
+      .collect();
+    restored.enqueue("g1", &items, None);
+
+    let paused = restored.get("a").unwrap();
+    assert_eq!(paused.state, QueueEntryState::Paused);
+    assert_eq!(paused.added_at, added_at);
+    assert_eq!(restored.get("b").unwrap().state, QueueEntryState::Queued);
+  }
+}
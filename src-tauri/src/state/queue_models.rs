@@ -0,0 +1,89 @@
+use crate::models::download::{
+  FormatOptions, LiveRecordingOptions, MetadataOptions, SectionOptions,
+};
+use crate::models::DownloadItem;
+use crate::runners::template_context::TemplateContext;
+use crate::scheduling::schedule::DownloadSchedule;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueEntryState {
+  Queued,
+  Running,
+  Paused,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedDownload {
+  pub id: String,
+  pub group_id: String,
+  pub url: String,
+  pub format: FormatOptions,
+  pub template_context: TemplateContext,
+  pub state: QueueEntryState,
+  pub added_at: u128,
+  #[serde(default)]
+  pub schedule: Option<DownloadSchedule>,
+  #[serde(default)]
+  pub live: Option<LiveRecordingOptions>,
+  #[serde(default)]
+  pub preset: Option<String>,
+  #[serde(default)]
+  pub sections: Option<SectionOptions>,
+  #[serde(default)]
+  pub metadata: Option<MetadataOptions>,
+}
+
+impl QueuedDownload {
+  pub fn from_item(
+    group_id: &str,
+    item: &DownloadItem,
+    schedule: Option<&DownloadSchedule>,
+  ) -> Self {
+    Self {
+      id: item.id.clone(),
+      group_id: group_id.to_string(),
+      url: item.url.clone(),
+      format: item.format.clone(),
+      template_context: item.template_context.clone(),
+      state: QueueEntryState::Queued,
+      added_at: std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0),
+      schedule: schedule.cloned(),
+      live: item.live.clone(),
+      preset: item.preset.clone(),
+      sections: item.sections.clone(),
+      metadata: item.metadata.clone(),
+    }
+  }
+
+  pub fn to_item(&self) -> DownloadItem {
+    DownloadItem {
+      id: self.id.clone(),
+      url: self.url.clone(),
+      format: self.format.clone(),
+      template_context: self.template_context.clone(),
+      live: self.live.clone(),
+      preset: self.preset.clone(),
+      sections: self.sections.clone(),
+      metadata: self.metadata.clone(),
+    }
+  }
+}
+
+/// A group whose downloads are held or paused by its schedule.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledGroup {
+  pub group_id: String,
+  pub schedule: DownloadSchedule,
+  /// Whether the group may currently run.
+  pub open: bool,
+  /// Unix time in milliseconds at which the group starts or pauses next.
+  pub next_change_at: Option<i64>,
+  pub pending_count: usize,
+}
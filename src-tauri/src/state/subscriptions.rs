@@ -0,0 +1,261 @@
+use crate::paths::PathsManager;
+use crate::state::json_handle::StoreWriter;
+use crate::state::subscriptions_models::{PendingSubscriptionItem, Subscription};
+use indexmap::{IndexMap, IndexSet};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "subscriptions.store.json";
+const SUBSCRIPTIONS_KEY: &str = "subscriptions";
+const ARCHIVE_KEY: &str = "archive";
+const PENDING_KEY: &str = "pending";
+
+#[derive(Default)]
+struct SubscriptionsState {
+  subscriptions: IndexMap<String, Subscription>,
+  /// Archive keys of the entries already taken, per subscription id.
+  archive: IndexMap<String, IndexSet<String>>,
+  /// Entries whose download has not finished yet, by download item id.
+  pending: IndexMap<String, PendingSubscriptionItem>,
+}
+
+/// Followed channels and playlists, together with the entries already taken from them.
+pub struct SubscriptionsHandle {
+  state: Mutex<SubscriptionsState>,
+  store: Arc<dyn StoreWriter>,
+}
+
+impl SubscriptionsHandle {
+  pub fn init(app: &AppHandle<Wry>) -> Result<Self, Box<dyn Error>> {
+    let paths_manager = app.state::<PathsManager>();
+    let store_path = paths_manager.app_dir().join(STORE_FILE);
+    let store = app.store(store_path)?;
+
+    let state = SubscriptionsState {
+      subscriptions: read_stored::<Vec<Subscription>>(
+        store.get(SUBSCRIPTIONS_KEY),
+        "subscriptions",
+      )
+      .into_iter()
+      .map(|subscription| (subscription.id.clone(), subscription))
+      .collect(),
+      archive: read_stored(store.get(ARCHIVE_KEY), "subscription archive"),
+      pending: read_stored(store.get(PENDING_KEY), "pending subscription items"),
+    };
+    Ok(Self::with_state(state, store))
+  }
+
+  fn with_state(state: SubscriptionsState, store: Arc<dyn StoreWriter>) -> Self {
+    Self {
+      state: Mutex::new(state),
+      store,
+    }
+  }
+
+  pub fn list(&self) -> Vec<Subscription> {
+    let state = self.state.lock().unwrap();
+    state.subscriptions.values().cloned().collect()
+  }
+
+  pub fn get(&self, id: &str) -> Option<Subscription> {
+    self.state.lock().unwrap().subscriptions.get(id).cloned()
+  }
+
+  pub fn add(&self, subscription: Subscription) {
+    let mut state = self.state.lock().unwrap();
+    state
+      .subscriptions
+      .insert(subscription.id.clone(), subscription);
+    self.persist(&state);
+  }
+
+  pub fn remove(&self, id: &str) -> bool {
+    let mut state = self.state.lock().unwrap();
+    let removed = state.subscriptions.shift_remove(id).is_some();
+    state.archive.shift_remove(id);
+    state.pending.retain(|_, item| item.subscription_id != id);
+    if removed {
+      self.persist(&state);
+    }
+    removed
+  }
+
+  /// Returns the keys that are neither in the subscription's archive nor downloading.
+  pub fn unseen(&self, id: &str, keys: &[String]) -> Vec<String> {
+    let state = self.state.lock().unwrap();
+    let archive = state.archive.get(id);
+    keys
+      .iter()
+      .filter(|key| !archive.is_some_and(|seen| seen.contains(*key)))
+      .filter(|key| {
+        !state
+          .pending
+          .values()
+          .any(|item| item.subscription_id == id && &item.key == *key)
+      })
+      .cloned()
+      .collect()
+  }
+
+  /// Remembers the downloads started for the subscription's entries, so their keys are only
+  /// archived once the download finished. Takes `(download item id, archive key)` pairs.
+  pub fn track_downloads(&self, id: &str, items: &[(String, String)]) {
+    let mut state = self.state.lock().unwrap();
+    for (item_id, key) in items {
+      state.pending.insert(
+        item_id.clone(),
+        PendingSubscriptionItem {
+          subscription_id: id.to_string(),
+          key: key.clone(),
+        },
+      );
+    }
+    self.persist(&state);
+  }
+
+  /// Archives the key of a finished subscription download. Failed downloads are forgotten, so
+  /// the next check picks their entry up again.
+  pub fn finish_download(&self, item_id: &str, succeeded: bool) {
+    let mut state = self.state.lock().unwrap();
+    let Some(item) = state.pending.shift_remove(item_id) else {
+      return;
+    };
+    if succeeded && state.subscriptions.contains_key(&item.subscription_id) {
+      state
+        .archive
+        .entry(item.subscription_id)
+        .or_default()
+        .insert(item.key);
+    }
+    self.persist(&state);
+  }
+
+  /// Adds keys to the subscription's archive and records a successful check.
+  pub fn record_check(&self, id: &str, keys: &[String], checked_at: u128) {
+    let mut state = self.state.lock().unwrap();
+    if !state.subscriptions.contains_key(id) {
+      return;
+    }
+    let archive = state.archive.entry(id.to_string()).or_default();
+    archive.extend(keys.iter().cloned());
+    if let Some(subscription) = state.subscriptions.get_mut(id) {
+      subscription.last_checked_at = Some(checked_at);
+      subscription.last_error = None;
+    }
+    self.persist(&state);
+  }
+
+  pub fn record_error(&self, id: &str, error: String, checked_at: u128) {
+    let mut state = self.state.lock().unwrap();
+    if let Some(subscription) = state.subscriptions.get_mut(id) {
+      subscription.last_checked_at = Some(checked_at);
+      subscription.last_error = Some(error);
+      self.persist(&state);
+    }
+  }
+
+  fn persist(&self, state: &SubscriptionsState) {
+    let subscriptions: Vec<&Subscription> = state.subscriptions.values().collect();
+    match (
+      serde_json::to_value(subscriptions),
+      serde_json::to_value(&state.archive),
+      serde_json::to_value(&state.pending),
+    ) {
+      (Ok(raw_subscriptions), Ok(raw_archive), Ok(raw_pending)) => {
+        self.store.set(SUBSCRIPTIONS_KEY, raw_subscriptions);
+        self.store.set(ARCHIVE_KEY, raw_archive);
+        self.store.set(PENDING_KEY, raw_pending);
+        if let Err(e) = self.store.save() {
+          tracing::warn!("Failed to persist subscriptions: {e}");
+        }
+      }
+      (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+        tracing::warn!("Failed to serialize subscriptions: {e}")
+      }
+    }
+  }
+}
+
+fn read_stored<T: DeserializeOwned + Default>(raw: Option<Value>, what: &str) -> T {
+  match raw {
+    Some(raw) => serde_json::from_value(raw).unwrap_or_else(|e| {
+      tracing::warn!("Discarding unreadable {what}: {e}");
+      T::default()
+    }),
+    None => T::default(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct NullStore;
+
+  impl StoreWriter for NullStore {
+    fn set(&self, _key: &str, _value: Value) {}
+
+    fn save(&self) -> tauri_plugin_store::Result<()> {
+      Ok(())
+    }
+  }
+
+  fn handle() -> SubscriptionsHandle {
+    let handle =
+      SubscriptionsHandle::with_state(SubscriptionsState::default(), Arc::new(NullStore));
+    handle.add(Subscription {
+      id: "s".into(),
+      url: "https://example.com/channel".into(),
+      title: None,
+      format: Default::default(),
+      download_existing: false,
+      created_at: 0,
+      last_checked_at: None,
+      last_error: None,
+    });
+    handle
+  }
+
+  fn keys(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+  }
+
+  #[test]
+  fn recorded_keys_are_no_longer_unseen() {
+    let subscriptions = handle();
+    assert_eq!(
+      subscriptions.unseen("s", &keys(&["a", "b"])),
+      keys(&["a", "b"])
+    );
+
+    subscriptions.record_check("s", &keys(&["a"]), 10);
+    assert_eq!(subscriptions.unseen("s", &keys(&["a", "b"])), keys(&["b"]));
+    assert_eq!(subscriptions.get("s").unwrap().last_checked_at, Some(10));
+    // Other subscriptions have an archive of their own.
+    assert_eq!(subscriptions.unseen("other", &keys(&["a"])), keys(&["a"]));
+  }
+
+  #[test]
+  fn only_finished_downloads_are_archived() {
+    let subscriptions = handle();
+    let all = keys(&["a", "b", "c"]);
+    subscriptions.track_downloads(
+      "s",
+      &[("item-a".into(), "a".into()), ("item-b".into(), "b".into())],
+    );
+    // Downloading entries are not enqueued a second time.
+    assert_eq!(subscriptions.unseen("s", &all), keys(&["c"]));
+
+    subscriptions.finish_download("item-a", true);
+    subscriptions.finish_download("item-b", false);
+    assert_eq!(subscriptions.unseen("s", &all), keys(&["b", "c"]));
+
+    // Unknown items, e.g. downloads that are not from a subscription, are ignored.
+    subscriptions.finish_download("item-x", true);
+    assert_eq!(subscriptions.unseen("s", &all), keys(&["b", "c"]));
+  }
+}
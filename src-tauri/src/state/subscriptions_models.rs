@@ -0,0 +1,33 @@
+use crate::models::download::FormatOptions;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+  pub id: String,
+  pub url: String,
+  pub title: Option<String>,
+  pub format: FormatOptions,
+  /// Download the items that already exist on the first check instead of only later ones.
+  pub download_existing: bool,
+  pub created_at: u128,
+  pub last_checked_at: Option<u128>,
+  pub last_error: Option<String>,
+}
+
+/// Subscription entry whose download is still queued or running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSubscriptionItem {
+  pub subscription_id: String,
+  pub key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionCheckResult {
+  pub subscription_id: String,
+  /// Group the new items were enqueued in, `None` when there was nothing new.
+  pub group_id: Option<String>,
+  pub new_count: usize,
+}
@@ -0,0 +1,320 @@
+use crate::runners::host_match::{host_pattern_score, normalize_host_pattern, url_host, ANY_HOST};
+use crate::stronghold::stronghold_state::AuthSecrets;
+use serde::{Deserialize, Serialize};
+
+/// Vault key holding every credential profile as a JSON array.
+pub const PROFILES_KEY: &str = "auth.profiles";
+/// Profile the old global `auth.*` keys are folded into. It starts unbound and is the only
+/// profile allowed to stay that way, so nothing is sent until the user picks its sites.
+pub const MIGRATED_PROFILE_ID: &str = "migrated";
+/// Pattern matching every site.
+pub const ANY_SITE: &str = ANY_HOST;
+
+/// Named set of credentials that is only sent to the sites it is bound to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CredentialProfile {
+  pub id: String,
+  pub name: String,
+  /// Domain patterns such as `vimeo.com` or `*.example.org`. A domain also matches its
+  /// subdomains, `*` matches every site.
+  pub domains: Vec<String>,
+  /// yt-dlp extractor keys such as `Vimeo`, compared case-insensitively.
+  pub extractors: Vec<String>,
+  pub username: Option<String>,
+  pub password: Option<String>,
+  pub video_password: Option<String>,
+  pub bearer_token: Option<String>,
+  pub headers: Vec<String>,
+}
+
+/// What the frontend sees of a profile; secrets never leave the vault.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProfileSummary {
+  pub id: String,
+  pub name: String,
+  pub domains: Vec<String>,
+  pub extractors: Vec<String>,
+  pub username: Option<String>,
+  pub has_password: bool,
+  pub has_video_password: bool,
+  pub has_bearer_token: bool,
+  pub header_count: usize,
+  /// False while the profile is not bound to any site and therefore never used.
+  pub bound: bool,
+}
+
+/// Profile sent by the settings page. Secrets it leaves out keep their stored value, an empty
+/// value removes them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CredentialProfileUpdate {
+  pub id: String,
+  pub name: String,
+  pub domains: Vec<String>,
+  pub extractors: Vec<String>,
+  pub username: Option<String>,
+  pub password: Option<String>,
+  pub video_password: Option<String>,
+  pub bearer_token: Option<String>,
+  pub headers: Option<Vec<String>>,
+}
+
+impl CredentialProfileUpdate {
+  /// Builds the profile to store on top of the currently stored one, if any.
+  pub fn apply(self, existing: Option<&CredentialProfile>) -> CredentialProfile {
+    let keep = |value: Option<String>, stored: Option<&Option<String>>| {
+      value.or_else(|| stored.cloned().flatten())
+    };
+    CredentialProfile {
+      id: self.id,
+      name: self.name,
+      domains: self.domains,
+      extractors: self.extractors,
+      username: keep(self.username, existing.map(|p| &p.username)),
+      password: keep(self.password, existing.map(|p| &p.password)),
+      video_password: keep(self.video_password, existing.map(|p| &p.video_password)),
+      bearer_token: keep(self.bearer_token, existing.map(|p| &p.bearer_token)),
+      headers: self
+        .headers
+        .or_else(|| existing.map(|p| p.headers.clone()))
+        .unwrap_or_default(),
+    }
+  }
+}
+
+impl From<&CredentialProfile> for CredentialProfileSummary {
+  fn from(profile: &CredentialProfile) -> Self {
+    Self {
+      id: profile.id.clone(),
+      name: profile.name.clone(),
+      domains: profile.domains.clone(),
+      extractors: profile.extractors.clone(),
+      username: profile.username.clone(),
+      has_password: profile.password.is_some(),
+      has_video_password: profile.video_password.is_some(),
+      has_bearer_token: profile.bearer_token.is_some(),
+      header_count: profile.headers.len(),
+      bound: profile.is_bound(),
+    }
+  }
+}
+
+impl CredentialProfile {
+  /// Trims every field, drops empty values and checks that the profile is bound to something,
+  /// except for the migrated profile which waits for the user to bind it.
+  pub fn normalize(mut self) -> Result<Self, String> {
+    self.name = self.name.trim().to_string();
+    if self.name.is_empty() {
+      return Err("Profile name must not be empty".into());
+    }
+
+    self.domains = clean_list(self.domains)
+      .into_iter()
+      .map(|domain| domain.to_lowercase())
+      .collect();
+    for domain in &self.domains {
+      if domain != ANY_SITE && normalize_host_pattern(domain).is_empty() {
+        return Err(format!("Invalid domain pattern: {domain}"));
+      }
+    }
+    self.extractors = clean_list(self.extractors);
+    if !self.is_bound() && self.id != MIGRATED_PROFILE_ID {
+      return Err("Profile must be bound to at least one domain or extractor".into());
+    }
+
+    self.username = clean_value(self.username);
+    self.password = clean_value(self.password);
+    self.video_password = clean_value(self.video_password);
+    self.bearer_token = clean_value(self.bearer_token);
+    self.headers = clean_list(self.headers)
+      .into_iter()
+      .filter(|header| header.contains(':'))
+      .collect();
+    Ok(self)
+  }
+
+  pub fn is_bound(&self) -> bool {
+    !self.domains.is_empty() || !self.extractors.is_empty()
+  }
+
+  pub fn secrets(&self) -> AuthSecrets {
+    AuthSecrets {
+      username: self.username.clone(),
+      password: self.password.clone(),
+      video_password: self.video_password.clone(),
+      bearer_token: self.bearer_token.clone(),
+      headers: self.headers.clone(),
+    }
+  }
+
+  /// How specific this profile is for the given site, `None` when it does not apply. An
+  /// extractor match beats any domain, a longer domain beats a shorter one and `*` comes last.
+  fn match_score(&self, host: Option<&str>, extractor: Option<&str>) -> Option<usize> {
+    if let Some(extractor) = extractor {
+      if self
+        .extractors
+        .iter()
+        .any(|key| key.eq_ignore_ascii_case(extractor))
+      {
+        return Some(usize::MAX);
+      }
+    }
+
+    self
+      .domains
+      .iter()
+      .filter_map(|pattern| host_pattern_score(pattern, host))
+      .max()
+  }
+}
+
+/// Picks the most specific profile bound to the URL's host or the extractor, if any.
+pub fn resolve_profile<'a>(
+  profiles: &'a [CredentialProfile],
+  url: &str,
+  extractor: Option<&str>,
+) -> Option<&'a CredentialProfile> {
+  let host = url_host(url);
+  profiles
+    .iter()
+    .filter_map(|profile| {
+      profile
+        .match_score(host.as_deref(), extractor)
+        .map(|score| (score, profile))
+    })
+    .max_by_key(|(score, _)| *score)
+    .map(|(_, profile)| profile)
+}
+
+fn clean_value(value: Option<String>) -> Option<String> {
+  value
+    .map(|v| v.trim().to_string())
+    .filter(|v| !v.is_empty())
+}
+
+fn clean_list(values: Vec<String>) -> Vec<String> {
+  values
+    .into_iter()
+    .filter_map(|v| clean_value(Some(v)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn profile(id: &str, domains: &[&str], extractors: &[&str]) -> CredentialProfile {
+    CredentialProfile {
+      id: id.into(),
+      name: id.into(),
+      domains: domains.iter().map(|d| d.to_string()).collect(),
+      extractors: extractors.iter().map(|e| e.to_string()).collect(),
+      ..CredentialProfile::default()
+    }
+  }
+
+  #[test]
+  fn domain_matches_itself_and_subdomains_only() {
+    let profiles = vec![profile("vimeo", &["vimeo.com"], &[])];
+    let resolve = |url| resolve_profile(&profiles, url, None).map(|p| p.id.as_str());
+
+    assert_eq!(resolve("https://vimeo.com/123"), Some("vimeo"));
+    assert_eq!(resolve("https://player.VIMEO.com/video/1"), Some("vimeo"));
+    assert_eq!(resolve("https://notvimeo.com/123"), None);
+    assert_eq!(resolve("https://www.youtube.com/watch?v=x"), None);
+    assert_eq!(resolve("not a url"), None);
+  }
+
+  #[test]
+  fn most_specific_profile_wins() {
+    let profiles = vec![
+      profile("any", &[ANY_SITE], &[]),
+      profile("example", &["*.example.org"], &[]),
+      profile("media", &["media.example.org"], &[]),
+      profile("vimeo", &[], &["Vimeo"]),
+    ];
+    let resolve =
+      |url, extractor| resolve_profile(&profiles, url, extractor).map(|p| p.id.as_str());
+
+    assert_eq!(resolve("https://media.example.org/a", None), Some("media"));
+    assert_eq!(resolve("https://www.example.org/a", None), Some("example"));
+    assert_eq!(resolve("https://other.net/a", None), Some("any"));
+    assert_eq!(
+      resolve("https://media.example.org/a", Some("vimeo")),
+      Some("vimeo")
+    );
+  }
+
+  #[test]
+  fn normalize_requires_a_binding() {
+    let unbound = CredentialProfile {
+      name: "Empty".into(),
+      domains: vec!["  ".into()],
+      ..CredentialProfile::default()
+    };
+    assert!(unbound.normalize().is_err());
+
+    let bound = CredentialProfile {
+      name: " Vimeo ".into(),
+      domains: vec![" Vimeo.com ".into()],
+      password: Some("  ".into()),
+      headers: vec!["X-Test: 1".into(), "invalid".into()],
+      ..CredentialProfile::default()
+    }
+    .normalize()
+    .unwrap();
+    assert_eq!(bound.name, "Vimeo");
+    assert_eq!(bound.domains, vec!["vimeo.com"]);
+    assert_eq!(bound.password, None);
+    assert_eq!(bound.headers, vec!["X-Test: 1"]);
+  }
+
+  #[test]
+  fn migrated_profile_stays_unbound_and_matches_nothing() {
+    let migrated = CredentialProfile {
+      id: MIGRATED_PROFILE_ID.into(),
+      name: "Migrated credentials".into(),
+      password: Some("secret".into()),
+      ..CredentialProfile::default()
+    }
+    .normalize()
+    .unwrap();
+    assert!(!migrated.is_bound());
+    assert!(!CredentialProfileSummary::from(&migrated).bound);
+
+    let profiles = vec![migrated];
+    assert!(resolve_profile(&profiles, "https://vimeo.com/1", Some("Vimeo")).is_none());
+  }
+
+  #[test]
+  fn update_keeps_omitted_secrets_and_clears_empty_ones() {
+    let stored = CredentialProfile {
+      id: "vimeo".into(),
+      name: "Vimeo".into(),
+      domains: vec!["vimeo.com".into()],
+      username: Some("user".into()),
+      password: Some("secret".into()),
+      bearer_token: Some("token".into()),
+      headers: vec!["X-Test: 1".into()],
+      ..CredentialProfile::default()
+    };
+    let updated = CredentialProfileUpdate {
+      id: "vimeo".into(),
+      name: "Vimeo".into(),
+      domains: vec!["vimeo.com".into(), "vimeocdn.com".into()],
+      username: Some("user".into()),
+      bearer_token: Some(String::new()),
+      ..CredentialProfileUpdate::default()
+    }
+    .apply(Some(&stored))
+    .normalize()
+    .unwrap();
+
+    assert_eq!(updated.domains, vec!["vimeo.com", "vimeocdn.com"]);
+    assert_eq!(updated.password.as_deref(), Some("secret"));
+    assert_eq!(updated.bearer_token, None);
+    assert_eq!(updated.headers, vec!["X-Test: 1"]);
+  }
+}
@@ -1 +1,2 @@
+pub mod credential_profiles;
 pub mod stronghold_state;
@@ -1,9 +1,13 @@
+use crate::remote::RemoteApiServer;
+use crate::stronghold::credential_profiles::{
+  resolve_profile, CredentialProfile, MIGRATED_PROFILE_ID, PROFILES_KEY,
+};
 use base64::engine::general_purpose;
 use base64::Engine;
 use rand::Rng;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, Runtime, State};
 use tauri_plugin_keyring::KeyringExt;
 use tauri_plugin_stronghold::stronghold::Stronghold;
 
@@ -11,6 +15,14 @@ pub const CLIENT: &[u8] = b"ovd";
 const KR_SERVICE: &str = env!("TAURI_APP_IDENTIFIER");
 const KR_ACCOUNT: &str = "master_key";
 
+const FLAT_USERNAME: &str = "auth.username";
+const FLAT_PASSWORD: &str = "auth.password";
+const FLAT_VIDEO_PASSWORD: &str = "video.password";
+const FLAT_BEARER: &str = "auth.bearer";
+const FLAT_HEADERS: &str = "auth.headers";
+
+const REMOTE_API_TOKEN: &str = "remote_api.token";
+
 #[derive(Debug, Default, Clone)]
 pub struct AuthSecrets {
   pub username: Option<String>,
@@ -34,56 +46,160 @@ impl StrongholdState {
       init_error: Mutex::new(None),
     }
   }
-  pub fn load_auth_secrets(&self) -> Result<AuthSecrets, String> {
-    let (username, password, video_password, bearer_token, headers) = {
-      let guard = self
-        .inner
-        .lock()
-        .map_err(|_| "failed to lock stronghold".to_string())?;
-      let sh = guard.as_ref().ok_or_else(|| "vault locked".to_string())?;
-      let store = sh.store();
-
-      let get = |key: &str| -> Result<Option<String>, String> {
-        match store.get(key.as_bytes()).map_err(|e| e.to_string())? {
-          Some(bytes) if !bytes.is_empty() => {
-            let s = String::from_utf8(bytes).map_err(|e| e.to_string())?;
-            let t = s.trim();
-            if t.is_empty() {
-              Ok(None)
-            } else {
-              Ok(Some(t.to_string()))
-            }
-          }
-          _ => Ok(None),
-        }
-      };
-
-      let username = get("auth.username")?;
-      let password = get("auth.password")?;
-      let video_password = get("video.password")?;
-      let bearer_token = get("auth.bearer")?;
-
-      let mut headers: Vec<String> = Vec::new();
-      if let Some(hblob) = get("auth.headers")? {
-        for line in hblob.lines() {
-          let line = line.trim();
-          if line.is_empty() || !line.contains(':') {
-            continue;
-          }
-          headers.push(line.to_string());
-        }
-      }
+  /// Secrets of the most specific profile bound to the URL or extractor, `None` when no profile
+  /// applies.
+  pub fn load_auth_secrets(
+    &self,
+    url: &str,
+    extractor: Option<&str>,
+  ) -> Result<Option<AuthSecrets>, String> {
+    let profiles = self.load_profiles()?;
+    Ok(resolve_profile(&profiles, url, extractor).map(CredentialProfile::secrets))
+  }
+
+  pub fn load_profiles(&self) -> Result<Vec<CredentialProfile>, String> {
+    match self.read_value(PROFILES_KEY)? {
+      Some(json) => serde_json::from_str(&json).map_err(|e| format!("invalid profiles: {e}")),
+      None => Ok(Vec::new()),
+    }
+  }
 
-      (username, password, video_password, bearer_token, headers)
-    };
+  pub fn save_profiles(&self, profiles: &[CredentialProfile]) -> Result<(), String> {
+    let json = serde_json::to_vec(profiles).map_err(|e| e.to_string())?;
+    self.write_values(vec![(PROFILES_KEY, Some(json))])
+  }
+
+  /// Token the local API expects, created on first use.
+  pub fn remote_api_token(&self) -> Result<String, String> {
+    match self.read_value(REMOTE_API_TOKEN)? {
+      Some(token) => Ok(token),
+      None => self.rotate_remote_api_token(),
+    }
+  }
+
+  /// Replaces the local API token, clients using the old one are rejected from now on.
+  pub fn rotate_remote_api_token(&self) -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+    self.write_values(vec![(REMOTE_API_TOKEN, Some(token.clone().into_bytes()))])?;
+    Ok(token)
+  }
 
-    Ok(AuthSecrets {
+  /// Moves the old global `auth.*` keys into the migrated profile. It is not bound to any site,
+  /// so the secrets stay unused until the user picks where they belong on the settings page.
+  pub fn migrate_flat_credentials(&self) -> Result<(), String> {
+    let username = self.read_value(FLAT_USERNAME)?;
+    let password = self.read_value(FLAT_PASSWORD)?;
+    let video_password = self.read_value(FLAT_VIDEO_PASSWORD)?;
+    let bearer_token = self.read_value(FLAT_BEARER)?;
+    let headers: Vec<String> = self
+      .read_value(FLAT_HEADERS)?
+      .map(|blob| blob.lines().map(str::to_string).collect())
+      .unwrap_or_default();
+
+    if username.is_none()
+      && password.is_none()
+      && video_password.is_none()
+      && bearer_token.is_none()
+      && headers.is_empty()
+    {
+      return Ok(());
+    }
+
+    let migrated = CredentialProfile {
+      id: MIGRATED_PROFILE_ID.into(),
+      name: "Migrated credentials".into(),
+      domains: Vec::new(),
+      extractors: Vec::new(),
       username,
       password,
       video_password,
       bearer_token,
       headers,
-    })
+    }
+    .normalize()?;
+
+    let mut profiles = self.load_profiles()?;
+    match profiles.iter_mut().find(|p| p.id == MIGRATED_PROFILE_ID) {
+      Some(existing) => {
+        // Keep the sites the user bound the profile to and only refresh the secrets.
+        let bindings = (
+          std::mem::take(&mut existing.domains),
+          std::mem::take(&mut existing.extractors),
+        );
+        *existing = migrated;
+        (existing.domains, existing.extractors) = bindings;
+      }
+      None => profiles.push(migrated),
+    }
+
+    let json = serde_json::to_vec(&profiles).map_err(|e| e.to_string())?;
+    self.write_values(vec![
+      (PROFILES_KEY, Some(json)),
+      (FLAT_USERNAME, None),
+      (FLAT_PASSWORD, None),
+      (FLAT_VIDEO_PASSWORD, None),
+      (FLAT_BEARER, None),
+      (FLAT_HEADERS, None),
+    ])
+  }
+
+  fn read_value(&self, key: &str) -> Result<Option<String>, String> {
+    let guard = self
+      .inner
+      .lock()
+      .map_err(|_| "failed to lock stronghold".to_string())?;
+    let sh = guard.as_ref().ok_or_else(|| "vault locked".to_string())?;
+    let client = sh
+      .get_client(CLIENT)
+      .map_err(|e| format!("get_client failed: {e}"))?;
+
+    match client
+      .store()
+      .get(key.as_bytes())
+      .map_err(|e| e.to_string())?
+    {
+      Some(bytes) if !bytes.is_empty() => {
+        let s = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        let t = s.trim();
+        if t.is_empty() {
+          Ok(None)
+        } else {
+          Ok(Some(t.to_string()))
+        }
+      }
+      _ => Ok(None),
+    }
+  }
+
+  fn write_values(&self, entries: Vec<(&str, Option<Vec<u8>>)>) -> Result<(), String> {
+    let guard = self
+      .inner
+      .lock()
+      .map_err(|_| "failed to lock stronghold".to_string())?;
+    let sh = guard.as_ref().ok_or_else(|| "vault locked".to_string())?;
+    let client = sh
+      .get_client(CLIENT)
+      .map_err(|e| format!("get_client failed: {e}"))?;
+    let store = client.store();
+
+    for (key, value) in entries {
+      match value {
+        Some(value) => {
+          store
+            .insert(key.as_bytes().to_vec(), value, None)
+            .map_err(|e| e.to_string())?;
+        }
+        None => {
+          store.delete(key.as_bytes()).map_err(|e| e.to_string())?;
+        }
+      }
+    }
+
+    sh.write_client(CLIENT)
+      .map_err(|e| format!("write_client failed: {e}"))?;
+    sh.save().map_err(|e| e.to_string())
   }
 }
 
@@ -106,6 +222,9 @@ fn open_with_key(state: &StrongholdState, key: &[u8]) -> Result<(), String> {
   }
 
   *state.inner.lock().unwrap() = Some(sh);
+  if let Err(e) = state.migrate_flat_credentials() {
+    tracing::warn!("Failed to migrate credentials to profiles: {e}");
+  }
   Ok(())
 }
 
@@ -113,12 +232,14 @@ fn create_new_stronghold(state: &StrongholdState, key: &[u8]) -> Result<(), Stri
   open_with_key(state, key)
 }
 
-pub fn init(app: &AppHandle, state: &State<StrongholdState>) -> Result<(), String> {
-  create_and_store_new(app, state)
+pub fn init<R: Runtime>(app: &AppHandle<R>, state: &State<StrongholdState>) -> Result<(), String> {
+  create_and_store_new(app, state)?;
+  share_remote_api_token(app, state);
+  Ok(())
 }
 
 #[allow(dead_code)]
-pub fn init_on_startup(app: &AppHandle, state: &State<StrongholdState>) {
+pub fn init_on_startup<R: Runtime>(app: &AppHandle<R>, state: &State<StrongholdState>) {
   if !state.snapshot_path.exists() {
     return;
   }
@@ -152,9 +273,20 @@ pub fn init_on_startup(app: &AppHandle, state: &State<StrongholdState>) {
       *state.init_error.lock().unwrap() = Some(format!("Secure keyring unavailable: {e}"));
     }
   }
+  share_remote_api_token(app, state);
+}
+
+/// Hands the local API its token once the vault is open.
+fn share_remote_api_token<R: Runtime>(app: &AppHandle<R>, state: &StrongholdState) {
+  if let Some(server) = app.try_state::<RemoteApiServer>() {
+    server.load_token(state);
+  }
 }
 
-fn create_and_store_new(app: &AppHandle, state: &State<StrongholdState>) -> Result<(), String> {
+fn create_and_store_new<R: Runtime>(
+  app: &AppHandle<R>,
+  state: &State<StrongholdState>,
+) -> Result<(), String> {
   let key = generate_master_key();
   create_new_stronghold(state, &key)?;
   let b64 = general_purpose::STANDARD.encode(key);
@@ -0,0 +1,4 @@
+/** Livestreams are the only items the backend sends a `liveStatus` for. */
+export function isLivestream(media: { liveStatus?: string | null }): boolean {
+  return media.liveStatus != null;
+}
@@ -6,13 +6,14 @@ import { useMediaGroupStore } from './group';
 import { useMediaDestinationStore } from './destination';
 import { useMediaProgressStore } from './progress';
 import { useMediaOptionsStore } from './options';
-import { DownloadOptions, MediaAddPayload, MediaItem, TrackType } from '../../tauri/types/media';
+import { DownloadOptions, MediaAddPayload, MediaFetchPayload, MediaItem, TrackType } from '../../tauri/types/media';
 import { useMediaSizeStore } from './size.ts';
 import { useMediaDiagnosticsStore } from './diagnostics.ts';
 import { useSettingsStore } from '../settings.ts';
 import { Group } from '../../tauri/types/group.ts';
 import { notify, notifyGroup } from '../../tauri/notifications';
 import { NotificationKind } from '../../tauri/types/app';
+import { isLivestream } from '../../helpers/livestream';
 
 export const useMediaStore = defineStore('media', () => {
   const groupStore = useMediaGroupStore();
@@ -81,10 +82,7 @@ export const useMediaStore = defineStore('media', () => {
     stateStore.setState(item.id, next);
   }
 
-  async function dispatchMediaInfoFetch(url: string, fromShortcut: boolean = false) {
-    const id = uuidv4();
-    const groupId = uuidv4();
-
+  function createFetchingGroup(url: string, id: string, groupId: string, fromShortcut: boolean): Group {
     stateStore.setState(id, MediaState.fetching);
     const newGroup: Group = {
       id: groupId,
@@ -109,11 +107,25 @@ export const useMediaStore = defineStore('media', () => {
       },
     };
     groupStore.createGroup(newGroup);
+    return newGroup;
+  }
+
+  async function dispatchMediaInfoFetch(url: string, fromShortcut: boolean = false) {
+    const id = uuidv4();
+    const groupId = uuidv4();
+    const newGroup = createFetchingGroup(url, id, groupId, fromShortcut);
 
     await invoke('media_info', { url, id, groupId });
     await notifyGroup(NotificationKind.QueueAdded, newGroup);
   }
 
+  /** Lists a fetch the backend started on its own, before its results arrive. */
+  function processMediaFetchPayload(payload: MediaFetchPayload) {
+    if (groupStore.findGroupById(payload.groupId)) return;
+    const newGroup = createFetchingGroup(payload.url, payload.id, payload.groupId, false);
+    void notifyGroup(NotificationKind.QueueAdded, newGroup);
+  }
+
   async function downloadGroup(
     groupId: string,
     options: DownloadOptions,
@@ -141,6 +153,8 @@ export const useMediaStore = defineStore('media', () => {
           templateContext: {
             values: buildTemplateContext(item, group),
           },
+          // Empty options record with the backend defaults: from the start, waiting for scheduled streams.
+          live: isLivestream(item) ? {} : undefined,
         })),
       });
     } catch (e) {
@@ -174,6 +188,17 @@ export const useMediaStore = defineStore('media', () => {
     groupStore.cancelGroup(groupId);
   }
 
+  async function stopRecording(groupId: string) {
+    const group = groupStore.findGroupById(groupId);
+    if (!group) return;
+
+    const recordings = Object.values(group.items).filter(isLivestream);
+    await Promise.all(recordings.map(item =>
+      invoke('media_stop_recording', { id: item.id })
+        .catch(e => console.warn(`Could not stop recording ${item.id}: ${e}`)),
+    ));
+  }
+
   function buildTemplateContext(item: MediaItem, group: Group): Record<string, string | undefined> {
     return {
       playlist_index: item.playlistIndex?.toString(),
@@ -254,10 +279,12 @@ export const useMediaStore = defineStore('media', () => {
     processMediaAddPayload,
     finalizePlaylistGroup,
     dispatchMediaInfoFetch,
+    processMediaFetchPayload,
     downloadGroup,
     downloadAllGroups,
     pauseAllGroups,
     pauseGroup,
+    stopRecording,
     resumeAllGroups,
     deleteGroup,
     deleteAllGroups,
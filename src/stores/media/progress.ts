@@ -19,6 +19,8 @@ export type MediaGroupProgress = MediaGroupProgressPayload & MediaProgressStageP
 
 export const useMediaProgressStore = defineStore('media-progress', () => {
   const progress = ref<Record<string, MediaProgress>>({});
+  // Items yt-dlp skipped because they are already in the download archive.
+  const skipped = ref<Record<string, boolean>>({});
   const groupStore = useMediaGroupStore();
   const stateStore = useMediaStateStore();
   const recentGroupSpeedBps = new Map<string, { speedBps: number; expiresAt: number }>();
@@ -69,6 +71,18 @@ export const useMediaProgressStore = defineStore('media-progress', () => {
     }
   }
 
+  function processMediaSkippedPayload(payload: MediaProgressCompletePayload) {
+    skipped.value[payload.id] = true;
+    processMediaCompletePayload(payload);
+  }
+
+  function isGroupSkipped(groupId: string): boolean {
+    const group = groupStore.findGroupById(groupId);
+    if (!group) return false;
+    const items = Object.values(group.items).filter(item => !group.isCombined || !item.isLeader);
+    return items.length > 0 && items.every(item => skipped.value[item.id]);
+  }
+
   function findGroupProgress(groupId: string): MediaGroupProgress | undefined {
     const group = groupStore.findGroupById(groupId);
     if (!group) return;
@@ -166,6 +180,7 @@ export const useMediaProgressStore = defineStore('media-progress', () => {
 
   function deleteProgress(id: string) {
     delete progress.value[id];
+    delete skipped.value[id];
   }
 
   return {
@@ -173,6 +188,8 @@ export const useMediaProgressStore = defineStore('media-progress', () => {
     processMediaProgressPayload,
     processMediaProgressStagePayload,
     processMediaCompletePayload,
+    processMediaSkippedPayload,
+    isGroupSkipped,
     findGroupProgress,
     findDownloadProgress,
     findAllProgress,
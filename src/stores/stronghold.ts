@@ -13,32 +13,44 @@ const defaultStrongholdStatus: StrongholdStatus = {
   unlocked: false,
 };
 
-export const STRONGHOLD_KEYS = {
-  username: 'auth.username',
-  password: 'auth.password',
-  videoPassword: 'video.password',
-  bearer: 'auth.bearer',
-  headers: 'auth.headers',
-} as const;
-
-export interface StrongholdFields {
+export interface CredentialProfileSummary {
+  id: string;
+  name: string;
+  domains: string[];
+  extractors: string[];
+  username: string | null;
+  hasPassword: boolean;
+  hasVideoPassword: boolean;
+  hasBearerToken: boolean;
+  headerCount: number;
+  bound: boolean;
+}
+
+/**
+ * Secrets left `null` keep their stored value, an empty value removes them.
+ */
+export interface CredentialProfileUpdate {
+  id: string;
+  name: string;
+  domains: string[];
+  extractors: string[];
   username: string | null;
   password: string | null;
   videoPassword: string | null;
-  bearer: string | null;
-  headers: string | null;
+  bearerToken: string | null;
+  headers: string[] | null;
 }
 
 export const useStrongholdStore = defineStore('stronghold', () => {
   const status = ref<StrongholdStatus>(defaultStrongholdStatus);
-  const availableKeys = ref<number[][]>([]);
+  const profiles = ref<CredentialProfileSummary[]>([]);
 
   async function loadStatus(): Promise<StrongholdStatus> {
     const strongholdStatus: StrongholdInitPayload = await invoke('stronghold_status');
     if (strongholdStatus.unlocked && !strongholdStatus.initError) {
       status.value.unlocked = true;
 
-      availableKeys.value = await invoke('stronghold_keys');
+      await loadProfiles();
     } else if (!strongholdStatus.unlocked && !strongholdStatus.initError) {
       status.value.unlocked = false;
     } else {
@@ -58,44 +70,45 @@ export const useStrongholdStore = defineStore('stronghold', () => {
     }
     status.value.unlocked = initStatus.unlocked;
 
-    availableKeys.value = await invoke('stronghold_keys');
+    if (initStatus.unlocked) {
+      await loadProfiles();
+    }
     return status.value;
   }
 
-  async function getValues(): Promise<StrongholdFields> {
-    const keys = Object.values(STRONGHOLD_KEYS);
-    const entries = await invoke<Record<string, number[] | null>>('stronghold_get', { keys });
-    const decoder = new TextDecoder();
-
-    const result = {} as StrongholdFields;
-    for (const [field, path] of Object.entries(STRONGHOLD_KEYS)) {
-      const raw = entries[path];
-      if (raw == null) {
-        result[field as keyof StrongholdFields] = null;
-      } else {
-        result[field as keyof StrongholdFields] = decoder.decode(new Uint8Array(raw));
-      }
-    }
-
-    return result;
+  async function loadProfiles(): Promise<CredentialProfileSummary[]> {
+    profiles.value = await invoke<CredentialProfileSummary[]>('credentials_list');
+    return profiles.value;
   }
 
-  async function setValues(fields: StrongholdFields): Promise<void> {
-    const encoder = new TextEncoder();
-    const converted: Record<string, number[] | null> = {};
+  async function saveProfile(profile: CredentialProfileUpdate): Promise<CredentialProfileSummary> {
+    const saved = await invoke<CredentialProfileSummary>('credentials_create', { profile });
+    await loadProfiles();
+    return saved;
+  }
 
-    for (const [field, path] of Object.entries(STRONGHOLD_KEYS)) {
-      const value = fields[field as keyof StrongholdFields];
-      converted[path] = value == null ? null : Array.from(encoder.encode(value));
-    }
+  async function deleteProfile(id: string): Promise<void> {
+    await invoke('credentials_delete', { id });
+    await loadProfiles();
+  }
 
-    await invoke('stronghold_set', { entries: converted });
-    availableKeys.value = await invoke<number[][]>('stronghold_keys');
+  function hasProfiles(): boolean {
+    return profiles.value.length > 0;
   }
 
-  function hasAvailableKeys(): boolean {
-    return availableKeys.value.length > 0;
+  function hasUnboundProfiles(): boolean {
+    return profiles.value.some(profile => !profile.bound);
   }
 
-  return { status, availableKeys, hasAvailableKeys, loadStatus, initialize, getValues, setValues };
+  return {
+    status,
+    profiles,
+    hasProfiles,
+    hasUnboundProfiles,
+    loadStatus,
+    initialize,
+    loadProfiles,
+    saveProfile,
+    deleteProfile,
+  };
 });
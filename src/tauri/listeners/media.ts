@@ -1,12 +1,16 @@
 import { listen } from '@tauri-apps/api/event';
 import { useMediaStore } from '../../stores/media/media';
 import { useMediaSizeStore } from '../../stores/media/size';
-import { MediaAddPayload, MediaAddWithFormatPayload } from '../types/media';
+import { MediaAddPayload, MediaAddWithFormatPayload, MediaFetchPayload } from '../types/media';
 
 export function registerMediaListeners() {
   const mediaStore = useMediaStore();
   const sizeStore = useMediaSizeStore();
 
+  void listen<MediaFetchPayload>('media_fetch', (event) => {
+    mediaStore.processMediaFetchPayload(event.payload);
+  });
+
   void listen<MediaAddPayload>('media_add', (event) => {
     mediaStore.processMediaAddPayload(event.payload);
     sizeStore.processMediaAddPayload(event.payload);
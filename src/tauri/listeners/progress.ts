@@ -16,4 +16,8 @@ export function registerProgressListeners() {
   void listen<MediaProgressCompletePayload>('media_complete', (event) => {
     progressStore.processMediaCompletePayload(event.payload);
   });
+
+  void listen<MediaProgressCompletePayload>('media_skipped', (event) => {
+    progressStore.processMediaSkippedPayload(event.payload);
+  });
 }
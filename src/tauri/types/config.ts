@@ -105,6 +105,11 @@ export interface SystemSettings {
   closeBehavior: CloseBehavior;
 }
 
+export interface RemoteApiSettings {
+  enabled: boolean;
+  port: number;
+}
+
 export enum NotificationBehavior {
   Always = 'always',
   OnBackground = 'onBackground',
@@ -128,6 +133,7 @@ export interface Settings {
   update: UpdateSettings;
   system: SystemSettings;
   notifications: NotificationSettings;
+  remoteApi: RemoteApiSettings;
 }
 
 export const defaultAppearanceSettings: AppearanceSettings = {
@@ -206,6 +212,11 @@ export const defaultNotificationSettings: NotificationSettings = {
   disabledNotifications: [],
 };
 
+export const defaultRemoteApiSettings: RemoteApiSettings = {
+  enabled: false,
+  port: 17878,
+};
+
 export const defaultSettings: Settings = {
   appearance: defaultAppearanceSettings,
   auth: defaultAuthSettings,
@@ -226,4 +237,5 @@ export const defaultSettings: Settings = {
   update: defaultUpdateSettings,
   system: defaultSystemSettings,
   notifications: defaultNotificationSettings,
+  remoteApi: defaultRemoteApiSettings,
 };
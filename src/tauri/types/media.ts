@@ -36,12 +36,28 @@ export type DownloadOptions = {
   audioCodec?: string;
 };
 
+export type LiveRecordingOptions = {
+  /** Record from the start of the stream instead of from the moment the download starts. */
+  fromStart?: boolean;
+  /** Wait for scheduled streams, retrying every this many seconds. */
+  waitForVideoSecs?: number | null;
+  /** Stops the recording cleanly after this many seconds. */
+  maxDurationSecs?: number | null;
+};
+
 export interface MediaAddPayload {
   groupId: string;
   total: number;
   item: MediaItem;
 }
 
+/** A fetch started outside the UI, by a launch, a watch folder or the local API. */
+export interface MediaFetchPayload {
+  groupId: string;
+  id: string;
+  url: string;
+}
+
 export type MediaAddWithFormatPayload = MediaAddPayload & {
   format: DownloadOptions;
 };
@@ -70,6 +86,10 @@ export interface MediaItem {
   playlistId?: string;
   playlistCount?: number;
   playlistIndex?: number;
+  /** yt-dlp's `live_status`, only set for livestreams, e.g. `is_live` or `is_upcoming`. */
+  liveStatus?: string;
+  /** Unix time in seconds at which an upcoming stream is scheduled to start. */
+  releaseTimestamp?: number;
 }
 
 export interface EntryItem {
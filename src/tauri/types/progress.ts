@@ -55,6 +55,8 @@ export enum ProgressStage {
   downloading = 'downloading',
   merging = 'merging',
   finalizing = 'finalizing',
+  paused = 'paused',
+  stopping = 'stopping',
 }
 
 export enum ProgressStyle {
@@ -45,14 +45,32 @@ test.describe('when it has an initialization failure', () => {
 });
 
 test.describe('when authentication is initialized', () => {
-  const headersValue = 'TEST:1';
-
   test.use({
     mockData: [{
       stronghold: {
-        fields: {
-          'auth.headers': headersValue,
-        },
+        profiles: [{
+          id: 'vimeo',
+          name: 'Vimeo',
+          domains: ['vimeo.com'],
+          extractors: [],
+          username: 'user',
+          hasPassword: true,
+          hasVideoPassword: false,
+          hasBearerToken: false,
+          headerCount: 1,
+          bound: true,
+        }, {
+          id: 'migrated',
+          name: 'Migrated credentials',
+          domains: [],
+          extractors: [],
+          username: null,
+          hasPassword: false,
+          hasVideoPassword: false,
+          hasBearerToken: true,
+          headerCount: 0,
+          bound: false,
+        }],
         status: {
           unlocked: true,
           initError: undefined,
@@ -60,35 +78,36 @@ test.describe('when authentication is initialized', () => {
       },
     }, { scope: 'test' }],
   });
-  test('it opens the advanced section when a value exists', async ({ page }) => {
+  test('it opens the advanced section when a profile exists', async ({ page }) => {
     const authDetails = await getAuthDetails(page, false);
     await expect(authDetails).toHaveAttribute('open');
   });
-  test('it enables the save button upon changes', async ({ page }) => {
-    const authDetails = await getAuthDetails(page, false);
-    const bearerInput = authDetails.getByRole('textbox', { name: 'Token' });
-    await bearerInput.fill('test2');
-    const saveButton = page.getByRole('button', { name: 'Save' });
-    await expect(saveButton).toBeEnabled();
+  test('it warns about profiles that are not bound to any site', async ({ page }) => {
+    await getAuthDetails(page, false);
+    const migrated = page.getByTestId('credential-profile-migrated');
+    await expect(migrated.getByRole('alert')).toBeVisible();
+    await expect(page.getByTestId('credential-profile-vimeo').getByRole('alert')).not.toBeVisible();
   });
   test('it allows cookies to be imported from a browser', async ({ page }) => {
     await getAuthDetails(page, false);
     const browserInput = page.getByRole('combobox', { name: 'Browser' });
     await browserInput.selectOption('Firefox');
-    const saveButton = page.getByRole('button', { name: 'Save' });
+    const saveButton = page.getByRole('button', { name: 'Save' }).first();
     await expect(saveButton).toBeEnabled();
     await saveButton.click();
     // After saving, there should be no more changes.
     await expect(saveButton).toBeDisabled();
   });
-  test('it allows authentication secrets to be managed', async ({ page }) => {
-    const authDetails = await getAuthDetails(page, false);
-    const headerInput = authDetails.getByRole('textbox', { name: 'Headers' });
-    await expect(headerInput).toHaveValue(headersValue);
-    const bearerInput = authDetails.getByRole('textbox', { name: 'Token' });
+  test('it allows a credential profile to be managed', async ({ page }) => {
+    await getAuthDetails(page, false);
+    const profile = page.getByTestId('credential-profile-vimeo');
+    const saveButton = profile.getByRole('button', { name: 'Save' });
+    await expect(saveButton).toBeDisabled();
+    const bearerInput = profile.getByRole('textbox', { name: 'Token' });
+    // Stored secrets are never sent back to the page.
+    await expect(profile.getByRole('textbox', { name: 'Password' }).first()).toHaveValue('');
     const testValue = 'test2';
     await bearerInput.fill(testValue);
-    const saveButton = page.getByRole('button', { name: 'Save' });
     await expect(saveButton).toBeEnabled();
     // Verify keyboard accessibility of BaseSecretInput
     await expect(bearerInput).toHaveAttribute('type', 'password');
@@ -104,7 +123,6 @@ test.describe('when authentication is initialized', () => {
     await expect(saveButton).toBeDisabled();
     await bearerInput.fill(testValue);
     await saveButton.click();
-    // After saving, there should be no more changes.
-    await expect(saveButton).toBeDisabled();
+    await expect(page.getByText('Authentication settings saved!')).toBeVisible();
   });
 });
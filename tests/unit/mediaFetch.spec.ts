@@ -0,0 +1,37 @@
+import { describe, it, expect } from 'vitest';
+import { useMediaStore } from '../../src/stores/media/media';
+import { useMediaGroupStore } from '../../src/stores/media/group';
+import { MediaState, useMediaStateStore } from '../../src/stores/media/state';
+
+describe('media fetches started by the backend', () => {
+  it('lists the group so the fetched media is not orphaned', () => {
+    const mediaStore = useMediaStore();
+    const groupStore = useMediaGroupStore();
+    const stateStore = useMediaStateStore();
+    const url = 'https://example.com/video';
+
+    mediaStore.processMediaFetchPayload({ groupId: 'g1', id: 'i1', url });
+
+    const group = groupStore.findGroupById('g1');
+    expect(group.url).toBe(url);
+    expect(stateStore.getState('i1')).toBe(MediaState.fetching);
+
+    expect(() => mediaStore.processMediaAddPayload({
+      groupId: 'g1',
+      total: 1,
+      item: { id: 'i2', url, audioCodecs: [], formats: [], filesize: 0, title: 'Video' },
+    })).not.toThrow();
+    expect(group.title).toBe('Video');
+  });
+
+  it('does not replace a group that already exists', () => {
+    const mediaStore = useMediaStore();
+    const groupStore = useMediaGroupStore();
+
+    mediaStore.processMediaFetchPayload({ groupId: 'g1', id: 'i1', url: 'https://example.com/a' });
+    mediaStore.processMediaFetchPayload({ groupId: 'g1', id: 'i2', url: 'https://example.com/b' });
+
+    expect(groupStore.groupOrder).toEqual(['g1']);
+    expect(groupStore.findGroupById('g1').url).toBe('https://example.com/a');
+  });
+});
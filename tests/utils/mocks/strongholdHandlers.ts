@@ -1,9 +1,13 @@
 import { ensureInvokeArgsObject, IPCHandler } from '../tauriMock';
-import { StrongholdFields, StrongholdInitPayload } from '../../../src/stores/stronghold';
+import {
+  CredentialProfileSummary,
+  CredentialProfileUpdate,
+  StrongholdInitPayload,
+} from '../../../src/stores/stronghold';
 import { InvokeArgs } from '@tauri-apps/api/core';
 
 export type StrongholdMockData = {
-  fields?: Record<string, string | null>;
+  profiles?: CredentialProfileSummary[];
   status?: StrongholdInitPayload;
 };
 
@@ -18,29 +22,25 @@ export const strongholdHandlers: Record<string, IPCHandler> = {
       unlocked: true,
     };
   },
-  stronghold_keys: (): number[][] => {
-    const fields: StrongholdFields = window.E2E.stronghold?.fields ?? {};
-    const encoder = new TextEncoder();
-
-    const keys = Object.keys(fields);
-
-    return keys.map(key => Array.from(encoder.encode(key)));
+  credentials_list: (): CredentialProfileSummary[] => {
+    return window.E2E.stronghold?.profiles ?? [];
   },
-  stronghold_get: (): Record<string, number[] | null> => {
-    const fields: StrongholdFields = window.E2E.stronghold?.fields ?? {};
-    const encoder = new TextEncoder();
-
-    const result: Record<string, number[] | null> = {};
-    for (const [key, value] of Object.entries(fields)) {
-      if (value == null) {
-        result[key] = null;
-      } else {
-        result[key] = Array.from(encoder.encode(value));
-      }
-    }
-    return result;
+  credentials_create: (_cmd, args: InvokeArgs | undefined): CredentialProfileSummary => {
+    const { profile } = ensureInvokeArgsObject<{ profile: CredentialProfileUpdate }>(args, 'credentials_create');
+    return {
+      id: profile.id || 'new',
+      name: profile.name,
+      domains: profile.domains,
+      extractors: profile.extractors,
+      username: profile.username || null,
+      hasPassword: !!profile.password,
+      hasVideoPassword: !!profile.videoPassword,
+      hasBearerToken: !!profile.bearerToken,
+      headerCount: profile.headers?.length ?? 0,
+      bound: profile.domains.length > 0 || profile.extractors.length > 0,
+    };
   },
-  stronghold_set: (_cmd, args: InvokeArgs | undefined): void => {
-    ensureInvokeArgsObject<Record<string, number[] | null>>(args, 'stronghold_set');
+  credentials_delete: (_cmd, args: InvokeArgs | undefined): void => {
+    ensureInvokeArgsObject<{ id: string }>(args, 'credentials_delete');
   },
 };